@@ -1 +1,415 @@
 airplay = AirPlay
+receiver-name = Receiver name
+auto-rename = Rename when the name is taken
+auto-rename-description = Add the computer name when another receiver on the network already uses this name, instead of the "(2)" that senders would show.
+name-taken = ⚠ { $host } already uses this name
+name-taken-description = Senders show this receiver with a "(2)" added, or mix the two up.
+name-taken-rename = Rename to { $name }
+wake-on-demand = Wake on demand
+wake-on-demand-description = Advertise the receiver without running UXPlay, and only start it when a device connects.
+performance-profile = Performance power profile while streaming
+performance-profile-description = Avoids dropped frames from the CPU slowing down, and switches back when the device leaves. Always on in game mode.
+quality = Quality
+quality-low = Low
+quality-balanced = Balanced
+quality-high = High
+quality-custom = Custom
+concurrent-clients = When another device connects
+concurrent-reject = Turn it away
+concurrent-allow = Let it take over
+concurrent-prompt = Ask
+session-limit = Session time limit (minutes)
+session-limit-description = Ends a session after this long, with a warning a minute before. For shared screens.
+session-limit-none = No limit
+session-left = Time left
+session-pin = PIN to enter on the device
+session-ending = Session ending
+session-ending-body = The screen will be handed back in a minute.
+notifications = Notify when devices connect and leave
+weekly-summary = Weekly summary
+weekly-summary-description = On Monday mornings, notify how long was streamed the week before and from which devices.
+weekly-summary-title = Last week on AirPlay
+weekly-summary-body = { $total } streamed. Most from: { $devices }
+sounds = Play sounds when devices connect and leave
+sounds-description = A sound theme name such as "device-added", or the path of a sound file.
+connect-sound = Connect sound
+disconnect-sound = Disconnect sound
+grace-period = Reconnect grace period (seconds)
+grace-period-description = A device that drops out and comes back within this time, e.g. after its screen locked, keeps its session without notifications.
+notification-connected = { $name } connected
+notification-disconnected = { $name } disconnected
+notification-connected-repeated = { $name } connected ({ $count } times in the last minute)
+notification-disconnected-repeated = { $name } disconnected ({ $count } times in the last minute)
+notification-waiting = { $name } wants to stream
+notification-waiting-body = Another device is streaming right now.
+notification-take-over = Let it take over
+notification-show-log = Show details
+notification-restart = Restart
+notification-stop = Stop receiver
+notification-block = Block device
+low-latency = Game mode
+low-latency-description = Lowest latency at the cost of smoothness, with the performance power profile during sessions. Applies when AirPlay is next turned on.
+bluetooth-sink = Bluetooth audio
+bluetooth-sink-description = Let phones play audio over Bluetooth
+status = Status
+status-stopped = Off
+status-starting = Starting…
+status-announcing = Announcing on the network…
+status-waiting = Waiting for a device
+status-streaming = { $clients ->
+    [one] One device connected
+   *[other] { $clients } devices connected
+}
+status-stopping = Stopping…
+status-standby = Starts when a device connects
+client-battery = Battery { $charge }%
+client-battery-charging = Battery { $charge }%, charging
+
+## Errors
+error-binary-not-found = { $binary } is not installed
+error-spawn-failed = Could not start { $binary }: { $reason }
+error-process-exited = The receiver stopped unexpectedly
+error-process-exited-code = The receiver stopped unexpectedly (exit code { $code })
+error-audio = Audio setup failed: { $reason }
+error-port-in-use = { $protocol } port { $port } is used by another program
+error-port-in-use-by = { $protocol } port { $port } is used by { $process }
+error-port-in-use-by-user = { $protocol } port { $port } is used by { $user }, probably their AirTray in another login session. Only one receiver can run on this computer at a time: stop theirs first, or have both use "Only while on screen".
+error-history = Session history is unavailable: { $reason }
+error-config = Settings could not be saved: { $reason }
+error-dbus = A system service did not respond: { $reason }
+error-control = Could not control the sender: { $reason }
+error-debug-bundle = Could not save the debug bundle: { $reason }
+error-uxplayrc = UXPlay's startup file failed: { $reason }
+error-webhook = The webhook failed: { $reason }
+error-identity = The receiver keys failed: { $reason }
+error-token = No access token could be made, so the endpoint stays off: { $reason }
+
+## Profiles
+profiles = Profiles
+profile = Profile
+profile-none = No profile
+profile-new = Profile { $number }
+profile-name = Name
+profile-pin = PIN
+profile-pin-none = No PIN
+profile-resolution = Maximum resolution
+profile-fps = Maximum frame rate
+profile-remove = Remove profile
+profile-imported = From uxplayrc
+pipeline-warning = ⚠ For experts: GStreamer sinks given to UXPlay as they are, replacing the monitor, window, audio output and Snapcast settings. If the mirror or sound breaks, you're on your own; clear them to go back.
+video-pipeline = Video sink pipeline
+audio-pipeline = Audio sink pipeline
+pipeline-missing = ⚠ Not installed: { $elements }
+networks = Networks
+networks-enabled = Switch profiles by network
+networks-description = Turn on with the matching profile on known networks, and stay off elsewhere.
+networks-use-profile = Use selected profile here
+networks-rule = { $network }: { $profile }
+
+## Schedule
+schedule = Schedule
+schedule-enabled = Available only during set hours
+schedule-description = Turns the receiver on and off automatically. A window ending before it starts runs past midnight.
+schedule-start = From
+schedule-end = Until
+schedule-on-at = Turns on { $time }
+schedule-off-at = Turns off { $time }
+schedule-never = No upcoming hours
+day-mon = Mo
+day-tue = Tu
+day-wed = We
+day-thu = Th
+day-fri = Fr
+day-sat = Sa
+day-sun = Su
+
+## Audio
+audio = Audio
+audio-level = Audio level
+poor-connection = ⚠ Poor Wi-Fi connection detected
+nickname = Nickname
+stream-volume = Volume
+pause-local-media = Pause other media when a device connects
+resume-local-media = Resume it when the session ends
+equalizer = Equalizer
+equalizer-description = Adjust bass and treble, e.g. for thin TV speakers. Saved with the selected profile and applies when AirPlay is next turned on. Not used with Snapcast.
+equalizer-bass = Bass
+equalizer-treble = Treble
+loudness = Loudness
+loudness-description = Boost bass and treble further, which helps at low volume.
+recording = Record audio
+recording-description = Save music and other audio-only streams to files named after the track.
+recording-format = Format
+recording-dir = Save to
+output-sink = Play on
+output-sink-description = Moves the sound back to this output when it is plugged in again during a session.
+output-sink-default = Default output
+output-sink-missing = { $name } (unplugged)
+sink-delay = Output delay (ms)
+sink-delay-description = How late each output plays, e.g. HDMI soundbars. The video is held back to match, by up to 999 ms, except in game mode. Applies when AirPlay is next turned on.
+duplicate-audio = Duplicate audio to a second output
+secondary-sink = Second output
+snapcast = Multi-room audio (Snapcast)
+snapcast-host = Snapcast server
+snapcast-port = Port
+snapcast-status = Status
+snapcast-reachable = Connected
+snapcast-unreachable = Server not reachable
+
+## Pages
+back = Back
+settings = Settings
+duration-hours = { $hours } h { $minutes } min
+duration-minutes = { $minutes } min
+
+## Devices
+devices = Devices
+devices-description = Blocking a device takes effect when AirPlay is next turned on. A device with its own profile gets reconnected with it. A priority device disconnects anyone without priority.
+devices-none = No devices have connected yet
+device-last-seen = Last seen { $time }
+device-allowed = Allowed
+device-priority = Priority
+device-hours = Only at certain hours
+device-hours-description = Outside these hours the device is disconnected and refused.
+device-profile-selected = Selected profile
+
+## Statistics
+statistics = Statistics
+stats-this-week = Streamed this week
+stats-sessions = Sessions
+stats-average = Average session
+stats-top-device = Most frequent device
+stats-top-device-value = { $name } ({ $count } sessions)
+stats-none = —
+stats-recent = Recent sessions
+stats-recent-description = Data use is estimated from all network traffic during a session, so downloads meanwhile count too.
+stats-recent-session = { $start }, { $duration }
+history-export = Export all sessions
+history-export-description = Saves device, start, end, duration and data use of every session to the Downloads folder, e.g. for room usage reports.
+history-exported = Saved to { $path }
+data-mb = { $megabytes } MB
+data-gb = { $gigabytes } GB
+stream-health = Current stream, last 30 seconds
+stream-health-dropped = Dropped frame warnings
+stream-health-resent = Resent packets
+stream-audio-codec = Audio format
+stream-audio-codec-value = { $codec }, decoded here
+usage = Receiver resource use
+usage-cpu = CPU
+usage-cpu-value = { $percent }% of a core
+usage-memory = Memory
+usage-memory-value = { $mib } MiB
+
+## Video
+video = Video
+output = Monitor
+output-description = Show the mirror fullscreen on this monitor.
+output-automatic = Automatic
+gpu = GPU
+gpu-description = Graphics card that decodes and shows the mirror, on laptops with more than one. Applies when AirPlay is next turned on.
+gpu-default = Default
+display-server = Display server
+display-server-description = Picks the video output and how to go fullscreen. Change it if the mirror window doesn't show up.
+display-server-automatic = Automatic ({ $detected })
+codec = Codec
+codec-description = H.265 gives newer iPhones better quality at the same bitrate. Applies to the selected profile.
+codec-h265-unavailable = ⚠ GStreamer has no H.265 decoder installed, so H.264 is used instead.
+max-bitrate = Maximum bitrate (Mbit/s)
+max-bitrate-description = Requests a lower resolution and frame rate so the stream stays under this, to spare busy networks.
+max-bitrate-unlimited = Unlimited
+embedded-mirror = Show in AirTray window
+embedded-mirror-description = Draw the mirror in a window of its own with the frame rate and media controls on top.
+mirror-fps = { $fps } fps
+extended-display = Second display for a Mac (experimental)
+extended-display-description = Request the monitor's native resolution and show fullscreen, so a Mac can pick "Use as Separate Display". Overrides the AirTray window and picture in picture.
+pip = Picture in picture
+pip-description = Show the mirror in a small window that stays on top, instead of a normal window.
+pip-corner = Corner
+pip-size = Size
+signage = Connect instructions
+signage-description = While nobody is connected, show the receiver's name and PIN on the screen, like meeting room signage.
+signage-connect = AirPlay to: { $name }
+signage-pin = PIN: { $pin }
+scale-method = Scaling
+scale-nearest = Nearest neighbour
+scale-bilinear = Bilinear
+integer-scaling = Integer scaling
+integer-scaling-description = Only enlarge the picture by whole multiples, for pixel-perfect low-resolution content. Applies from the next session.
+brightness = Brightness
+picture-description = Applies from the next session.
+contrast = Contrast
+saturation = Saturation
+picture = Picture
+picture-reset = Reset
+pip-small = Small
+pip-medium = Medium
+pip-large = Large
+corner-top-left = Top left
+corner-top-right = Top right
+corner-bottom-left = Bottom left
+corner-bottom-right = Bottom right
+browser-viewer = Browser viewer
+browser-viewer-description = Watch the mirror in a browser at { $url } instead of a local window. Needs the GStreamer Rust plugins.
+
+## Phone
+phone = Phone
+clipboard-sharing = Clipboard sharing
+clipboard-sharing-description = Let the connected phone send text to the desktop clipboard during a session.
+clipboard-sharing-address = During a session, POST text to { $url } (for example from a Shortcut).
+file-receiving = Receive files
+file-receiving-description = Let the connected phone send files by scanning a QR code during a session.
+download-dir = Save files to
+files-received = Received files
+files-session-only = Connect a phone to show the QR code for sending files.
+files-none = No files received yet
+
+## Remote
+remote = Apple TV remote
+remote-device = Apple TV
+remote-scanning = Looking for Apple TVs…
+remote-none = No Apple TVs found
+remote-select = Select
+remote-menu = Menu
+
+## Nearby devices
+nearby = Nearby devices
+nearby-description = Other AirPlay devices announcing themselves on this network. If nothing shows up although devices are around, the network may block multicast, e.g. with client isolation on guest Wi-Fi.
+nearby-scanning = Looking for devices…
+nearby-refresh = Search again
+nearby-receivers = Receivers
+nearby-senders = iPhones, iPads and Macs
+nearby-none = None found
+nearby-address = { $host } at { $address }
+duplicates = Receivers claiming to be this one
+duplicates-description = Either UXPlay still running on another machine with the same settings, or a device impersonating this receiver to capture what is sent to it. Senders can't tell them apart.
+duplicates-notification = Another receiver claims to be this one
+duplicates-show = Show details
+duplicate-name = { $host } uses the same receiver name
+duplicate-id = ⚠ { $host } uses this receiver's device ID
+duplicate-same-name = Same name
+duplicate-same-id = Same device ID
+
+## Integrations
+integrations = Integrations
+kdeconnect = KDE Connect
+kdeconnect-description = Show the battery of connecting phones and ping them. To toggle AirPlay from your phone, add a KDE Connect command running "airtray --toggle".
+kdeconnect-ping = AirPlay mirroring started
+mqtt = MQTT
+mqtt-description = Publish the receiver state to a broker, with Home Assistant discovery.
+mqtt-host = Broker
+mqtt-port = Port
+mqtt-username = Username
+mqtt-password = Password
+rest = Control API
+rest-description = Control the receiver over HTTP, e.g. from a tablet on the wall of a meeting room.
+rest-address = Open { $url } for a control page.
+rest-lan = Allow other devices
+rest-lan-description = Accept requests from the network, not only from this computer.
+webhook = Webhook
+webhook-description = POST the session starting and ending and receiver errors as JSON, e.g. to n8n or Node-RED.
+webhook-url = URL
+invalid-webhook-url = Use an http:// URL with a host, e.g. http://localhost:5678/webhook/airtray. HTTPS is not supported.
+hook-start = Run when started
+hook-start-description = Shell command run when the receiver starts, e.g. to switch the TV to this computer with cec-ctl.
+hook-stop = Run when stopped
+hook-stop-description = Shell command run when the receiver stops.
+hook-client-connect = Run when a device connects
+hook-client-connect-description = Shell command run when a device connects, with its name in $AIRTRAY_CLIENT_NAME and its id in $AIRTRAY_CLIENT_ID.
+hook-client-disconnect = Run when a device leaves
+hook-client-disconnect-description = Shell command run once a device is gone, with the same variables.
+
+## Settings lock
+lock = Settings lock
+lock-method = Lock settings and devices
+lock-description = Others can still turn AirPlay on and off, but need to authenticate to change settings or allow devices.
+lock-none = Not locked
+lock-pin = PIN
+lock-polkit = System password
+random-pin = New PIN for every device
+random-pin-description = Instead of the profile's PIN, devices get a random one to enter each time they connect, shown in the popup and the connect instructions. Guests can't come back later without it.
+kiosk = Kiosk mode
+kiosk-description = For shared machines: AirPlay starts with the applet, the popup only shows the status, and the settings always need authentication, with the system password unless a PIN is set.
+active-seat-only = Only while on screen
+active-seat-only-description = Stops AirPlay when you switch to another user, so their AirTray can take over the ports, and starts it again when you come back.
+locked = Locked
+locked-description = Authenticate to change settings or devices.
+unlock = Unlock
+unlock-failed = Wrong PIN or authentication failed
+
+## Advanced
+advanced = Advanced
+uxplayrc-import = Import UXPlay settings
+uxplayrc-import-description = Takes over the options of ~/.uxplayrc. Resolution, frame rate, PIN and sinks go into a "From uxplayrc" profile.
+uxplayrc-import-button = Import
+uxplayrc-imported = Imported
+uxplayrc-imported-skipped = Imported, except for: { $options }
+uxplayrc-export = Export UXPlay settings
+uxplayrc-export-description = Saves the settings with the selected profile as a uxplayrc to the Downloads folder, to run UXPlay the same way without AirTray.
+uxplayrc-export-button = Export
+uxplayrc-exported = Saved to { $path }. Run it with UXPLAYRC={ $path } uxplay
+keys-backup = Back up receiver keys
+keys-backup-description = Saves the key devices know this receiver by, and the devices that entered the PIN, to the Downloads folder. Restore it after reinstalling so they don't have to pair again.
+keys-backup-button = Back up
+keys-backed-up = Saved to { $path }. Keep it private, it lets others pose as this receiver.
+keys-restore = Restore receiver keys
+keys-restore-description = Takes over the keys from airtray-keys.zip in the Downloads folder.
+keys-restore-button = Restore
+keys-restored = Restored
+
+## Invalid settings
+invalid-resolution = Not a resolution like 1920x1080. Senders pick one themselves until it is fixed.
+invalid-udp-port = Use a port from 1024 to { $last }, or leave it empty to let UXPlay pick.
+invalid-search-paths = Not a directory: { $dirs }
+invalid-uxplay = UXPlay is neither in these directories nor in PATH.
+invalid-container = { $program } is not installed, so the container can't be entered.
+
+## Resetting settings
+reset = Reset to defaults
+reset-button = Reset
+reset-cancel = Cancel
+reset-confirm = Reset these settings?
+reset-confirm-description = What you changed here is lost.
+reset-video-description = Puts the mirror window, browser viewer and low latency back as they were on a fresh install.
+reset-audio-description = Puts audio routing, volume and tone back as they were on a fresh install.
+reset-security-description = Removes the settings lock and what was set for individual devices, and turns away second senders again.
+reset-all-description = Puts every setting back as it was on a fresh install. Profiles and device settings are removed.
+ip-version = Network protocol
+ip-version-description = Where AirTray listens and announces the receiver itself, e.g. on standby. Try IPv4 only if devices can't find the receiver on a network with broken IPv6. UXPlay follows the use-ipv4 and use-ipv6 options of avahi-daemon.
+ip-both = IPv4 and IPv6
+ip-v4 = IPv4 only
+ip-v6 = IPv6 only
+mdns = Network announcements
+mdns-description = What announces the receiver on standby, looks for other receivers and checks the name. The built-in one does so without avahi-daemon, but while UXPlay runs it announces the receiver through avahi-daemon either way, so that is still needed.
+mdns-avahi = System (Avahi)
+mdns-builtin = Built in
+udp-ports = UDP ports
+udp-ports-description = First of three consecutive UDP ports for audio and timing, to match a firewall rule, e.g. 6000 for 6000–6002. Applies when AirPlay is next turned on.
+udp-ports-automatic = Any free
+search-paths = Search UXPlay in
+search-paths-description = Directories to look for UXPlay in before the usual ones, separated by colons, e.g. on NixOS or with Homebrew.
+container = Run UXPlay in a container
+container-description = Command that enters the Toolbox or Distrobox container UXPlay is installed in. Leave empty to run it from the system.
+
+## Troubleshooting
+troubleshooting = Troubleshooting
+debug-mode = Debug mode
+debug-mode-description = Restarts UXPlay with its debug output, which is also written to AirTray's own log. Leave it off otherwise, as it is very chatty.
+log = Receiver log
+log-show = Show
+log-errors = Errors only
+log-warnings = Warnings and errors
+log-all = Everything
+log-empty = Nothing logged yet
+debug-bundle = Save debug bundle
+debug-bundle-description = Saves settings without passwords, recent logs and system details to the Downloads folder, to attach to a bug report.
+debug-bundle-saved = Saved to { $path }
+
+## Names read by screen readers
+a11y-rename = Rename { $name }
+a11y-mute = Mute
+a11y-unmute = Unmute
+a11y-previous = Previous
+a11y-play-pause = Play or pause
+a11y-next = Next
+a11y-volume-down = Lower the sender's volume
+a11y-volume-up = Raise the sender's volume
@@ -1,80 +1,121 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::process::{Child, Command, Stdio};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{Datelike, Local, Timelike, Weekday};
 use cosmic::app::{Core, Task};
+use cosmic::cosmic_config;
+use cosmic::iced::keyboard::{self, key::Named, Key, Modifiers};
+use cosmic::iced::widget::qr_code;
 use cosmic::iced::window::Id;
-use cosmic::iced::Limits;
+use cosmic::iced::{Limits, Subscription};
 use cosmic::iced_winit::commands::popup::{destroy_popup, get_popup};
-use cosmic::widget::{self, settings};
+use cosmic::widget::{self, image};
 use cosmic::{Application, Element};
 
+use crate::appletv::{self, AppleTv, RemoteKey};
+use crate::audio::snapcast::{self, SnapcastStatus};
+use crate::audio::{self, Sink};
+use crate::backend::capabilities::{self, Capabilities};
+#[cfg(test)]
+use crate::backend::Backend;
+use crate::backend::{AudioCodec, BackendEvent};
+use crate::companion::{self, CompanionEvent, Endpoint};
+use crate::config::validation::{self, Invalid};
+use crate::config::{
+    AudioConfig, Codec, CompanionConfig, ConcurrentPolicy, Config, Corner, DeviceConfig,
+    DisplayServer, Equalizer, Hook, IntegrationsConfig, IpVersion, LaunchConfig, LockConfig,
+    LockMethod, MdnsResponder, MqttConfig, NetworkConfig, NetworkRule, NotificationConfig,
+    PictureConfig, PipConfig, PipSize, PortConfig, Profile, RecordingConfig, RecordingFormat,
+    RestConfig, ScaleMethod, ScalingConfig, ScheduleConfig, SettingsSection, SnapcastConfig,
+    StreamQuality, StreamVolume, VideoConfig, ViewerConfig, WebhookConfig,
+};
+use crate::dacp::{self, DacpCommand};
+use crate::dbus::bluez;
+use crate::dbus::kdeconnect::{self, BatteryLevel};
+use crate::dbus::logind;
+use crate::dbus::mpris;
+use crate::dbus::networkmanager;
+use crate::dbus::notifications;
+use crate::dbus::polkit;
+use crate::dbus::powerprofiles;
+use crate::dbus::service::{self, Request};
+#[cfg(feature = "tray")]
+use crate::dbus::tray::{self, TrayEvent, TrayState};
+use crate::debug;
+use crate::error::AirTrayError;
 use crate::fl;
+use crate::frames::{self, FrameSize};
+use crate::gpu::{self, Gpu};
+use crate::health::StreamHealth;
+use crate::history::{self, ExportFormat, History, KnownDevice, Stats};
+use crate::hooks;
+use crate::identity;
+use crate::log::{self, LogLine};
+use crate::mdns::{self, Nearby, Service};
+use crate::metadata::{self, NowPlaying};
+use crate::mqtt::{self, Publisher};
+use crate::plugins::{self, Output, PluginEvent};
+use crate::receiver::{Receiver, ReceiverEvent, ReceiverState};
+use crate::rest::{self, Api};
+use crate::schedule;
+use crate::usage::{Usage, UsageSampler};
+use crate::uxplayrc::{self, Imported};
+use crate::video::{self, QualityPreset};
+use crate::viewer;
+use crate::wake;
+use crate::watchdog;
+use crate::webhook::{self, WebhookEvent};
 
-#[derive(Default)]
-struct Uxplay {
-    airplay: bool,
-    process: Option<Child>,
-}
+mod clients;
+mod mirror;
+mod pages;
+mod pip;
+mod signage;
 
-impl Uxplay {
-    fn new() -> Self {
-        Self {
-            airplay: false,
-            process: None,
-        }
-    }
+pub use pages::Page;
 
-    /// Manages the UXPlay process based on the airplay setting.
-    /// Spawns a new process if airplay is true and no process is running.
-    /// Kills the existing process if airplay is false and a process is running.
-    fn manage_uxplay_process(&mut self) -> Result<(), std::io::Error> {
-        if self.airplay {
-            // Only spawn a new process if we don't already have one running
-            if self.process.is_none() {
-                println!("Starting UXPlay process");
-                let child = Command::new("uxplay")
-                    .stdout(Stdio::piped())
-                    .spawn()?;
+/// Key of the notification button showing why the receiver failed.
+const SHOW_LOG: &str = "show-log";
 
-                self.process = Some(child);
-            }
-        } else {
-            // Kill the process if it exists
-            if let Some(mut child) = self.process.take() {
-                println!("Stopping UXPlay process");
+/// Key of the notification button starting the failed receiver again.
+const RESTART: &str = "restart";
 
-                // Try to kill the process gracefully
-                if let Err(e) = child.kill() {
-                    println!("Failed to kill UXPlay process: {}", e);
+/// Key of the notification button listing receivers that claim to be this one.
+const SHOW_DUPLICATES: &str = "show-duplicates";
 
-                    // Even if kill fails, try to wait for it to avoid zombies
-                    if let Err(e) = child.wait() {
-                        println!("Failed to wait for UXPlay process: {}", e);
-                    }
-                } else {
-                    // Wait for the process to exit
-                    if let Err(e) = child.wait() {
-                        println!("Failed to wait for UXPlay process: {}", e);
-                    }
-                }
-            }
-        }
+/// How long to wait for UXPlay to report being ready before assuming it is,
+/// in case its output changed.
+const READY_TIMEOUT: Duration = Duration::from_secs(15);
 
-        Ok(())
-    }
+/// Times a receiver that exits before it is ready is started again, e.g.
+/// when the network came up but wasn't usable yet, before giving up.
+const MAX_START_RETRIES: u32 = 3;
 
-    /// Updates the airplay setting and manages the UXPlay process accordingly
-    fn set_airplay(&mut self, enabled: bool) -> Result<(), std::io::Error> {
-        // Only take action if the value is changing
-        if self.airplay != enabled {
-            self.airplay = enabled;
-            self.manage_uxplay_process()?;
-        }
+/// Hour on Mondays from which the weekly summary is sent.
+const SUMMARY_HOUR: u32 = 9;
 
-        Ok(())
-    }
-}
+/// How long before a session's time limit the sender is warned.
+const SESSION_LIMIT_WARNING: Duration = Duration::from_secs(60);
+
+/// Pause before starting such a receiver again.
+const START_RETRY_DELAY: Duration = Duration::from_secs(5);
 
+/// Where the applet's controls are shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// In a popup of a COSMIC panel button.
+    #[default]
+    Applet,
+    /// In a window of their own, for desktops without the COSMIC panel.
+    Window,
+    /// In a window opened from a tray icon, for desktops with a tray.
+    #[cfg(feature = "tray")]
+    Tray,
+}
 
 /// This is the struct that represents your application.
 /// It is used to define the data that will be used by your application.
@@ -82,11 +123,210 @@ impl Uxplay {
 pub struct AirTray {
     /// Application state which is managed by the COSMIC runtime.
     core: Core,
+    /// Whether this runs in the panel or as a window.
+    mode: Mode,
     /// The popup id.
     popup: Option<Id>,
-    /// Airplay toggler.
-    airplay_toggle: bool,
-    uxplay_process: Uxplay,
+    /// The AirPlay receiver and its lifecycle state.
+    receiver: Receiver,
+    /// Handle used to persist settings changes.
+    config_handler: Option<cosmic_config::Config>,
+    /// Current settings.
+    config: Config,
+    /// Audio sinks offered as the duplication target.
+    sinks: Vec<Sink>,
+    /// Descriptions of `sinks`, as shown in the dropdown.
+    sink_labels: Vec<String>,
+    /// "Default" followed by `sink_labels`, and the chosen sink if it is gone.
+    audio_output_labels: Vec<String>,
+    /// Index in `sinks` whose delay is being edited.
+    delay_sink: Option<usize>,
+    sink_delay_input: String,
+    /// Last known reachability of the Snapcast server, if enabled.
+    snapcast_status: Option<SnapcastStatus>,
+    /// Port field contents, kept separately so partial input can be edited.
+    snapcast_port_input: String,
+    /// Last error not tied to the receiver state, shown in the popup.
+    error: Option<AirTrayError>,
+    /// Page shown in the popup.
+    page: Page,
+    /// Where finished sessions are recorded.
+    history: History,
+    /// Totals for the statistics page, computed when it is opened.
+    stats: Stats,
+    /// Whether the Bluetooth audio sink is on, or `None` without an adapter.
+    bluetooth_sink: Option<bool>,
+    /// Battery levels of connected clients found in KDE Connect, by client id.
+    phones: HashMap<String, BatteryLevel>,
+    /// Apple TVs found by the last scan of the Remote page.
+    apple_tvs: Vec<AppleTv>,
+    apple_tv_labels: Vec<String>,
+    selected_apple_tv: Option<usize>,
+    scanning_apple_tvs: bool,
+    /// Receivers and senders found by the last scan of the Nearby page.
+    nearby: Option<Nearby>,
+    scanning_nearby: bool,
+    /// Files received from the phone since the applet started.
+    received_files: Vec<PathBuf>,
+    /// QR code of the upload page, while receiving files is enabled.
+    upload_qr: Option<qr_code::Data>,
+    /// Connection to the MQTT broker, once established.
+    mqtt: Option<Publisher>,
+    /// The status last handed to the broker.
+    mqtt_status: Option<mqtt::Status>,
+    /// Text of the MQTT port field, which may not be a valid port yet.
+    mqtt_port_input: String,
+    /// Whether the schedule window was open at the last check.
+    schedule_open: Option<bool>,
+    /// Whether the receiver was stopped because the user switched to another
+    /// login session.
+    seat_paused: bool,
+    /// Text of the schedule's time fields, which may not be valid times yet.
+    schedule_start_input: String,
+    schedule_end_input: String,
+    /// Text of the webhook URL field, which may not be a usable URL yet.
+    webhook_url_input: String,
+    /// Id of the primary NetworkManager connection, if known.
+    current_network: Option<String>,
+    /// Address of this machine on the LAN, as of the last network change.
+    lan_address: Option<IpAddr>,
+    /// "No profile" followed by the profile names, for the dropdown.
+    profile_labels: Vec<String>,
+    /// Text of the selected profile's frame rate field.
+    profile_fps_input: String,
+    /// Connected monitors, refreshed when the popup opens.
+    outputs: Vec<String>,
+    /// "Automatic" followed by the monitor names, for the dropdown.
+    output_labels: Vec<String>,
+    /// GPUs with a render node, refreshed with the monitors.
+    gpus: Vec<Gpu>,
+    /// "Default" followed by the GPU names.
+    gpu_labels: Vec<String>,
+    /// The picture-in-picture window, while open.
+    pip_window: Option<Id>,
+    /// The connect instructions, while shown.
+    signage_window: Option<Id>,
+    /// Latest frame streamed by UXPlay and its size, for mirror views the
+    /// applet draws.
+    frame: Option<(image::Handle, FrameSize)>,
+    corner_labels: Vec<String>,
+    pip_size_labels: Vec<String>,
+    scale_method_labels: Vec<String>,
+    /// Whether GStreamer can decode H.265, once known.
+    h265_available: Option<bool>,
+    /// Options of the installed UXPlay, for hiding settings it can't apply.
+    capabilities: Capabilities,
+    codec_labels: Vec<String>,
+    /// "Automatic" with the detected display server, then Wayland and X11.
+    display_server_labels: Vec<String>,
+    concurrent_labels: Vec<String>,
+    recording_format_labels: Vec<String>,
+    /// Text of the maximum bitrate field.
+    max_bitrate_input: String,
+    /// Text of the session time limit field.
+    session_limit_input: String,
+    /// Whether the session's end was announced.
+    session_limit_warned: bool,
+    /// The quality presets followed by "Custom".
+    quality_labels: Vec<String>,
+    /// The embedded mirror window, while open.
+    mirror_window: Option<Id>,
+    /// The user closed the mirror window during the current session.
+    mirror_dismissed: bool,
+    frame_rate: mirror::FrameRate,
+    /// Peak level of the received audio, while the popup is open.
+    audio_level: f32,
+    /// Track the sender is playing, for audio streams.
+    now_playing: Option<NowPlaying>,
+    /// Recent problems with the stream, for the poor connection warning.
+    health: StreamHealth,
+    /// CPU and memory use of the receiver, while the statistics are shown.
+    usage: Option<Usage>,
+    usage_sampler: UsageSampler,
+    /// Audio format of the current session, once the sender chose it.
+    audio_codec: Option<AudioCodec>,
+    /// Health checks the running receiver failed in a row.
+    watchdog_failures: u32,
+    /// Whether the running receiver has announced itself on the network.
+    ready: bool,
+    /// Times the receiver was started again after exiting before it was ready.
+    start_retries: u32,
+    /// Host of another receiver on the network using the receiver name.
+    name_conflict: Option<String>,
+    /// Receivers on other machines using the name or device id of this one.
+    duplicates: Vec<Service>,
+    /// GStreamer elements the selected profile's pipelines use that aren't installed.
+    missing_elements: Vec<String>,
+    /// UXPlay's output at the level picked on the log page, as last refreshed.
+    log_lines: Vec<LogLine>,
+    log_level: log::Level,
+    log_level_labels: Vec<String>,
+    /// "Selected profile" followed by the profile names.
+    device_profile_labels: Vec<String>,
+    /// Senders from the history, for the devices page.
+    known_devices: Vec<KnownDevice>,
+    /// Profile the receiver runs with instead of the selected one, for the
+    /// device that connected last.
+    device_profile: Option<String>,
+    /// The devices refused as of the last check of their hours.
+    device_blocks: Vec<String>,
+    /// Id of the device whose hours are being edited on the devices page.
+    editing_hours: Option<String>,
+    /// Text of the edited device's time fields, which may not be valid times yet.
+    hours_start_input: String,
+    hours_end_input: String,
+    /// Device id and text of the nickname being edited.
+    renaming: Option<(String, String)>,
+    /// Senders that left recently and may still come back.
+    departed: Vec<clients::Departed>,
+    /// Notifications about senders shown recently, to update instead of repeat.
+    notifications: clients::Coalesced,
+    /// Text of the grace period field.
+    grace_period_input: String,
+    /// Whether a sender was connected after the last update.
+    in_session: bool,
+    /// Whether the start hook ran last rather than the stop hook.
+    running: bool,
+    /// Output modules of the plugins built in.
+    output_modules: Vec<Box<dyn Output>>,
+    /// Whether the receiver had failed after the last update.
+    failed: bool,
+    /// Desktop players paused when the session started.
+    paused_players: Vec<String>,
+    lock_labels: Vec<String>,
+    ip_version_labels: Vec<String>,
+    mdns_labels: Vec<String>,
+    /// The settings lock was passed since the popup opened.
+    unlocked: bool,
+    /// Page opened once the lock is passed.
+    unlock_page: Page,
+    /// Text of the PIN field on the unlock page.
+    unlock_input: String,
+    /// Waiting for polkit's dialog.
+    unlocking: bool,
+    /// The last attempt to unlock was wrong or denied.
+    unlock_failed: bool,
+    /// Where the last debug bundle was saved.
+    debug_bundle: Option<PathBuf>,
+    /// Settings UXPlay can't be started with, checked when they change.
+    invalid: Vec<Invalid>,
+    /// Settings waiting for the reset to be confirmed.
+    confirm_reset: Option<SettingsSection>,
+    /// Options of the last imported uxplayrc that had no matching setting.
+    uxplayrc_skipped: Option<Vec<String>>,
+    /// Where the settings were last exported as a uxplayrc.
+    uxplayrc_exported: Option<PathBuf>,
+    /// Where the receiver's keys were last backed up.
+    keys_backed_up: Option<PathBuf>,
+    keys_restored: bool,
+    /// Where the session history was last exported.
+    history_exported: Option<PathBuf>,
+    /// The PIN UXPlay picked for the sender trying to connect.
+    session_pin: Option<String>,
+    /// Text of the search paths field.
+    search_paths_input: String,
+    /// Text of the UDP port field, which may not be a valid port.
+    udp_port_input: String,
 }
 
 /// This is the enum that contains all the possible variants that your application will need to transmit messages.
@@ -96,7 +336,242 @@ pub struct AirTray {
 pub enum Message {
     TogglePopup,
     PopupClosed(Id),
+    /// A click on the tray icon.
+    #[cfg(feature = "tray")]
+    Tray(TrayEvent),
+    /// Moves the keyboard focus to the next or previous control.
+    FocusNext,
+    FocusPrevious,
     ToggleAirPlay(bool),
+    /// Progress of the receiver's asynchronous start and stop.
+    Receiver(ReceiverEvent),
+    /// The process with the given generation took too long to report being ready.
+    ReadyTimeout(u64),
+    /// Starts the receiver again after it exited before it was ready.
+    RetryStart,
+    UpdateConfig(Config),
+    ToggleDuplicateAudio(bool),
+    NameChanged(String),
+    /// Looks for another receiver on the network with the same name.
+    CheckName,
+    /// The host of another receiver with the given name, if any.
+    NameChecked(String, Vec<Service>),
+    /// Switches to [`Config::alternative_name`] because the name is taken.
+    RenameForConflict,
+    ToggleAutoRename(bool),
+    SelectSecondarySink(usize),
+    /// Selects the sink to play on by its index in the dropdown, 0 being the default.
+    SelectOutputSink(usize),
+    /// Selects the sink whose delay is edited by its index in the dropdown.
+    SelectDelaySink(usize),
+    SinkDelayChanged(String),
+    ToggleSnapcast(bool),
+    SnapcastHostChanged(String),
+    SnapcastPortChanged(String),
+    /// Result of checking whether the Snapcast server is reachable.
+    SnapcastStatus(SnapcastStatus),
+    OpenPage(Page),
+    ToggleBluetoothSink(bool),
+    /// Result of querying or changing the Bluetooth sink.
+    BluetoothSink(Result<Option<bool>, AirTrayError>),
+    /// A request from another program through the D-Bus interface.
+    Remote(Request),
+    ToggleKdeConnect(bool),
+    ToggleViewer(bool),
+    /// Selects the monitor by its index in the dropdown, 0 being automatic.
+    SelectOutput(usize),
+    /// Selects the GPU by its index in the dropdown, 0 being the default one.
+    SelectGpu(usize),
+    TogglePip(bool),
+    ToggleSignage(bool),
+    ToggleKiosk(bool),
+    ToggleRandomPin(bool),
+    ToggleActiveSeatOnly(bool),
+    /// Whether this login session is the one on screen.
+    SeatActive(bool),
+    SelectPipCorner(usize),
+    SelectPipSize(usize),
+    /// Selects a quality preset by its index in the dropdown.
+    SelectQualityPreset(usize),
+    MaxBitrateChanged(String),
+    /// Selects the codec by its index in the dropdown.
+    SelectCodec(usize),
+    /// Whether GStreamer can decode H.265.
+    H265Available(bool),
+    /// Selects the display server by its index in the dropdown, 0 being automatic.
+    SelectDisplayServer(usize),
+    /// What the installed UXPlay supports.
+    Capabilities(Capabilities),
+    SelectScaleMethod(usize),
+    ToggleIntegerScaling(bool),
+    BrightnessChanged(i32),
+    ContrastChanged(u32),
+    SaturationChanged(u32),
+    ResetPicture,
+    /// A video frame streamed by UXPlay.
+    Frame(frames::Frame),
+    /// Level of the received audio, from 0.0 to 1.0.
+    AudioLevel(f32),
+    NowPlaying(Option<NowPlaying>),
+    StreamVolumeChanged(u32),
+    TogglePauseLocalMedia(bool),
+    ToggleEqualizer(bool),
+    /// Bass gain in dB.
+    BassChanged(i32),
+    /// Treble gain in dB.
+    TrebleChanged(i32),
+    ToggleLoudness(bool),
+    ToggleRecording(bool),
+    /// Selects the recording format by its index in the dropdown.
+    SelectRecordingFormat(usize),
+    RecordingDirChanged(String),
+    ToggleResumeLocalMedia(bool),
+    /// Desktop players paused for the session, by bus name.
+    PlayersPaused(Result<Vec<String>, AirTrayError>),
+    ToggleStreamMute,
+    ToggleEmbeddedMirror(bool),
+    ToggleExtendedDisplay(bool),
+    MirrorWindowClosed,
+    ToggleWakeOnDemand(bool),
+    TogglePerformanceProfile(bool),
+    /// Selects the profile for the device with the given id by its index in
+    /// the dropdown, 0 being the selected profile.
+    SelectDeviceProfile(String, usize),
+    /// Allows or blocks the device with the given id.
+    SetDeviceAllowed(String, bool),
+    /// Gives or takes priority from the device with the given id.
+    SetDevicePriority(String, bool),
+    /// Shows or hides the hours of the device with the given id.
+    EditDeviceHours(String),
+    /// Restricts the edited device to its hours, or lifts the restriction.
+    ToggleDeviceHours(bool),
+    /// Adds or removes a day of the week (0 being Monday) from the edited device's hours.
+    ToggleDeviceHoursDay(u8),
+    DeviceHoursStartChanged(String),
+    DeviceHoursEndChanged(String),
+    /// Removes everything stored about the device with the given id.
+    ForgetDevice(String),
+    /// Starts editing the nickname of the client with the given id.
+    RenameClient(String),
+    NicknameChanged(String),
+    SaveNickname,
+    ToggleNotifications(bool),
+    /// Selects the concurrent client policy by its index in the dropdown.
+    SelectConcurrentPolicy(usize),
+    /// The button clicked on a notification waited on, by its key.
+    NotificationAction(Option<String>),
+    /// The button clicked on any notification, by its key.
+    NotificationInvoked(String),
+    /// The notification server showed a notification of the kind with this id.
+    Notified(clients::NotificationKind, u32),
+    GracePeriodChanged(String),
+    ToggleSounds(bool),
+    ConnectSoundChanged(String),
+    DisconnectSoundChanged(String),
+    /// The grace period of a sender that left may have run out.
+    GraceExpired,
+    ToggleLowLatency(bool),
+    /// Periodic check of the availability schedule.
+    ScheduleTick,
+    /// Periodic sample of the receiver's resource use.
+    SampleUsage,
+    /// Periodic health check of the running receiver.
+    WatchdogTick,
+    /// Every second of a session, for its time limit and the timers shown.
+    SessionTick,
+    SessionLimitChanged(String),
+    /// Checks whether the weekly summary is due.
+    SummaryTick,
+    ToggleWeeklySummary(bool),
+    /// Result of the health check of the process with the given generation.
+    WatchdogChecked(u64, Result<(), String>),
+    ToggleSchedule(bool),
+    /// Adds or removes a day of the week (0 being Monday) from the schedule.
+    ToggleScheduleDay(u8),
+    ScheduleStartChanged(String),
+    ScheduleEndChanged(String),
+    /// The primary network connection changed.
+    NetworkChanged(Option<String>),
+    ToggleNetworkProfiles(bool),
+    /// Selects a profile by its index in the dropdown, 0 being no profile.
+    SelectProfile(usize),
+    AddProfile,
+    RemoveProfile,
+    ProfileNameChanged(String),
+    ProfilePinChanged(String),
+    ProfileResolutionChanged(String),
+    ProfileFpsChanged(String),
+    ProfileVideoPipelineChanged(String),
+    ProfileAudioPipelineChanged(String),
+    /// Elements missing from the pipelines of the selected profile, as checked
+    /// for the given video and audio pipeline.
+    PipelinesChecked((String, String), Vec<String>),
+    /// Uses the selected profile whenever the current network is connected.
+    UseProfileOnNetwork,
+    RemoveNetworkRule(usize),
+    /// A sender tried to connect while in standby.
+    Wake,
+    /// KDE Connect lookup result for the client with the given id.
+    PhoneFound(String, Result<Option<BatteryLevel>, AirTrayError>),
+    /// A media control for the connected sender, from the popup or media keys.
+    Control(DacpCommand),
+    /// Result of a command sent to the sender or an Apple TV.
+    ControlSent(Result<(), AirTrayError>),
+    ScanAppleTvs,
+    AppleTvsFound(Result<Vec<AppleTv>, AirTrayError>),
+    SelectAppleTv(usize),
+    PressRemoteKey(RemoteKey),
+    ScanNearby,
+    NearbyFound(Result<Nearby, AirTrayError>),
+    ToggleClipboardSharing(bool),
+    ToggleFileReceiving(bool),
+    DownloadDirChanged(String),
+    /// Opens the received file at the given index of the list.
+    OpenReceivedFile(usize),
+    MqttConnected(Publisher),
+    ToggleMqtt(bool),
+    MqttHostChanged(String),
+    MqttPortChanged(String),
+    MqttUsernameChanged(String),
+    MqttPasswordChanged(String),
+    HookChanged(Hook, String),
+    ToggleWebhook(bool),
+    ToggleRest(bool),
+    ToggleRestLan(bool),
+    WebhookUrlChanged(String),
+    /// Something a phone sent to the companion endpoint.
+    Companion(CompanionEvent),
+    /// Selects how the settings are locked by its index in the dropdown.
+    SelectLockMethod(usize),
+    /// Selects the IP versions by their index in the dropdown.
+    SelectIpVersion(usize),
+    /// Selects the mDNS responder by its index in the dropdown.
+    SelectMdnsResponder(usize),
+    LockPinChanged(String),
+    UnlockInputChanged(String),
+    /// Checks the PIN or asks polkit to unlock the settings.
+    Unlock,
+    /// Whether authentication succeeded.
+    Unlocked(Result<bool, AirTrayError>),
+    ContainerChanged(String),
+    /// Directories to search for UXPlay, separated by colons.
+    SearchPathsChanged(String),
+    UdpPortChanged(String),
+    SaveDebugBundle,
+    /// Asks to confirm resetting the settings of a section.
+    AskReset(SettingsSection),
+    CancelReset,
+    Reset(SettingsSection),
+    ImportUxplayrc,
+    ExportUxplayrc,
+    BackupKeys,
+    ExportHistory(ExportFormat),
+    RestoreKeys,
+    ToggleDebug(bool),
+    /// Selects the least serious log lines shown by index in the dropdown.
+    SelectLogLevel(usize),
+    RefreshLog,
+    DebugBundleSaved(Result<PathBuf, AirTrayError>),
 }
 
 /// Implement the `Application` trait for your application.
@@ -110,7 +585,7 @@ pub enum Message {
 impl Application for AirTray {
     type Executor = cosmic::executor::Default;
 
-    type Flags = ();
+    type Flags = Mode;
 
     type Message = Message;
 
@@ -131,20 +606,312 @@ impl Application for AirTray {
     /// - `core` is used to passed on for you by libcosmic to use in the core of your own application.
     /// - `flags` is used to pass in any data that your application needs to use before it starts.
     /// - `Command` type is used to send messages to your application. `Command::none()` can be used to send no messages to your application.
-    fn init(core: Core, _flags: Self::Flags) -> (Self, Task<Self::Message>) {
-        let app = AirTray {
+    fn init(core: Core, mode: Self::Flags) -> (Self, Task<Self::Message>) {
+        let (config_handler, config) = Config::load(Self::APP_ID);
+
+        let plugins = plugins::registry();
+        let mut receiver = Receiver::new(plugins.backend(&config.launch.backend));
+        receiver.config = config.clone();
+
+        let mut app = AirTray {
             core,
             popup: None,
-            airplay_toggle: false,
-            uxplay_process: Uxplay::new(),
+            receiver,
+            output_modules: plugins.into_outputs(),
+            snapcast_port_input: config.audio.snapcast.port.to_string(),
+            mqtt_port_input: config.integrations.mqtt.port.to_string(),
+            corner_labels: vec![
+                fl!("corner-top-left"),
+                fl!("corner-top-right"),
+                fl!("corner-bottom-left"),
+                fl!("corner-bottom-right"),
+            ],
+            pip_size_labels: vec![fl!("pip-small"), fl!("pip-medium"), fl!("pip-large")],
+            grace_period_input: config.notifications.grace_period.to_string(),
+            max_bitrate_input: match config.video.max_bitrate {
+                0 => String::new(),
+                max_bitrate => max_bitrate.to_string(),
+            },
+            session_limit_input: match config.session_limit {
+                0 => String::new(),
+                minutes => minutes.to_string(),
+            },
+            session_limit_warned: false,
+            concurrent_labels: vec![
+                fl!("concurrent-reject"),
+                fl!("concurrent-allow"),
+                fl!("concurrent-prompt"),
+            ],
+            codec_labels: vec![String::from("H.264"), String::from("H.265 (HEVC)")],
+            display_server_labels: vec![
+                fl!(
+                    "display-server-automatic",
+                    detected = match video::detect_display_server() {
+                        DisplayServer::X11 => "X11",
+                        _ => "Wayland",
+                    }
+                ),
+                String::from("Wayland"),
+                String::from("X11"),
+            ],
+            lock_labels: vec![fl!("lock-none"), fl!("lock-pin"), fl!("lock-polkit")],
+            ip_version_labels: vec![fl!("ip-both"), fl!("ip-v4"), fl!("ip-v6")],
+            mdns_labels: vec![fl!("mdns-avahi"), fl!("mdns-builtin")],
+            log_level_labels: vec![fl!("log-errors"), fl!("log-warnings"), fl!("log-all")],
+            recording_format_labels: vec![String::from("FLAC"), String::from("Ogg Vorbis")],
+            quality_labels: vec![
+                fl!("quality-low"),
+                fl!("quality-balanced"),
+                fl!("quality-high"),
+                fl!("quality-custom"),
+            ],
+            scale_method_labels: vec![fl!("scale-nearest"), fl!("scale-bilinear")],
+            schedule_start_input: config.schedule.start.format("%H:%M").to_string(),
+            schedule_end_input: config.schedule.end.format("%H:%M").to_string(),
+            device_blocks: config.blocked_devices(Local::now()),
+            webhook_url_input: config.integrations.webhook.url.clone(),
+            udp_port_input: match config.ports.udp {
+                0 => String::new(),
+                port => port.to_string(),
+            },
+            search_paths_input: std::env::join_paths(&config.launch.search_paths)
+                .map(|paths| paths.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            lan_address: Some(viewer::lan_address()),
+            config_handler,
+            config,
+            mode,
             ..Default::default()
         };
 
-        (app, Task::none())
+        app.refresh_upload_qr();
+        app.ensure_rest_token();
+        app.refresh_profiles();
+        app.invalid = validation::check(&app.config);
+
+        let task = app.handle(Message::ScheduleTick);
+        let h265 = app_task(cosmic::iced::Task::perform(
+            video::h265_available(),
+            Message::H265Available,
+        ));
+        let capabilities = app.probe_capabilities();
+        let kiosk = if app.config.kiosk {
+            app.handle(Message::ToggleAirPlay(true))
+        } else {
+            Task::none()
+        };
+        // The window is shown right away, unlike the popup
+        let shown = match mode {
+            Mode::Window => app.on_shown(),
+            Mode::Applet => Task::none(),
+            #[cfg(feature = "tray")]
+            Mode::Tray => Task::none(),
+        };
+
+        (app, Task::batch([task, h265, capabilities, kiosk, shown]))
     }
 
     fn on_close_requested(&self, id: Id) -> Option<Message> {
-        Some(Message::PopupClosed(id))
+        if self.mirror_window == Some(id) {
+            Some(Message::MirrorWindowClosed)
+        } else {
+            Some(Message::PopupClosed(id))
+        }
+    }
+
+    /// Escape goes back to the main page, and from there closes the popup.
+    fn on_escape(&mut self) -> Task<Message> {
+        if !self.is_shown() {
+            Task::none()
+        } else if self.page != Page::Main {
+            self.handle(Message::OpenPage(Page::Main))
+        } else if self.popup.is_some() {
+            self.handle(Message::TogglePopup)
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Keeps the settings in sync when they are changed from outside the applet,
+    /// serves the D-Bus control interface and, while a sender is connected,
+    /// an MPRIS player forwarding media keys to it.
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let mut subscriptions = vec![
+            self.core()
+                .watch_config::<Config>(Self::APP_ID)
+                .map(|update| Message::UpdateConfig(update.config)),
+            Subscription::run(service::serve).map(Message::Remote),
+            Subscription::run(notifications::invoked).map(Message::NotificationInvoked),
+        ];
+
+        #[cfg(feature = "tray")]
+        if self.mode == Mode::Tray {
+            subscriptions.push(Subscription::run(tray::serve).map(Message::Tray));
+        }
+
+        if let Some(api) = self.rest_api() {
+            subscriptions.push(
+                Subscription::run_with_id(api.clone(), rest::serve(api)).map(Message::Remote),
+            );
+        }
+
+        if self.has_clients() {
+            subscriptions.push(Subscription::run(mpris::serve).map(Message::Control));
+            subscriptions.push(Subscription::run(metadata::watch).map(Message::NowPlaying));
+
+            let name = self.config.receiver_name();
+            let volume = self.config.stream_volume();
+            subscriptions.push(Subscription::run_with_id(
+                ("volume", name.clone(), volume),
+                audio::volume::hold(name, volume.percent, volume.muted),
+            ));
+
+            // The equalizer and duplication route the stream through sinks of their own
+            let audio = &self.config.audio;
+            if let Some(sink) = audio.sink.clone() {
+                if !audio.snapcast.enabled && !audio.duplicate && !self.config.equalizer().enabled {
+                    let name = self.config.receiver_name();
+                    subscriptions.push(Subscription::run_with_id(
+                        ("pin", name.clone(), sink.clone()),
+                        audio::pin::hold(name, sink),
+                    ));
+                }
+            }
+
+            // Only audio streams come with track metadata
+            let recording = &self.config.audio.recording;
+            if let Some(now_playing) = self.now_playing.as_ref().filter(|_| recording.enabled) {
+                let track = match (now_playing.artist.is_empty(), now_playing.title.is_empty()) {
+                    (false, false) => format!("{} - {}", now_playing.artist, now_playing.title),
+                    _ => now_playing.title.clone(),
+                };
+                let name = self.config.receiver_name();
+                let dir = recording.directory();
+                subscriptions.push(
+                    Subscription::run_with_id(
+                        ("record", name.clone(), track.clone(), recording.format, dir.clone()),
+                        audio::record::record(name, dir, track, recording.format),
+                    )
+                    .map(|never| match never {}),
+                );
+            }
+
+            if self.config.low_latency || self.config.performance_profile {
+                subscriptions.push(
+                    Subscription::run_with_id(
+                        "performance",
+                        powerprofiles::hold_performance(Self::APP_ID),
+                    )
+                    .map(|never| match never {}),
+                );
+            }
+
+            if let Some(endpoint) = self.companion_endpoint() {
+                subscriptions.push(
+                    Subscription::run_with_id(endpoint.clone(), companion::serve(endpoint))
+                        .map(Message::Companion),
+                );
+            }
+        }
+
+        if *self.receiver.state() == ReceiverState::Standby {
+            let name = self.config.receiver_name();
+            subscriptions.push(
+                Subscription::run_with_id(
+                    ("wake", name.clone(), self.config.ip_version, self.config.mdns),
+                    wake::listen(name, self.config.ip_version, self.config.mdns),
+                )
+                .map(|()| Message::Wake),
+            );
+        }
+
+        if self.config.schedule.enabled
+            || self.config.devices.values().any(|device| device.schedule.enabled)
+        {
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_secs(30)).map(|_| Message::ScheduleTick),
+            );
+        }
+
+        // Also needed to show the current network while setting up rules
+        subscriptions.push(
+            Subscription::run(networkmanager::watch).map(Message::NetworkChanged),
+        );
+
+        if let ReceiverState::Running { .. } = self.receiver.state() {
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_secs(60)).map(|_| Message::WatchdogTick),
+            );
+        }
+
+        if self.config.active_seat_only {
+            subscriptions.push(Subscription::run(logind::watch_active).map(Message::SeatActive));
+        }
+
+        if self.config.notifications.weekly_summary {
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_secs(15 * 60))
+                    .map(|_| Message::SummaryTick),
+            );
+        }
+
+        // Also keeps the timers in the popup going
+        if self.has_clients() && (self.config.session_limit > 0 || self.is_shown()) {
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_secs(1)).map(|_| Message::SessionTick),
+            );
+        }
+
+        // Notices receivers claiming to be this one while senders can reach it
+        if self.receiver.state().is_active() {
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_secs(300)).map(|_| Message::CheckName),
+            );
+        }
+
+        // Tab is taken care of by libcosmic's keyboard navigation
+        if self.is_shown() {
+            subscriptions.push(keyboard::on_key_press(arrow_navigation));
+        }
+
+        if self.is_shown() && self.page == Page::Log {
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_secs(2)).map(|_| Message::RefreshLog),
+            );
+        }
+
+        if self.is_shown()
+            && self.page == Page::Statistics
+            && self.receiver.state().is_active()
+        {
+            subscriptions.push(
+                cosmic::iced::time::every(Duration::from_secs(2)).map(|_| Message::SampleUsage),
+            );
+        }
+
+        if video::frame_size(&self.config.video).is_some() && self.receiver.state().is_active() {
+            subscriptions
+                .push(Subscription::run_with_id("frames", frames::receive()).map(Message::Frame));
+        }
+
+        // Only metered while someone is looking at it
+        if self.is_shown() && self.has_clients() {
+            let name = self.config.receiver_name();
+            subscriptions.push(
+                Subscription::run_with_id(("meter", name.clone()), audio::meter::levels(name))
+                    .map(Message::AudioLevel),
+            );
+        }
+
+        let mqtt = &self.config.integrations.mqtt;
+        if mqtt.enabled {
+            subscriptions.push(
+                Subscription::run_with_id(mqtt.clone(), mqtt::connect(mqtt.clone()))
+                    .map(Message::MqttConnected),
+            );
+        }
+
+        Subscription::batch(subscriptions)
     }
 
     /// This is the main view of your application, it is the root of your widget tree.
@@ -154,34 +921,107 @@ impl Application for AirTray {
     ///
     /// To get a better sense of which widgets are available, check out the `widget` module.
     fn view(&self) -> Element<Self::Message> {
+        if self.mode == Mode::Window {
+            return widget::scrollable(self.view_page()).into();
+        }
+
+        let icon = if self.is_starting() {
+            "process-working-symbolic"
+        } else {
+            "com.github.introini.airtray"
+        };
         self.core
             .applet
-            .icon_button("com.github.introini.airtray")
+            .icon_button(icon)
             .on_press(Message::TogglePopup)
             .into()
     }
 
-    fn view_window(&self, _id: Id) -> Element<Self::Message> {
-        let content_list = widget::list_column()
-            .padding(5)
-            .spacing(0)
-            .add(settings::item(
-                fl!("airplay"),
-                widget::toggler(self.airplay_toggle).on_toggle(Message::ToggleAirPlay),
-            ));
-
-        self.core.applet.popup_container(content_list).into()
+    fn view_window(&self, id: Id) -> Element<Self::Message> {
+        if self.pip_window == Some(id) {
+            return self.view_pip();
+        }
+        if self.signage_window == Some(id) {
+            return self.view_signage();
+        }
+        if self.mirror_window == Some(id) {
+            return self.view_mirror();
+        }
+        if self.mode != Mode::Applet {
+            return widget::scrollable(self.view_page()).into();
+        }
+        self.core.applet.popup_container(self.view_page()).into()
     }
 
     /// Application messages are handled here. The application state can be modified based on
     /// what message was received. Commands may be returned for asynchronous execution on a
     /// background thread managed by the application's executor.
     fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        let before = self.clients().to_vec();
+        let disconnected = matches!(
+            message,
+            Message::Receiver(ReceiverEvent::Backend {
+                event: BackendEvent::ClientsDisconnected,
+                ..
+            })
+        );
+        let task = self.handle(message);
+        let graceful = disconnected || self.receiver.is_restarting();
+        let clients = self.on_clients_change(&before, graceful);
+        self.record_sessions();
+        self.publish_status();
+        if self.config.integrations.rest.enabled {
+            rest::publish_settings(&self.receiver.config);
+        }
+        #[cfg(feature = "tray")]
+        if self.mode == Mode::Tray {
+            tray::show(TrayState {
+                streaming: self.has_clients(),
+                status: self.status_text(),
+            });
+        }
+        let session = self.on_session_change();
+        if !self.in_grace_or_session() {
+            // Leave nothing of the last session on screen
+            self.frame = None;
+            self.mirror_dismissed = false;
+            self.audio_level = 0.0;
+            self.now_playing = None;
+            self.health.clear();
+            self.audio_codec = None;
+        }
+        let hook = self.on_running_change();
+        let failure = self.on_failure();
+        Task::batch([
+            task,
+            clients,
+            session,
+            hook,
+            failure,
+            self.sync_pip(),
+            self.sync_mirror(),
+            self.sync_signage(),
+        ])
+    }
+
+    fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
+        (self.mode == Mode::Applet).then(cosmic::applet::style)
+    }
+
+}
+
+impl AirTray {
+    fn handle(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::TogglePopup => {
+                #[cfg(feature = "tray")]
+                if self.mode == Mode::Tray {
+                    return self.toggle_tray_window();
+                }
                 return if let Some(p) = self.popup.take() {
                     destroy_popup(p)
                 } else {
+                    let shown = self.on_shown();
                     let new_id = Id::unique();
                     self.popup.replace(new_id);
                     let mut popup_settings = self.core.applet.get_popup_settings(
@@ -196,7 +1036,7 @@ impl Application for AirTray {
                         .min_width(300.0)
                         .min_height(200.0)
                         .max_height(1080.0);
-                    get_popup(popup_settings)
+                    Task::batch([get_popup(popup_settings), shown])
                 }
             }
             Message::PopupClosed(id) => {
@@ -204,19 +1044,2314 @@ impl Application for AirTray {
                     self.popup = None;
                 }
             }
+            #[cfg(feature = "tray")]
+            Message::Tray(TrayEvent::Activate) => return self.handle(Message::TogglePopup),
+            #[cfg(feature = "tray")]
+            Message::Tray(TrayEvent::SecondaryActivate) => {
+                let active = self.receiver.state().is_active();
+                return self.handle(Message::ToggleAirPlay(!active));
+            }
+            Message::FocusNext => return app_task(cosmic::iced::widget::focus_next()),
+            Message::FocusPrevious => return app_task(cosmic::iced::widget::focus_previous()),
             Message::ToggleAirPlay(toggled) => {
-                self.airplay_toggle = toggled;
-                let _ = self.uxplay_process.manage_uxplay_process();
-                if let Err(e) = self.uxplay_process.set_airplay(self.airplay_toggle) {
-                    eprintln!("Failed to set airplay: {}", e);
+                self.start_retries = 0;
+                if toggled && self.config.wake_on_demand {
+                    self.receiver.standby();
+                } else if toggled {
+                    let start = app_task(self.receiver.start().map(Message::Receiver));
+                    return Task::batch([start, self.handle(Message::CheckName)]);
+                } else {
+                    self.receiver.stop();
+                }
+            }
+            Message::Receiver(event) => {
+                let mut ready_timeout = Task::none();
+                match &event {
+                    ReceiverEvent::Started {
+                        generation,
+                        result: Ok(()),
+                    } if *generation == self.receiver.generation() => {
+                        self.ready = false;
+                        let generation = *generation;
+                        ready_timeout = app_task(cosmic::iced::Task::perform(
+                            tokio::time::sleep(READY_TIMEOUT),
+                            move |()| Message::ReadyTimeout(generation),
+                        ));
+                    }
+                    ReceiverEvent::Backend {
+                        event: BackendEvent::Ready,
+                        ..
+                    } => {
+                        self.ready = true;
+                        self.start_retries = 0;
+                    }
+                    ReceiverEvent::Backend {
+                        event: BackendEvent::FramesDropped,
+                        ..
+                    } => self.health.frames_dropped(),
+                    ReceiverEvent::Backend {
+                        event: BackendEvent::PacketsResent,
+                        ..
+                    } => self.health.packets_resent(),
+                    ReceiverEvent::Backend {
+                        event: BackendEvent::AudioFormat(codec),
+                        ..
+                    } => self.audio_codec = Some(*codec),
+                    ReceiverEvent::Backend {
+                        event: BackendEvent::Pin(pin),
+                        ..
+                    } => self.session_pin = Some(pin.clone()),
+                    ReceiverEvent::Backend {
+                        event: BackendEvent::ClientConnected { id, name, .. },
+                        ..
+                    } if self.config.concurrent_clients != ConcurrentPolicy::Allow
+                        && self.has_clients()
+                        && !self.clients().iter().any(|client| &client.id == id) =>
+                    {
+                        // UXPlay turned it away
+                        return self.refused(id, name);
+                    }
+                    // Used up, or no longer of use
+                    ReceiverEvent::Backend {
+                        event: BackendEvent::ClientConnected { .. } | BackendEvent::Exited(..),
+                        ..
+                    } => self.session_pin = None,
+                    _ => {}
+                }
+                let greet = self.greet_phone(&event);
+                let disconnected = matches!(
+                    event,
+                    ReceiverEvent::Backend {
+                        event: BackendEvent::ClientsDisconnected,
+                        ..
+                    }
+                );
+                let was_running = matches!(self.receiver.state(), ReceiverState::Running { .. });
+                let task = app_task(self.receiver.update(event).map(Message::Receiver));
+                if disconnected && self.config.wake_on_demand {
+                    self.receiver.idle();
+                }
+                let failed = match self.receiver.state() {
+                    ReceiverState::Failed {
+                        reason: reason @ AirTrayError::ProcessExited { code, stderr },
+                    } if was_running && *code != Some(0) => {
+                        if !self.ready && self.start_retries < MAX_START_RETRIES {
+                            self.start_retries += 1;
+                            eprintln!(
+                                "Receiver exited before it was ready, starting it again ({}/{})",
+                                self.start_retries, MAX_START_RETRIES
+                            );
+                            app_task(cosmic::iced::Task::perform(
+                                tokio::time::sleep(START_RETRY_DELAY),
+                                |()| Message::RetryStart,
+                            ))
+                        } else {
+                            self.notify_failure(reason.user_message(), stderr.join("\n"))
+                        }
+                    }
+                    _ => Task::none(),
+                };
+                return Task::batch([task, greet, failed, ready_timeout]);
+            }
+            Message::RetryStart => {
+                // Unless turned off in the meantime
+                if let ReceiverState::Failed { .. } = self.receiver.state() {
+                    return app_task(self.receiver.start().map(Message::Receiver));
+                }
+            }
+            Message::ReadyTimeout(generation) => {
+                if generation == self.receiver.generation() && !self.ready {
+                    eprintln!("UXPlay did not report being ready, assuming it is");
+                    self.ready = true;
+                }
+            }
+            Message::Wake => {
+                return app_task(self.receiver.start().map(Message::Receiver));
+            }
+            Message::SessionTick => return self.enforce_session_limit(),
+            Message::SummaryTick => return self.weekly_summary(),
+            Message::SessionLimitChanged(input) => {
+                let minutes = if input.is_empty() { Ok(0) } else { input.parse() };
+                if let Ok(minutes) = minutes {
+                    self.save_setting(minutes, Config::set_session_limit, |config| {
+                        &mut config.session_limit
+                    });
+                }
+                self.session_limit_input = input;
+            }
+            Message::ToggleLowLatency(low_latency) => {
+                self.save_setting(low_latency, Config::set_low_latency, |config| {
+                    &mut config.low_latency
+                });
+            }
+            Message::SelectDeviceProfile(id, index) => {
+                let profile = match index {
+                    0 => String::new(),
+                    index => self.profile_labels.get(index).cloned().unwrap_or_default(),
+                };
+                let mut devices = self.config.devices.clone();
+                devices.entry(id).or_default().profile = profile;
+                self.save_devices(devices);
+            }
+            Message::SetDeviceAllowed(id, allowed) => {
+                let mut devices = self.config.devices.clone();
+                devices.entry(id).or_default().blocked = !allowed;
+                self.save_devices(devices);
+            }
+            Message::EditDeviceHours(id) => {
+                if self.editing_hours.as_ref() == Some(&id) {
+                    self.editing_hours = None;
+                } else {
+                    let hours = self.device_hours(&id);
+                    self.hours_start_input = hours.start.format("%H:%M").to_string();
+                    self.hours_end_input = hours.end.format("%H:%M").to_string();
+                    self.editing_hours = Some(id);
+                }
+            }
+            Message::ToggleDeviceHours(enabled) => self.save_device_hours(|hours| {
+                hours.enabled = enabled;
+            }),
+            Message::ToggleDeviceHoursDay(day) => self.save_device_hours(|hours| {
+                if let Some(index) = hours.days.iter().position(|d| *d == day) {
+                    hours.days.remove(index);
+                } else {
+                    hours.days.push(day);
+                    hours.days.sort_unstable();
+                }
+            }),
+            Message::DeviceHoursStartChanged(input) => {
+                if let Some(start) = schedule::parse_time(&input) {
+                    self.save_device_hours(|hours| hours.start = start);
+                }
+                self.hours_start_input = input;
+            }
+            Message::DeviceHoursEndChanged(input) => {
+                if let Some(end) = schedule::parse_time(&input) {
+                    self.save_device_hours(|hours| hours.end = end);
+                }
+                self.hours_end_input = input;
+            }
+            Message::SetDevicePriority(id, priority) => {
+                let mut devices = self.config.devices.clone();
+                devices.entry(id).or_default().priority = priority;
+                self.save_devices(devices);
+            }
+            Message::ForgetDevice(id) => {
+                if let Err(e) = self.history.forget(&id) {
+                    eprintln!("Failed to forget device: {}", e);
+                    self.error = Some(e);
+                    return Task::none();
+                }
+
+                let mut devices = self.config.devices.clone();
+                devices.remove(&id);
+                self.save_devices(devices);
+                let mut nicknames = self.config.nicknames.clone();
+                nicknames.remove(&id);
+                self.save_nicknames(nicknames);
+                if self.editing_hours.as_ref() == Some(&id) {
+                    self.editing_hours = None;
+                }
+                self.refresh_devices();
+            }
+            Message::RenameClient(id) => {
+                let current = self.config.nicknames.get(&id).cloned().unwrap_or_default();
+                self.renaming = Some((id, current));
+            }
+            Message::NicknameChanged(input) => {
+                if let Some((_, nickname)) = &mut self.renaming {
+                    *nickname = input;
+                }
+            }
+            Message::SaveNickname => {
+                let Some((id, nickname)) = self.renaming.take() else {
+                    return Task::none();
+                };
+                let mut nicknames = self.config.nicknames.clone();
+                // An empty nickname goes back to the announced name
+                match nickname.trim() {
+                    "" => nicknames.remove(&id),
+                    nickname => nicknames.insert(id, nickname.to_string()),
+                };
+                self.save_nicknames(nicknames);
+            }
+            Message::SelectConcurrentPolicy(index) => {
+                let policy = match index {
+                    0 => ConcurrentPolicy::Reject,
+                    1 => ConcurrentPolicy::Allow,
+                    _ => ConcurrentPolicy::Prompt,
+                };
+                self.save_setting(policy, Config::set_concurrent_clients, |config| {
+                    &mut config.concurrent_clients
+                });
+            }
+            Message::NotificationAction(action) => {
+                // Drop the current sender so the waiting one gets in when it retries
+                if action.as_deref() == Some(clients::TAKE_OVER) {
+                    self.receiver.restart();
+                }
+                if action.as_deref() == Some(SHOW_LOG) {
+                    let popup = match self.is_shown() {
+                        true => Task::none(),
+                        false => self.handle(Message::TogglePopup),
+                    };
+                    return Task::batch([popup, self.handle(Message::OpenPage(Page::Log))]);
+                }
+                if action.as_deref() == Some(RESTART) {
+                    return self.handle(Message::ToggleAirPlay(true));
+                }
+                if action.as_deref() == Some(SHOW_DUPLICATES) {
+                    let popup = match self.is_shown() {
+                        true => Task::none(),
+                        false => self.handle(Message::TogglePopup),
+                    };
+                    return Task::batch([popup, self.handle(Message::OpenPage(Page::Nearby))]);
+                }
+            }
+            Message::NotificationInvoked(key) => {
+                if key == clients::STOP_RECEIVER {
+                    return self.handle(Message::ToggleAirPlay(false));
+                }
+                if let Some(id) = key.strip_prefix(clients::BLOCK_DEVICE) {
+                    return self.handle(Message::SetDeviceAllowed(id.to_string(), false));
+                }
+            }
+            Message::ToggleNotifications(enabled) => self.save_notifications(NotificationConfig {
+                enabled,
+                ..self.config.notifications.clone()
+            }),
+            Message::GracePeriodChanged(input) => {
+                if let Ok(grace_period) = input.parse() {
+                    self.save_notifications(NotificationConfig {
+                        grace_period,
+                        ..self.config.notifications.clone()
+                    });
+                }
+                self.grace_period_input = input;
+            }
+            Message::ToggleWeeklySummary(weekly_summary) => {
+                self.save_notifications(NotificationConfig {
+                    weekly_summary,
+                    ..self.config.notifications.clone()
+                })
+            }
+            Message::ToggleSounds(sounds) => self.save_notifications(NotificationConfig {
+                sounds,
+                ..self.config.notifications.clone()
+            }),
+            Message::ConnectSoundChanged(connect_sound) => {
+                self.save_notifications(NotificationConfig {
+                    connect_sound,
+                    ..self.config.notifications.clone()
+                })
+            }
+            Message::DisconnectSoundChanged(disconnect_sound) => {
+                self.save_notifications(NotificationConfig {
+                    disconnect_sound,
+                    ..self.config.notifications.clone()
+                })
+            }
+            Message::GraceExpired => return self.on_grace_expired(),
+            Message::Notified(kind, id) => self.notifications.shown(kind, id),
+            Message::ToggleWakeOnDemand(wake_on_demand) => {
+                self.save_setting(wake_on_demand, Config::set_wake_on_demand, |config| {
+                    &mut config.wake_on_demand
+                });
+            }
+            Message::TogglePerformanceProfile(performance_profile) => {
+                self.save_setting(
+                    performance_profile,
+                    Config::set_performance_profile,
+                    |config| &mut config.performance_profile,
+                );
+            }
+            Message::UpdateConfig(config) => {
+                // E.g. from `airtray config`, rather than saved from here
+                let external = config != self.config;
+                self.receiver.config = config.clone();
+                if let Some(profile) = &self.device_profile {
+                    self.receiver.config.profile = profile.clone();
+                }
+                self.snapcast_port_input = config.audio.snapcast.port.to_string();
+                self.mqtt_port_input = config.integrations.mqtt.port.to_string();
+                self.config = config;
+                self.receiver.apply_nicknames();
+                self.refresh_upload_qr();
+                self.ensure_rest_token();
+                self.refresh_profiles();
+                self.invalid = validation::check(&self.config);
+                if external {
+                    self.refresh_inputs();
+                }
+            }
+            Message::NameChanged(name) => {
+                // Checked again with the popup
+                self.name_conflict = None;
+                self.save_setting(name, Config::set_name, |config| &mut config.name);
+            }
+            Message::CheckName => {
+                let name = self.config.receiver_name();
+                let responder = self.config.mdns;
+                return app_task(cosmic::iced::Task::perform(
+                    async move {
+                        let duplicates = mdns::duplicates(responder, &name).await;
+                        (name, duplicates)
+                    },
+                    |(name, duplicates)| Message::NameChecked(name, duplicates),
+                ));
+            }
+            Message::NameChecked(name, duplicates) => {
+                if name != self.config.receiver_name() {
+                    return Task::none();
+                }
+                let auto_rename = self.config.auto_rename;
+                // Name clashes that get renamed away aren't worth a warning
+                let appeared = duplicates.iter().find(|service| {
+                    !self.duplicates.iter().any(|known| known.host == service.host)
+                        && (service.has_local_id() || !auto_rename)
+                });
+                let warning = match appeared {
+                    Some(service) => self.notify_duplicate(service),
+                    None => Task::none(),
+                };
+                let host = duplicates
+                    .iter()
+                    .find(|service| service.name == name)
+                    .map(|service| service.host.clone());
+                self.duplicates = duplicates;
+
+                if let Some(host) = host.as_ref().filter(|_| auto_rename) {
+                    eprintln!("{} is already used by {}, renaming", name, host);
+                    return Task::batch([warning, self.handle(Message::RenameForConflict)]);
+                }
+                self.name_conflict = host;
+                return warning;
+            }
+            Message::RenameForConflict => {
+                let task = self.handle(Message::NameChanged(self.config.alternative_name()));
+                // Announce the new name right away rather than the next time
+                self.receiver.config = self.effective_config();
+                self.receiver.restart();
+                return task;
+            }
+            Message::ToggleAutoRename(auto_rename) => {
+                self.save_setting(auto_rename, Config::set_auto_rename, |config| {
+                    &mut config.auto_rename
+                });
+            }
+            Message::ToggleDuplicateAudio(enabled) => {
+                let audio = AudioConfig {
+                    duplicate: enabled,
+                    ..self.config.audio.clone()
+                };
+                self.save_audio(audio);
+            }
+            Message::SelectSecondarySink(index) => {
+                if let Some(sink) = self.sinks.get(index) {
+                    let audio = AudioConfig {
+                        secondary_sink: Some(sink.name.clone()),
+                        ..self.config.audio.clone()
+                    };
+                    self.save_audio(audio);
+                }
+            }
+            Message::SelectOutputSink(index) => {
+                let sink = match index {
+                    0 => None,
+                    // The chosen sink while unplugged stays chosen
+                    _ => match self.sinks.get(index - 1) {
+                        Some(sink) => Some(sink.name.clone()),
+                        None => self.config.audio.sink.clone(),
+                    },
+                };
+                self.save_audio(AudioConfig {
+                    sink,
+                    ..self.config.audio.clone()
+                });
+                self.refresh_audio_output_labels();
+            }
+            Message::SelectDelaySink(index) => {
+                if let Some(sink) = self.sinks.get(index) {
+                    self.delay_sink = Some(index);
+                    self.sink_delay_input = self.config.audio.sink_delay(&sink.name).to_string();
+                }
+            }
+            Message::SinkDelayChanged(input) => {
+                let sink = self.delay_sink.and_then(|index| self.sinks.get(index));
+                if let (Some(sink), Ok(delay)) = (sink, input.parse::<u32>()) {
+                    let mut sink_delays = self.config.audio.sink_delays.clone();
+                    if delay == 0 {
+                        sink_delays.remove(&sink.name);
+                    } else {
+                        sink_delays.insert(sink.name.clone(), delay);
+                    }
+                    self.save_audio(AudioConfig {
+                        sink_delays,
+                        ..self.config.audio.clone()
+                    });
+                }
+                self.sink_delay_input = input;
+            }
+            Message::ToggleSnapcast(enabled) => {
+                self.save_snapcast(SnapcastConfig {
+                    enabled,
+                    ..self.config.audio.snapcast.clone()
+                });
+                return self.refresh_snapcast_status();
+            }
+            Message::SnapcastHostChanged(host) => {
+                self.save_snapcast(SnapcastConfig {
+                    host,
+                    ..self.config.audio.snapcast.clone()
+                });
+            }
+            Message::SnapcastPortChanged(input) => {
+                if let Ok(port) = input.parse() {
+                    self.save_snapcast(SnapcastConfig {
+                        port,
+                        ..self.config.audio.snapcast.clone()
+                    });
+                }
+                self.snapcast_port_input = input;
+            }
+            Message::SnapcastStatus(status) => {
+                // Unless it was turned off while the check ran
+                if self.config.audio.snapcast.enabled {
+                    self.snapcast_status = Some(status);
+                }
+            }
+            Message::ToggleBluetoothSink(enabled) => {
+                return app_task(cosmic::iced::Task::perform(
+                    async move { bluez::set_sink_enabled(enabled).await.map(Some) },
+                    Message::BluetoothSink,
+                ));
+            }
+            Message::BluetoothSink(result) => match result {
+                Ok(enabled) => self.bluetooth_sink = enabled,
+                Err(e) => {
+                    eprintln!("Bluetooth sink: {}", e);
+                    self.error = Some(e);
                 }
             },
-        }
-        Task::none()
-    }
+            Message::Remote(request) => {
+                let enable = match request {
+                    Request::Start => true,
+                    Request::Stop => false,
+                    Request::Toggle => !self.receiver.state().is_active(),
+                    // Toggling an open popup would close it
+                    Request::ShowPopup if self.is_shown() => return Task::none(),
+                    Request::ShowPopup => return self.handle(Message::TogglePopup),
+                };
+                return self.handle(Message::ToggleAirPlay(enable));
+            }
+            Message::ToggleKdeConnect(kdeconnect) => {
+                self.save_integrations(IntegrationsConfig {
+                    kdeconnect,
+                    ..self.config.integrations.clone()
+                });
+            }
+            Message::SelectOutput(index) => {
+                let video = VideoConfig {
+                    output: index
+                        .checked_sub(1)
+                        .and_then(|index| self.outputs.get(index))
+                        .cloned()
+                        .unwrap_or_default(),
+                    ..self.config.video.clone()
+                };
+                self.save_video(video);
+            }
+            Message::SelectGpu(index) => {
+                let video = VideoConfig {
+                    gpu: index
+                        .checked_sub(1)
+                        .and_then(|index| self.gpus.get(index))
+                        .map(|gpu| gpu.node.clone())
+                        .unwrap_or_default(),
+                    ..self.config.video.clone()
+                };
+                self.save_video(video);
+            }
+            Message::TogglePip(enabled) => {
+                self.save_video(VideoConfig {
+                    pip: PipConfig {
+                        enabled,
+                        ..self.config.video.pip.clone()
+                    },
+                    ..self.config.video.clone()
+                });
+            }
+            Message::ToggleRandomPin(random_pin) => {
+                self.save_setting(random_pin, Config::set_random_pin, |config| {
+                    &mut config.random_pin
+                });
+                self.receiver.config = self.effective_config();
+                self.receiver.restart();
+            }
+            Message::ToggleKiosk(kiosk) => {
+                self.save_setting(kiosk, Config::set_kiosk, |config| &mut config.kiosk);
+            }
+            Message::ToggleActiveSeatOnly(active_seat_only) => {
+                self.save_setting(active_seat_only, Config::set_active_seat_only, |config| {
+                    &mut config.active_seat_only
+                });
+                if !active_seat_only && self.seat_paused {
+                    self.seat_paused = false;
+                    return self.handle(Message::ToggleAirPlay(true));
+                }
+            }
+            Message::SeatActive(active) => {
+                // Only the switch itself acts, so AirPlay can still be turned on by hand
+                if !active && self.receiver.state().is_active() {
+                    eprintln!("Switched to another login session, stopping the receiver");
+                    self.seat_paused = true;
+                    return self.handle(Message::ToggleAirPlay(false));
+                }
+                if active && self.seat_paused {
+                    self.seat_paused = false;
+                    return self.handle(Message::ToggleAirPlay(true));
+                }
+            }
+            Message::ToggleSignage(signage) => {
+                self.save_video(VideoConfig {
+                    signage,
+                    ..self.config.video.clone()
+                });
+            }
+            Message::SelectPipCorner(index) => {
+                let corner = match index {
+                    0 => Corner::TopLeft,
+                    1 => Corner::TopRight,
+                    2 => Corner::BottomLeft,
+                    _ => Corner::BottomRight,
+                };
+                self.save_video(VideoConfig {
+                    pip: PipConfig {
+                        corner,
+                        ..self.config.video.pip.clone()
+                    },
+                    ..self.config.video.clone()
+                });
+            }
+            Message::SelectPipSize(index) => {
+                let size = match index {
+                    0 => PipSize::Small,
+                    1 => PipSize::Medium,
+                    _ => PipSize::Large,
+                };
+                self.save_video(VideoConfig {
+                    pip: PipConfig {
+                        size,
+                        ..self.config.video.pip.clone()
+                    },
+                    ..self.config.video.clone()
+                });
+            }
+            Message::SelectQualityPreset(index) => {
+                // The last entry, "Custom", only shows what is set
+                if let Some(preset) = QualityPreset::ALL.get(index) {
+                    self.save_stream_quality(preset.quality());
+                }
+            }
+            Message::SelectCodec(index) => {
+                let codec = match index {
+                    0 => Codec::H264,
+                    _ => Codec::H265,
+                };
+                self.save_stream_quality(StreamQuality {
+                    codec,
+                    ..self.config.stream_quality()
+                });
+            }
+            Message::H265Available(available) => self.h265_available = Some(available),
+            Message::SelectDisplayServer(index) => {
+                let display_server = match index {
+                    0 => DisplayServer::Automatic,
+                    1 => DisplayServer::Wayland,
+                    _ => DisplayServer::X11,
+                };
+                self.save_video(VideoConfig {
+                    display_server,
+                    ..self.config.video.clone()
+                });
+            }
+            Message::Capabilities(capabilities) => self.capabilities = capabilities,
+            Message::MaxBitrateChanged(input) => {
+                let max_bitrate = if input.is_empty() { Ok(0) } else { input.parse() };
+                if let Ok(max_bitrate) = max_bitrate {
+                    self.save_video(VideoConfig {
+                        max_bitrate,
+                        ..self.config.video.clone()
+                    });
+                }
+                self.max_bitrate_input = input;
+            }
+            Message::SelectScaleMethod(index) => {
+                let method = match index {
+                    0 => ScaleMethod::Nearest,
+                    _ => ScaleMethod::Bilinear,
+                };
+                self.save_video(VideoConfig {
+                    scaling: ScalingConfig {
+                        method,
+                        ..self.config.video.scaling
+                    },
+                    ..self.config.video.clone()
+                });
+            }
+            Message::ToggleIntegerScaling(integer) => {
+                self.save_video(VideoConfig {
+                    scaling: ScalingConfig {
+                        integer,
+                        ..self.config.video.scaling
+                    },
+                    ..self.config.video.clone()
+                });
+            }
+            Message::BrightnessChanged(brightness) => self.save_picture(PictureConfig {
+                brightness,
+                ..self.config.video.picture
+            }),
+            Message::ContrastChanged(contrast) => self.save_picture(PictureConfig {
+                contrast,
+                ..self.config.video.picture
+            }),
+            Message::SaturationChanged(saturation) => self.save_picture(PictureConfig {
+                saturation,
+                ..self.config.video.picture
+            }),
+            Message::ResetPicture => self.save_picture(PictureConfig::default()),
+            Message::Frame(frame) => {
+                let handle =
+                    image::Handle::from_rgba(frame.size.width, frame.size.height, frame.pixels);
+                self.frame = Some((handle, frame.size));
+                self.frame_rate.tick();
+            }
+            Message::AudioLevel(level) => self.audio_level = level,
+            Message::NowPlaying(now_playing) => self.now_playing = now_playing,
+            Message::ToggleRecording(enabled) => self.save_recording(RecordingConfig {
+                enabled,
+                ..self.config.audio.recording.clone()
+            }),
+            Message::SelectRecordingFormat(index) => {
+                let format = match index {
+                    0 => RecordingFormat::Flac,
+                    _ => RecordingFormat::Ogg,
+                };
+                self.save_recording(RecordingConfig {
+                    format,
+                    ..self.config.audio.recording.clone()
+                });
+            }
+            Message::RecordingDirChanged(dir) => self.save_recording(RecordingConfig {
+                directory: (!dir.is_empty()).then(|| PathBuf::from(dir)),
+                ..self.config.audio.recording.clone()
+            }),
+            Message::ToggleEqualizer(enabled) => self.save_equalizer(Equalizer {
+                enabled,
+                ..self.config.equalizer()
+            }),
+            Message::BassChanged(bass) => self.save_equalizer(Equalizer {
+                bass,
+                ..self.config.equalizer()
+            }),
+            Message::TrebleChanged(treble) => self.save_equalizer(Equalizer {
+                treble,
+                ..self.config.equalizer()
+            }),
+            Message::ToggleLoudness(loudness) => self.save_equalizer(Equalizer {
+                loudness,
+                ..self.config.equalizer()
+            }),
+            Message::TogglePauseLocalMedia(pause_local_media) => {
+                self.save_audio(AudioConfig {
+                    pause_local_media,
+                    ..self.config.audio.clone()
+                });
+            }
+            Message::ToggleResumeLocalMedia(resume_local_media) => {
+                self.save_audio(AudioConfig {
+                    resume_local_media,
+                    ..self.config.audio.clone()
+                });
+            }
+            Message::PlayersPaused(result) => match result {
+                Ok(players) => self.paused_players.extend(players),
+                Err(e) => eprintln!("Failed to pause local media: {}", e),
+            },
+            Message::StreamVolumeChanged(percent) => {
+                self.save_stream_volume(StreamVolume {
+                    percent,
+                    ..self.config.stream_volume()
+                });
+            }
+            Message::ToggleStreamMute => {
+                let volume = self.config.stream_volume();
+                self.save_stream_volume(StreamVolume {
+                    muted: !volume.muted,
+                    ..volume
+                });
+            }
+            Message::ToggleEmbeddedMirror(embedded) => {
+                self.save_video(VideoConfig {
+                    embedded,
+                    ..self.config.video.clone()
+                });
+            }
+            Message::ToggleExtendedDisplay(extended_display) => {
+                self.save_video(VideoConfig {
+                    extended_display,
+                    ..self.config.video.clone()
+                });
+            }
+            Message::MirrorWindowClosed => self.mirror_dismissed = true,
+            Message::ToggleViewer(enabled) => {
+                let viewer = ViewerConfig {
+                    enabled,
+                    ..self.config.viewer.clone()
+                };
+                self.save_setting(viewer, Config::set_viewer, |config| &mut config.viewer);
+            }
+            Message::PhoneFound(client, result) => match result {
+                Ok(Some(battery)) => {
+                    self.phones.insert(client, battery);
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("KDE Connect lookup failed: {}", e),
+            },
+            Message::Control(command) => {
+                return app_task(cosmic::iced::Task::perform(
+                    dacp::send(command),
+                    Message::ControlSent,
+                ));
+            }
+            Message::ControlSent(result) => {
+                if let Err(e) = result {
+                    eprintln!("Remote control failed: {}", e);
+                    self.error = Some(e);
+                }
+            }
+            Message::ScanAppleTvs => {
+                self.scanning_apple_tvs = true;
+                return app_task(cosmic::iced::Task::perform(
+                    appletv::scan(),
+                    Message::AppleTvsFound,
+                ));
+            }
+            Message::AppleTvsFound(result) => {
+                self.scanning_apple_tvs = false;
+                match result {
+                    Ok(apple_tvs) => {
+                        // Keep the selection if the device is still around
+                        let selected = self
+                            .selected_apple_tv
+                            .and_then(|index| self.apple_tvs.get(index))
+                            .and_then(|old| apple_tvs.iter().position(|tv| tv.id == old.id));
+                        self.selected_apple_tv = selected.or((!apple_tvs.is_empty()).then_some(0));
+                        self.apple_tv_labels = apple_tvs.iter().map(|tv| tv.name.clone()).collect();
+                        self.apple_tvs = apple_tvs;
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to scan for Apple TVs: {}", e);
+                        self.error = Some(e);
+                    }
+                }
+            }
+            Message::SelectAppleTv(index) => self.selected_apple_tv = Some(index),
+            Message::ScanNearby => {
+                self.scanning_nearby = true;
+                return app_task(cosmic::iced::Task::perform(
+                    mdns::nearby(self.config.mdns),
+                    Message::NearbyFound,
+                ));
+            }
+            Message::NearbyFound(result) => {
+                self.scanning_nearby = false;
+                match result {
+                    Ok(nearby) => self.nearby = Some(nearby),
+                    Err(e) => {
+                        eprintln!("Failed to browse nearby devices: {}", e);
+                        self.error = Some(e);
+                    }
+                }
+            }
+            Message::PressRemoteKey(key) => {
+                let Some(apple_tv) = self.selected_apple_tv.and_then(|i| self.apple_tvs.get(i))
+                else {
+                    return Task::none();
+                };
+                return app_task(cosmic::iced::Task::perform(
+                    appletv::press(apple_tv.id.clone(), key),
+                    Message::ControlSent,
+                ));
+            }
+            Message::ToggleClipboardSharing(clipboard) => {
+                self.save_companion(CompanionConfig {
+                    clipboard,
+                    ..self.config.companion.clone()
+                });
+            }
+            Message::ToggleFileReceiving(files) => {
+                self.save_companion(CompanionConfig {
+                    files,
+                    ..self.config.companion.clone()
+                });
+            }
+            Message::DownloadDirChanged(dir) => {
+                self.save_companion(CompanionConfig {
+                    download_dir: (!dir.is_empty()).then(|| PathBuf::from(dir)),
+                    ..self.config.companion.clone()
+                });
+            }
+            Message::Companion(CompanionEvent::Clipboard(text)) => {
+                return app_task(cosmic::iced::clipboard::write(text));
+            }
+            Message::Companion(CompanionEvent::FileReceived(path)) => {
+                self.received_files.push(path);
+            }
+            Message::OpenReceivedFile(index) => {
+                if let Some(path) = self.received_files.get(index) {
+                    if let Err(e) = open::that_detached(path) {
+                        eprintln!("Failed to open {}: {}", path.display(), e);
+                    }
+                }
+            }
+            Message::WatchdogTick => {
+                let generation = self.receiver.generation();
+                return app_task(cosmic::iced::Task::perform(
+                    watchdog::check(
+                        self.config.receiver_name(),
+                        self.config.ip_version,
+                        self.config.mdns,
+                    ),
+                    move |result| Message::WatchdogChecked(generation, result),
+                ));
+            }
+            Message::WatchdogChecked(generation, result) => {
+                let running = matches!(self.receiver.state(), ReceiverState::Running { .. });
+                if generation != self.receiver.generation() || !running {
+                    return Task::none();
+                }
+                match result {
+                    Ok(()) => self.watchdog_failures = 0,
+                    Err(reason) => {
+                        self.watchdog_failures += 1;
+                        eprintln!("Receiver failed its health check: {}", reason);
+                        if self.watchdog_failures >= watchdog::MAX_FAILURES {
+                            eprintln!("Receiver stopped responding, restarting it");
+                            self.watchdog_failures = 0;
+                            self.receiver.restart();
+                        }
+                    }
+                }
+            }
+            Message::SampleUsage => {
+                self.usage = self.usage_sampler.sample();
+            }
+            Message::ScheduleTick => {
+                self.enforce_device_hours();
+                let schedule = &self.config.schedule;
+                if !schedule.enabled {
+                    return Task::none();
+                }
 
-    fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
-        Some(cosmic::applet::style())
-    }
+                // Only act when the window opens or closes, so the toggle stays usable
+                let open = schedule.is_open(Local::now());
+                if self.schedule_open != Some(open) {
+                    self.schedule_open = Some(open);
+                    return self.handle(Message::ToggleAirPlay(open));
+                }
+            }
+            Message::ToggleSchedule(enabled) => {
+                self.schedule_open = None;
+                self.save_schedule(ScheduleConfig {
+                    enabled,
+                    ..self.config.schedule.clone()
+                });
+                return self.handle(Message::ScheduleTick);
+            }
+            Message::ToggleScheduleDay(day) => {
+                let mut schedule = self.config.schedule.clone();
+                if let Some(index) = schedule.days.iter().position(|d| *d == day) {
+                    schedule.days.remove(index);
+                } else {
+                    schedule.days.push(day);
+                    schedule.days.sort_unstable();
+                }
+                self.save_schedule(schedule);
+            }
+            Message::ScheduleStartChanged(input) => {
+                if let Some(start) = schedule::parse_time(&input) {
+                    self.save_schedule(ScheduleConfig {
+                        start,
+                        ..self.config.schedule.clone()
+                    });
+                }
+                self.schedule_start_input = input;
+            }
+            Message::ScheduleEndChanged(input) => {
+                if let Some(end) = schedule::parse_time(&input) {
+                    self.save_schedule(ScheduleConfig {
+                        end,
+                        ..self.config.schedule.clone()
+                    });
+                }
+                self.schedule_end_input = input;
+            }
+            Message::NetworkChanged(network) => {
+                self.current_network = network;
+                self.lan_address = Some(viewer::lan_address());
+                self.refresh_upload_qr();
+                return self.apply_network_rules();
+            }
+            Message::ToggleNetworkProfiles(enabled) => {
+                self.save_networks(NetworkConfig {
+                    enabled,
+                    ..self.config.networks.clone()
+                });
+                return self.apply_network_rules();
+            }
+            Message::SelectProfile(index) => {
+                let name = match index {
+                    0 => String::new(),
+                    index => self.profile_labels.get(index).cloned().unwrap_or_default(),
+                };
+                self.save_selected_profile(name);
+            }
+            Message::AddProfile => {
+                let mut profiles = self.config.profiles.clone();
+                let name = (1..)
+                    .map(|n| fl!("profile-new", number = n))
+                    .find(|name| !profiles.iter().any(|profile| &profile.name == name))
+                    .unwrap_or_default();
+                profiles.push(Profile {
+                    name: name.clone(),
+                    ..Profile::default()
+                });
+                self.save_profiles(profiles);
+                self.save_selected_profile(name);
+            }
+            Message::RemoveProfile => {
+                let mut profiles = self.config.profiles.clone();
+                profiles.retain(|profile| profile.name != self.config.profile);
+                self.save_profiles(profiles);
+                self.save_selected_profile(String::new());
+            }
+            Message::ProfileNameChanged(name) => {
+                // Names identify profiles, so they have to stay unique
+                if name.is_empty() || self.config.profiles.iter().any(|profile| profile.name == name) {
+                    return Task::none();
+                }
+                let old = self.config.profile.clone();
+                let mut networks = self.config.networks.clone();
+                for rule in networks.rules.iter_mut().filter(|rule| rule.profile == old) {
+                    rule.profile = name.clone();
+                }
+                self.edit_profile(|profile| profile.name = name.clone());
+                self.save_networks(networks);
+                self.save_selected_profile(name);
+            }
+            Message::ProfilePinChanged(pin) => {
+                if pin.len() <= 4 && pin.chars().all(|c| c.is_ascii_digit()) {
+                    self.edit_profile(|profile| profile.pin = pin);
+                }
+            }
+            Message::ProfileResolutionChanged(resolution) => {
+                self.edit_profile(|profile| profile.resolution = resolution);
+            }
+            Message::ProfileFpsChanged(input) => {
+                if let Ok(fps) = input.parse() {
+                    self.edit_profile(|profile| profile.fps = fps);
+                } else if input.is_empty() {
+                    self.edit_profile(|profile| profile.fps = 0);
+                }
+                self.profile_fps_input = input;
+            }
+            Message::ProfileVideoPipelineChanged(pipeline) => {
+                self.edit_profile(|profile| profile.video_pipeline = pipeline);
+                return self.check_pipelines();
+            }
+            Message::ProfileAudioPipelineChanged(pipeline) => {
+                self.edit_profile(|profile| profile.audio_pipeline = pipeline);
+                return self.check_pipelines();
+            }
+            Message::PipelinesChecked(pipelines, missing) => {
+                if Some(pipelines) == self.pipelines() {
+                    self.missing_elements = missing;
+                }
+            }
+            Message::UseProfileOnNetwork => {
+                let Some(network) = self.current_network.clone() else {
+                    return Task::none();
+                };
+                let mut networks = self.config.networks.clone();
+                networks.rules.retain(|rule| rule.connection != network);
+                networks.rules.push(NetworkRule {
+                    connection: network,
+                    profile: self.config.profile.clone(),
+                });
+                self.save_networks(networks);
+            }
+            Message::RemoveNetworkRule(index) => {
+                let mut networks = self.config.networks.clone();
+                if index < networks.rules.len() {
+                    networks.rules.remove(index);
+                    self.save_networks(networks);
+                }
+            }
+            Message::SelectIpVersion(index) => {
+                let ip_version = match index {
+                    0 => IpVersion::Both,
+                    1 => IpVersion::Ipv4,
+                    _ => IpVersion::Ipv6,
+                };
+                self.save_setting(ip_version, Config::set_ip_version, |config| {
+                    &mut config.ip_version
+                });
+            }
+            Message::SelectMdnsResponder(index) => {
+                let responder = match index {
+                    0 => MdnsResponder::Avahi,
+                    _ => MdnsResponder::Builtin,
+                };
+                self.save_setting(responder, Config::set_mdns, |config| &mut config.mdns);
+            }
+            Message::SelectLockMethod(index) => {
+                let method = match index {
+                    0 => LockMethod::None,
+                    1 => LockMethod::Pin,
+                    _ => LockMethod::Polkit,
+                };
+                self.save_lock(LockConfig {
+                    method,
+                    ..self.config.lock.clone()
+                });
+            }
+            Message::LockPinChanged(pin) => self.save_lock(LockConfig {
+                pin,
+                ..self.config.lock.clone()
+            }),
+            Message::UnlockInputChanged(input) => self.unlock_input = input,
+            Message::Unlock => match self.config.lock_method() {
+                LockMethod::Pin => {
+                    let correct = self.unlock_input == self.config.lock.pin;
+                    self.unlock_input.clear();
+                    return self.handle(Message::Unlocked(Ok(correct)));
+                }
+                LockMethod::Polkit => {
+                    self.unlocking = true;
+                    return app_task(cosmic::iced::Task::perform(
+                        polkit::authorize_unlock(),
+                        Message::Unlocked,
+                    ));
+                }
+                LockMethod::None => return self.handle(Message::Unlocked(Ok(true))),
+            },
+            Message::Unlocked(result) => {
+                self.unlocking = false;
+                match result {
+                    Ok(true) => {
+                        self.unlocked = true;
+                        return self.handle(Message::OpenPage(self.unlock_page));
+                    }
+                    Ok(false) => self.unlock_failed = true,
+                    Err(e) => {
+                        eprintln!("Failed to unlock the settings: {}", e);
+                        self.error = Some(e);
+                    }
+                }
+            }
+            Message::ContainerChanged(container) => {
+                self.save_launch(LaunchConfig {
+                    container,
+                    ..self.config.launch.clone()
+                });
+                self.invalid = validation::check(&self.config);
+                return self.probe_capabilities();
+            }
+            Message::SearchPathsChanged(input) => {
+                let search_paths = input
+                    .split(':')
+                    .filter(|dir| !dir.trim().is_empty())
+                    .map(|dir| PathBuf::from(dir.trim()))
+                    .collect();
+                self.save_launch(LaunchConfig {
+                    search_paths,
+                    ..self.config.launch.clone()
+                });
+                self.search_paths_input = input;
+                self.invalid = validation::check(&self.config);
+                return self.probe_capabilities();
+            }
+            Message::UdpPortChanged(input) => {
+                let udp = match input.trim() {
+                    "" => Some(0),
+                    port => port.parse().ok().filter(|port| validation::udp_port(*port)),
+                };
+                if let Some(udp) = udp {
+                    self.save_ports(PortConfig { udp });
+                }
+                self.udp_port_input = input;
+            }
+            Message::ImportUxplayrc => match uxplayrc::load() {
+                Ok(imported) => return self.import(imported),
+                Err(e) => {
+                    eprintln!("Failed to import uxplayrc: {}", e);
+                    self.error = Some(e);
+                }
+            },
+            Message::ExportUxplayrc => match uxplayrc::export(&self.config) {
+                Ok(path) => self.uxplayrc_exported = Some(path),
+                Err(e) => {
+                    eprintln!("Failed to export uxplayrc: {}", e);
+                    self.error = Some(e);
+                }
+            },
+            Message::ExportHistory(format) => {
+                let dir = self.config.companion.download_dir();
+                let result = self.history.load().and_then(|sessions| {
+                    history::export(&sessions, &self.config.nicknames, format, &dir)
+                });
+                match result {
+                    Ok(path) => self.history_exported = Some(path),
+                    Err(e) => {
+                        eprintln!("Failed to export the history: {}", e);
+                        self.error = Some(e);
+                    }
+                }
+            }
+            Message::BackupKeys => {
+                let path = identity::backup_path(&self.config);
+                match identity::backup(&path) {
+                    Ok(()) => self.keys_backed_up = Some(path),
+                    Err(e) => {
+                        eprintln!("Failed to back up the receiver keys: {}", e);
+                        self.error = Some(e);
+                    }
+                }
+            }
+            Message::RestoreKeys => match identity::restore(&identity::backup_path(&self.config)) {
+                Ok(()) => {
+                    self.keys_restored = true;
+                    // UXPlay only reads the key when it starts
+                    self.receiver.restart();
+                }
+                Err(e) => {
+                    eprintln!("Failed to restore the receiver keys: {}", e);
+                    self.error = Some(e);
+                }
+            },
+            Message::ToggleDebug(debug) => {
+                self.save_setting(debug, Config::set_debug, |config| &mut config.debug);
+                // Takes effect right away, as it's wanted while something goes wrong
+                self.receiver.config = self.effective_config();
+                self.receiver.restart();
+            }
+            Message::SelectLogLevel(index) => {
+                self.log_level = match index {
+                    0 => log::Level::Error,
+                    1 => log::Level::Warning,
+                    _ => log::Level::Info,
+                };
+                self.log_lines = log::lines(self.log_level);
+            }
+            Message::RefreshLog => self.log_lines = log::lines(self.log_level),
+            Message::AskReset(section) => self.confirm_reset = Some(section),
+            Message::CancelReset => self.confirm_reset = None,
+            Message::Reset(section) => {
+                self.confirm_reset = None;
+                let result = self.config.reset(self.config_handler.as_ref(), section);
+                self.saved(result);
+                self.device_blocks = self.config.blocked_devices(Local::now());
+                self.refresh_inputs();
+                self.refresh_upload_qr();
+                self.refresh_profiles();
+                self.refresh_audio_output_labels();
+                self.invalid = validation::check(&self.config);
+            }
+            Message::SaveDebugBundle => {
+                return app_task(cosmic::iced::Task::perform(
+                    debug::save(self.config.clone()),
+                    Message::DebugBundleSaved,
+                ));
+            }
+            Message::DebugBundleSaved(result) => match result {
+                Ok(path) => self.debug_bundle = Some(path),
+                Err(e) => {
+                    eprintln!("Failed to save debug bundle: {}", e);
+                    self.error = Some(e);
+                }
+            },
+            Message::HookChanged(hook, command) => {
+                let mut integrations = self.config.integrations.clone();
+                integrations.hooks.set(hook, command);
+                self.save_integrations(integrations);
+            }
+            Message::ToggleWebhook(enabled) => {
+                self.save_integrations(IntegrationsConfig {
+                    webhook: WebhookConfig {
+                        enabled,
+                        ..self.config.integrations.webhook.clone()
+                    },
+                    ..self.config.integrations.clone()
+                });
+            }
+            Message::WebhookUrlChanged(input) => {
+                let url = input.trim();
+                if url.is_empty() || webhook::parse_url(url).is_ok() {
+                    self.save_integrations(IntegrationsConfig {
+                        webhook: WebhookConfig {
+                            url: url.to_string(),
+                            ..self.config.integrations.webhook.clone()
+                        },
+                        ..self.config.integrations.clone()
+                    });
+                }
+                self.webhook_url_input = input;
+            }
+            Message::ToggleRest(enabled) => {
+                self.save_integrations(IntegrationsConfig {
+                    rest: RestConfig {
+                        enabled,
+                        ..self.config.integrations.rest.clone()
+                    },
+                    ..self.config.integrations.clone()
+                });
+                self.ensure_rest_token();
+            }
+            Message::ToggleRestLan(lan) => {
+                self.save_integrations(IntegrationsConfig {
+                    rest: RestConfig {
+                        lan,
+                        ..self.config.integrations.rest.clone()
+                    },
+                    ..self.config.integrations.clone()
+                });
+            }
+            Message::MqttConnected(publisher) => {
+                self.mqtt = Some(publisher);
+                self.mqtt_status = None;
+            }
+            Message::ToggleMqtt(enabled) => {
+                if !enabled {
+                    self.mqtt = None;
+                }
+                self.save_mqtt(MqttConfig {
+                    enabled,
+                    ..self.config.integrations.mqtt.clone()
+                });
+            }
+            Message::MqttHostChanged(host) => {
+                self.save_mqtt(MqttConfig {
+                    host,
+                    ..self.config.integrations.mqtt.clone()
+                });
+            }
+            Message::MqttPortChanged(input) => {
+                if let Ok(port) = input.parse() {
+                    self.save_mqtt(MqttConfig {
+                        port,
+                        ..self.config.integrations.mqtt.clone()
+                    });
+                }
+                self.mqtt_port_input = input;
+            }
+            Message::MqttUsernameChanged(username) => {
+                self.save_mqtt(MqttConfig {
+                    username,
+                    ..self.config.integrations.mqtt.clone()
+                });
+            }
+            Message::MqttPasswordChanged(password) => {
+                self.save_mqtt(MqttConfig {
+                    password,
+                    ..self.config.integrations.mqtt.clone()
+                });
+            }
+            Message::OpenPage(page) => {
+                if page.is_protected()
+                    && self.config.lock_method() != LockMethod::None
+                    && !self.unlocked
+                {
+                    self.unlock_page = page;
+                    self.unlock_input.clear();
+                    self.unlock_failed = false;
+                    self.page = Page::Unlock;
+                    return Task::none();
+                }
+                if page == Page::Statistics {
+                    self.refresh_stats();
+                    self.usage_sampler.clear();
+                    self.usage = None;
+                }
+                if page == Page::Devices {
+                    self.refresh_devices();
+                }
+                if page == Page::Log {
+                    self.log_lines = log::lines(self.log_level);
+                }
+                self.page = page;
+                if page == Page::Settings {
+                    return self.check_pipelines();
+                }
+                if page == Page::Remote && self.apple_tvs.is_empty() {
+                    return self.handle(Message::ScanAppleTvs);
+                }
+                if page == Page::Nearby && !self.scanning_nearby {
+                    return self.handle(Message::ScanNearby);
+                }
+            }
+        }
+        Task::none()
+    }
+
+    /// Whether a sender is currently connected.
+    pub(crate) fn has_clients(&self) -> bool {
+        matches!(self.receiver.state(), ReceiverState::Running { clients } if !clients.is_empty())
+    }
+
+    fn save_companion(&mut self, mut companion: CompanionConfig) {
+        let mut failed = None;
+        if companion.token.is_empty() {
+            match companion::new_token() {
+                Ok(token) => companion.token = token,
+                Err(e) => failed = Some(e),
+            }
+        }
+        self.save_setting(companion, Config::set_companion, |config| {
+            &mut config.companion
+        });
+        self.refresh_upload_qr();
+        if let Some(error) = failed {
+            eprintln!("Failed to make a companion token: {}", error);
+            self.error = Some(error);
+        }
+    }
+
+    /// Encodes the upload page's address, if receiving files is enabled.
+    fn refresh_upload_qr(&mut self) {
+        let companion = &self.config.companion;
+        self.upload_qr = (companion.files && !companion.token.is_empty())
+            .then(|| companion::upload_url(self.lan_address(), companion.port, &companion.token))
+            .and_then(|url| qr_code::Data::new(url).ok());
+    }
+
+    /// Gives the control API a token if it is on without one, e.g. after
+    /// `airtray config set integrations.rest.enabled true`.
+    fn ensure_rest_token(&mut self) {
+        let rest = &self.config.integrations.rest;
+        if !rest.enabled || !rest.token.is_empty() {
+            return;
+        }
+        match companion::new_token() {
+            Ok(token) => self.save_integrations(IntegrationsConfig {
+                rest: RestConfig {
+                    token,
+                    ..rest.clone()
+                },
+                ..self.config.integrations.clone()
+            }),
+            Err(e) => {
+                eprintln!("Failed to make a control API token: {}", e);
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Address of this machine on the LAN, or localhost before it is known.
+    pub(crate) fn lan_address(&self) -> IpAddr {
+        self.lan_address.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+
+    /// Where the control API is served, if enabled. Without a token it stays
+    /// off, as anyone could use it.
+    fn rest_api(&self) -> Option<Api> {
+        let rest = &self.config.integrations.rest;
+        (rest.enabled && !rest.token.is_empty()).then(|| Api {
+            port: rest.port,
+            token: rest.token.clone(),
+            lan: rest.lan,
+        })
+    }
+
+    /// What the companion endpoint should serve, or `None` if nothing is
+    /// enabled. Without a token it stays off, as anyone could use it.
+    fn companion_endpoint(&self) -> Option<Endpoint> {
+        let companion = &self.config.companion;
+        let enabled = companion.clipboard || companion.files;
+        (enabled && !companion.token.is_empty()).then(|| Endpoint {
+            port: companion.port,
+            token: companion.token.clone(),
+            clipboard: companion.clipboard,
+            download_dir: companion.files.then(|| companion.download_dir()),
+        })
+    }
+
+    /// Looks up a newly connected client in KDE Connect, if enabled.
+    fn greet_phone(&self, event: &ReceiverEvent) -> Task<Message> {
+        let ReceiverEvent::Backend {
+            event: BackendEvent::ClientConnected { id, name, .. },
+            ..
+        } = event
+        else {
+            return Task::none();
+        };
+
+        if !self.config.integrations.kdeconnect || self.phones.contains_key(id) {
+            return Task::none();
+        }
+
+        let id = id.clone();
+        app_task(cosmic::iced::Task::perform(
+            kdeconnect::greet(name.clone(), fl!("kdeconnect-ping")),
+            move |result| Message::PhoneFound(id.clone(), result),
+        ))
+    }
+
+    /// Exposes the receiver state on D-Bus and sends it to the MQTT broker
+    /// when it changed.
+    fn publish_status(&mut self) {
+        let (state, clients) = match self.receiver.state() {
+            ReceiverState::Stopped => ("stopped", Vec::new()),
+            ReceiverState::Standby => ("standby", Vec::new()),
+            ReceiverState::Starting => ("starting", Vec::new()),
+            ReceiverState::Running { clients } => (
+                "running",
+                clients.iter().map(|client| client.name.clone()).collect(),
+            ),
+            ReceiverState::Stopping => ("stopping", Vec::new()),
+            ReceiverState::Failed { .. } => ("failed", Vec::new()),
+        };
+        service::publish(service::Status {
+            state: state.to_string(),
+            clients: clients.clone(),
+            text: self.status_text(),
+        });
+
+        let Some(publisher) = &self.mqtt else {
+            return;
+        };
+        let now_playing = self
+            .now_playing
+            .as_ref()
+            .map(|now_playing| mqtt::Track {
+                title: now_playing.title.clone(),
+                artist: now_playing.artist.clone(),
+                album: now_playing.album.clone(),
+            });
+        let status = mqtt::Status {
+            state,
+            clients,
+            now_playing,
+        };
+
+        if self.mqtt_status.as_ref() != Some(&status) {
+            publisher.publish(status.clone());
+            self.mqtt_status = Some(status);
+        }
+    }
+
+    /// Writes sessions that just ended to the history.
+    fn record_sessions(&mut self) {
+        if let ReceiverState::Running { clients } = self.receiver.state() {
+            self.phones
+                .retain(|id, _| clients.iter().any(|client| &client.id == id));
+        } else {
+            self.phones.clear();
+        }
+
+        for session in self.receiver.take_ended() {
+            if let Err(e) = self.history.append(&session) {
+                eprintln!("Failed to record session: {}", e);
+                self.error = Some(e);
+            }
+        }
+    }
+
+    fn refresh_devices(&mut self) {
+        match self.history.load() {
+            Ok(sessions) => self.known_devices = history::known_devices(&sessions),
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn refresh_stats(&mut self) {
+        match self.history.load() {
+            Ok(sessions) => {
+                self.stats =
+                    Stats::compute(&sessions, &self.config.nicknames, chrono::Local::now())
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Creates the applet around a specific backend, for driving it in tests.
+    #[cfg(test)]
+    pub(crate) fn with_backend(backend: Box<dyn Backend>, history: History) -> Self {
+        AirTray {
+            receiver: Receiver::new(backend),
+            history,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the controls are on screen: always in a window, else while
+    /// the popup is open.
+    fn is_shown(&self) -> bool {
+        self.mode == Mode::Window || self.popup.is_some()
+    }
+
+    /// Opens the controls in a window from the tray icon, or closes them.
+    #[cfg(feature = "tray")]
+    fn toggle_tray_window(&mut self) -> Task<Message> {
+        use cosmic::iced::{window, Size};
+
+        if let Some(id) = self.popup.take() {
+            return app_task(window::close(id));
+        }
+        let shown = self.on_shown();
+        let (id, open) = window::open(window::Settings {
+            size: Size::new(400.0, 720.0),
+            min_size: Some(Size::new(300.0, 200.0)),
+            ..Default::default()
+        });
+        self.popup = Some(id);
+        Task::batch([app_task(open.discard()), shown])
+    }
+
+    /// Brings what the controls show up to date as they come on screen.
+    fn on_shown(&mut self) -> Task<Message> {
+        self.page = Page::Main;
+        self.unlocked = false;
+        self.refresh_sinks();
+        self.refresh_outputs();
+        let snapcast = self.refresh_snapcast_status();
+        let bluetooth = app_task(cosmic::iced::Task::perform(
+            bluez::sink_enabled(),
+            Message::BluetoothSink,
+        ));
+        let check_name = self.handle(Message::CheckName);
+        Task::batch([bluetooth, snapcast, check_name])
+    }
+
+    /// Whether the airplay toggle is shown as on.
+    #[cfg(test)]
+    pub(crate) fn airplay_enabled(&self) -> bool {
+        self.receiver.state().is_active()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn receiver_state(&self) -> &ReceiverState {
+        self.receiver.state()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn receiver_generation(&self) -> u64 {
+        self.receiver.generation()
+    }
+
+    /// Tells that the receiver failed, with what it last wrote to stderr and a
+    /// button opening the popup, which shows the same.
+    fn notify_failure(&self, summary: String, output: String) -> Task<Message> {
+        let actions = vec![
+            (RESTART, fl!("notification-restart")),
+            (SHOW_LOG, fl!("notification-show-log")),
+        ];
+        let ask = notifications::ask(summary, output, actions);
+        app_task(cosmic::iced::Task::perform(ask, |result| {
+            Message::NotificationAction(result.unwrap_or_else(|e| {
+                eprintln!("Failed to show notification: {}", e);
+                None
+            }))
+        }))
+    }
+
+    /// Warns that `service` claims to be this receiver, with a button listing
+    /// all such receivers.
+    fn notify_duplicate(&self, service: &Service) -> Task<Message> {
+        let body = if service.has_local_id() {
+            fl!("duplicate-id", host = service.host.as_str())
+        } else {
+            fl!("duplicate-name", host = service.host.as_str())
+        };
+        let actions = vec![(SHOW_DUPLICATES, fl!("duplicates-show"))];
+        let ask = notifications::ask(fl!("duplicates-notification"), body, actions);
+        app_task(cosmic::iced::Task::perform(ask, |result| {
+            Message::NotificationAction(result.unwrap_or_else(|e| {
+                eprintln!("Failed to show notification: {}", e);
+                None
+            }))
+        }))
+    }
+
+    /// Whether the receiver was turned on but can't be found by senders yet.
+    fn is_starting(&self) -> bool {
+        match self.receiver.state() {
+            ReceiverState::Starting => true,
+            ReceiverState::Running { clients } => clients.is_empty() && !self.ready,
+            _ => false,
+        }
+    }
+
+    /// One-line description of the receiver state for the popup.
+    fn status_text(&self) -> String {
+        match self.receiver.state() {
+            ReceiverState::Stopped => fl!("status-stopped"),
+            ReceiverState::Standby => fl!("status-standby"),
+            ReceiverState::Starting => fl!("status-starting"),
+            ReceiverState::Running { clients } if clients.is_empty() && !self.ready => {
+                fl!("status-announcing")
+            }
+            ReceiverState::Running { clients } if clients.is_empty() => fl!("status-waiting"),
+            ReceiverState::Running { clients } => {
+                fl!("status-streaming", clients = clients.len())
+            }
+            ReceiverState::Stopping => fl!("status-stopping"),
+            ReceiverState::Failed { reason } => reason.user_message(),
+        }
+    }
+
+    /// Re-reads the list of available sinks for the duplication dropdown.
+    fn refresh_sinks(&mut self) {
+        match audio::list_sinks() {
+            Ok(sinks) => {
+                self.sink_labels = sinks.iter().map(|sink| sink.description.clone()).collect();
+                // Start out with the sink the audio plays on
+                let default = audio::default_sink().ok();
+                self.delay_sink = sinks
+                    .iter()
+                    .position(|sink| Some(&sink.name) == default.as_ref())
+                    .or((!sinks.is_empty()).then_some(0));
+                self.sink_delay_input = self
+                    .delay_sink
+                    .map(|index| self.config.audio.sink_delay(&sinks[index].name).to_string())
+                    .unwrap_or_default();
+                self.sinks = sinks;
+                self.refresh_audio_output_labels();
+            }
+            Err(e) => {
+                eprintln!("Failed to list audio sinks: {}", e);
+                self.error = Some(e);
+            }
+        }
+    }
+
+    fn refresh_audio_output_labels(&mut self) {
+        self.audio_output_labels = std::iter::once(fl!("output-sink-default"))
+            .chain(self.sink_labels.iter().cloned())
+            .collect();
+        if let Some(sink) = &self.config.audio.sink {
+            if !self.sinks.iter().any(|known| &known.name == sink) {
+                self.audio_output_labels.push(fl!("output-sink-missing", name = sink.as_str()));
+            }
+        }
+    }
+
+    /// Index of the chosen sink in `audio_output_labels`.
+    fn selected_output(&self) -> usize {
+        match &self.config.audio.sink {
+            Some(sink) => self
+                .sinks
+                .iter()
+                .position(|known| &known.name == sink)
+                .unwrap_or(self.sinks.len())
+                + 1,
+            None => 0,
+        }
+    }
+
+    /// Checks in the background whether the Snapcast server is reachable, if
+    /// multi-room output is enabled.
+    fn refresh_snapcast_status(&mut self) -> Task<Message> {
+        let snapcast = &self.config.audio.snapcast;
+        if !snapcast.enabled {
+            self.snapcast_status = None;
+            return Task::none();
+        }
+        app_task(cosmic::iced::Task::perform(
+            snapcast::probe(snapcast.clone()),
+            Message::SnapcastStatus,
+        ))
+    }
+
+    fn save_snapcast(&mut self, snapcast: SnapcastConfig) {
+        let audio = AudioConfig {
+            snapcast,
+            ..self.config.audio.clone()
+        };
+        self.save_audio(audio);
+    }
+
+    /// Persists new audio settings. They take effect the next time UXPlay starts.
+    fn save_audio(&mut self, audio: AudioConfig) {
+        self.save_setting(audio, Config::set_audio, |config| &mut config.audio);
+    }
+
+    /// Turns the receiver on with the current network's profile, or off on
+    /// networks without a rule.
+    fn apply_network_rules(&mut self) -> Task<Message> {
+        let networks = &self.config.networks;
+        if !networks.enabled {
+            return Task::none();
+        }
+
+        let rule = self.current_network.as_ref().and_then(|network| {
+            networks
+                .rules
+                .iter()
+                .find(|rule| &rule.connection == network)
+        });
+
+        match rule.cloned() {
+            Some(rule) => {
+                if rule.profile != self.config.profile {
+                    self.save_selected_profile(rule.profile);
+                }
+                self.handle(Message::ToggleAirPlay(true))
+            }
+            None => self.handle(Message::ToggleAirPlay(false)),
+        }
+    }
+
+    /// Reacts to the first sender connecting and the last one leaving.
+    fn on_session_change(&mut self) -> Task<Message> {
+        let in_session = self.in_grace_or_session();
+        if in_session == self.in_session {
+            return Task::none();
+        }
+        self.in_session = in_session;
+        let webhook = self.call_webhook(if in_session {
+            WebhookEvent::SessionStarted
+        } else {
+            WebhookEvent::SessionEnded
+        });
+
+        let audio = &self.config.audio;
+        let media = if in_session && audio.pause_local_media {
+            app_task(cosmic::iced::Task::perform(
+                mpris::pause_players(),
+                Message::PlayersPaused,
+            ))
+        } else if !in_session && audio.resume_local_media && !self.paused_players.is_empty() {
+            let players = std::mem::take(&mut self.paused_players);
+            app_task(
+                cosmic::iced::Task::future(async move {
+                    if let Err(e) = mpris::resume_players(players).await {
+                        eprintln!("Failed to resume local media: {}", e);
+                    }
+                })
+                .discard(),
+            )
+        } else {
+            self.paused_players.clear();
+            Task::none()
+        };
+        Task::batch([webhook, media])
+    }
+
+    /// Runs the start or stop hook when the receiver comes up or goes down.
+    /// Restarting it, e.g. for a device profile, doesn't count.
+    fn on_running_change(&mut self) -> Task<Message> {
+        let running = match self.receiver.state() {
+            ReceiverState::Starting | ReceiverState::Running { .. } => true,
+            ReceiverState::Stopping => self.receiver.is_restarting(),
+            _ => false,
+        };
+        if running == self.running {
+            return Task::none();
+        }
+        self.running = running;
+        self.tell_outputs(if running {
+            PluginEvent::Started
+        } else {
+            PluginEvent::Stopped
+        });
+        self.run_hook(if running { Hook::Start } else { Hook::Stop }, None)
+    }
+
+    /// Calls the webhook when the receiver fails.
+    fn on_failure(&mut self) -> Task<Message> {
+        let reason = match self.receiver.state() {
+            ReceiverState::Failed { reason } => Some(reason.to_string()),
+            _ => None,
+        };
+        if reason.is_some() == self.failed {
+            return Task::none();
+        }
+        self.failed = reason.is_some();
+        match reason {
+            Some(reason) => self.call_webhook(WebhookEvent::Error(reason)),
+            None => Task::none(),
+        }
+    }
+
+    /// Calls the webhook, if set up, in the background.
+    fn call_webhook(&self, event: WebhookEvent) -> Task<Message> {
+        let webhook = &self.config.integrations.webhook;
+        if !webhook.enabled || webhook.url.trim().is_empty() {
+            return Task::none();
+        }
+
+        let clients: Vec<String> =
+            self.clients().iter().map(|client| client.name.clone()).collect();
+        let body = webhook::payload(&event, &self.config.receiver_name(), &clients);
+        let call = webhook::call(webhook.url.trim().to_string(), body);
+        app_task(
+            cosmic::iced::Task::future(async move {
+                if let Err(e) = call.await {
+                    eprintln!("Failed to call the webhook: {}", e);
+                }
+            })
+            .discard(),
+        )
+    }
+
+    /// Hands `event` to the output modules.
+    pub(super) fn tell_outputs(&mut self, event: PluginEvent) {
+        let config = self.effective_config();
+        for output in &mut self.output_modules {
+            if let Err(e) = output.on_event(&event, &config) {
+                eprintln!("Output {} failed on {}: {}", output.name(), event, e);
+            }
+        }
+    }
+
+    /// Runs the user's command for `hook` in the background, with the id and
+    /// name of the sender it is about.
+    pub(super) fn run_hook(&self, hook: Hook, client: Option<(&str, &str)>) -> Task<Message> {
+        let receiver = self.config.receiver_name();
+        let run = hooks::run(&self.config.integrations.hooks, hook, &receiver, client);
+        app_task(cosmic::iced::Task::future(run).discard())
+    }
+
+    fn save_devices(&mut self, devices: BTreeMap<String, DeviceConfig>) {
+        self.save_setting(devices, Config::set_devices, |config| &mut config.devices);
+    }
+
+    /// The hours of the device with `id`, the defaults if it has none yet.
+    fn device_hours(&self, id: &str) -> ScheduleConfig {
+        self.config
+            .devices
+            .get(id)
+            .map(|device| device.schedule.clone())
+            .unwrap_or_default()
+    }
+
+    /// Changes the hours of the device being edited.
+    fn save_device_hours(&mut self, change: impl FnOnce(&mut ScheduleConfig)) {
+        let Some(id) = self.editing_hours.clone() else {
+            return;
+        };
+        let mut devices = self.config.devices.clone();
+        change(&mut devices.entry(id).or_default().schedule);
+        self.save_devices(devices);
+    }
+
+    /// Time left until the session's limit, if it has one.
+    pub(crate) fn session_left(&self) -> Option<chrono::Duration> {
+        let limit = self.config.session_limit;
+        let start = self.clients().iter().map(|client| client.connected_at).min()?;
+        (limit > 0).then(|| start + chrono::Duration::minutes(limit.into()) - Local::now())
+    }
+
+    /// Warns the sender a minute before the session's time is up, and ends
+    /// the session when it is.
+    fn enforce_session_limit(&mut self) -> Task<Message> {
+        let Some(left) = self.session_left() else {
+            self.session_limit_warned = false;
+            return Task::none();
+        };
+
+        if left <= chrono::Duration::zero() {
+            eprintln!("Ending the session after {} minutes", self.config.session_limit);
+            self.session_limit_warned = false;
+            // Disconnects everyone but keeps the receiver available
+            self.receiver.restart();
+            Task::none()
+        } else if left.to_std().unwrap_or_default() <= SESSION_LIMIT_WARNING
+            && !self.session_limit_warned
+        {
+            self.session_limit_warned = true;
+            let warn = notifications::notify(
+                fl!("session-ending"),
+                fl!("session-ending-body"),
+                0,
+                Vec::new(),
+            );
+            app_task(
+                cosmic::iced::Task::future(async move {
+                    if let Err(e) = warn.await {
+                        eprintln!("Failed to show notification: {}", e);
+                    }
+                })
+                .discard(),
+            )
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Sums up last week's streaming in a notification on Monday morning, or
+    /// when the applet first runs later in the week.
+    fn weekly_summary(&mut self) -> Task<Message> {
+        let now = Local::now();
+        if !self.config.notifications.weekly_summary
+            || (now.weekday() == Weekday::Mon && now.hour() < SUMMARY_HOUR)
+        {
+            return Task::none();
+        }
+        let sessions = match self.history.load() {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                eprintln!("Failed to read the history: {}", e);
+                return Task::none();
+            }
+        };
+        let summary = history::last_week(&sessions, &self.config.nicknames, now);
+        let nothing = summary.total <= chrono::Duration::zero();
+        if nothing || self.history.summarized() == Some(summary.week) {
+            return Task::none();
+        }
+        if let Err(e) = self.history.set_summarized(summary.week) {
+            // Rather none than one every check
+            eprintln!("Failed to record the weekly summary: {}", e);
+            return Task::none();
+        }
+
+        let devices: Vec<String> = summary
+            .top_devices
+            .iter()
+            .map(|(name, streamed)| format!("{} ({})", name, pages::format_duration(*streamed)))
+            .collect();
+        let body = fl!(
+            "weekly-summary-body",
+            total = pages::format_duration(summary.total),
+            devices = devices.join(", ")
+        );
+        let notification = notifications::notify(fl!("weekly-summary-title"), body, 0, Vec::new());
+        app_task(
+            cosmic::iced::Task::future(async move {
+                if let Err(e) = notification.await {
+                    eprintln!("Failed to show notification: {}", e);
+                }
+            })
+            .discard(),
+        )
+    }
+
+    /// Restarts the receiver when a device's hours begin or end, as UXPlay
+    /// only reads the devices to refuse when it starts. A session of an
+    /// allowed device is not cut short for it; the check repeats until it ends.
+    fn enforce_device_hours(&mut self) {
+        let blocked = self.config.blocked_devices(Local::now());
+        if blocked == self.device_blocks {
+            return;
+        }
+        if self.clients().iter().any(|client| !blocked.contains(&client.id)) {
+            return;
+        }
+
+        // Stopped or on standby, it refuses the right devices once started
+        self.receiver.restart();
+        self.device_blocks = blocked;
+    }
+
+    fn save_nicknames(&mut self, nicknames: BTreeMap<String, String>) {
+        self.save_setting(nicknames, Config::set_nicknames, |config| {
+            &mut config.nicknames
+        });
+        self.receiver.apply_nicknames();
+    }
+
+    fn save_notifications(&mut self, notifications: NotificationConfig) {
+        self.save_setting(notifications, Config::set_notifications, |config| {
+            &mut config.notifications
+        });
+    }
+
+    fn save_recording(&mut self, recording: RecordingConfig) {
+        self.save_audio(AudioConfig {
+            recording,
+            ..self.config.audio.clone()
+        });
+    }
+
+    /// Saves the stream volume into the selected profile, or the audio
+    /// settings without one.
+    fn save_stream_volume(&mut self, volume: StreamVolume) {
+        if self.config.active_profile().is_some() {
+            self.edit_profile(|profile| profile.volume = volume);
+        } else {
+            self.save_audio(AudioConfig {
+                volume,
+                ..self.config.audio.clone()
+            });
+        }
+    }
+
+    /// Saves the equalizer into the selected profile, or the audio settings
+    /// without one.
+    fn save_equalizer(&mut self, equalizer: Equalizer) {
+        if self.config.active_profile().is_some() {
+            self.edit_profile(|profile| profile.equalizer = equalizer);
+        } else {
+            self.save_audio(AudioConfig {
+                equalizer,
+                ..self.config.audio.clone()
+            });
+        }
+    }
+
+    /// Saves the stream quality into the selected profile, or the video
+    /// settings without one.
+    fn save_stream_quality(&mut self, quality: StreamQuality) {
+        if self.config.active_profile().is_some() {
+            self.edit_profile(|profile| {
+                profile.resolution = quality.resolution;
+                profile.fps = quality.fps;
+                profile.codec = quality.codec;
+            });
+        } else {
+            self.save_video(VideoConfig {
+                quality,
+                ..self.config.video.clone()
+            });
+        }
+    }
+
+    /// Takes over the settings of a uxplayrc, with those that belong to a
+    /// profile in a profile of their own, which gets selected.
+    fn import(&mut self, imported: Imported) -> Task<Message> {
+        let mut tasks = Vec::new();
+        if let Some(name) = imported.name {
+            tasks.push(self.handle(Message::NameChanged(name)));
+        }
+        if let Some(port) = imported.udp_port {
+            tasks.push(self.handle(Message::UdpPortChanged(port.to_string())));
+        }
+        for id in imported.blocked {
+            tasks.push(self.handle(Message::SetDeviceAllowed(id, false)));
+        }
+        if imported.debug && !self.config.debug {
+            tasks.push(self.handle(Message::ToggleDebug(true)));
+        }
+
+        let name = fl!("profile-imported");
+        let mut profiles = self.config.profiles.clone();
+        profiles.retain(|profile| profile.name != name);
+        profiles.push(Profile {
+            name: name.clone(),
+            ..imported.profile
+        });
+        self.save_profiles(profiles);
+        self.save_selected_profile(name);
+
+        self.uxplayrc_skipped = Some(imported.skipped);
+        Task::batch(tasks)
+    }
+
+    /// Video and audio pipeline overrides of the selected profile.
+    fn pipelines(&self) -> Option<(String, String)> {
+        self.config
+            .active_profile()
+            .map(|profile| (profile.video_pipeline.clone(), profile.audio_pipeline.clone()))
+    }
+
+    /// Looks for elements of the selected profile's pipelines that aren't
+    /// installed.
+    fn check_pipelines(&mut self) -> Task<Message> {
+        self.missing_elements.clear();
+        let Some((video, audio)) = self.pipelines() else {
+            return Task::none();
+        };
+        app_task(cosmic::iced::Task::perform(
+            async move {
+                let mut missing = video::missing_elements(video.clone()).await;
+                missing.extend(video::missing_elements(audio.clone()).await);
+                ((video, audio), missing)
+            },
+            |(pipelines, missing)| Message::PipelinesChecked(pipelines, missing),
+        ))
+    }
+
+    /// Changes the selected profile.
+    fn edit_profile(&mut self, edit: impl FnOnce(&mut Profile)) {
+        let mut profiles = self.config.profiles.clone();
+        if let Some(profile) = profiles
+            .iter_mut()
+            .find(|profile| profile.name == self.config.profile)
+        {
+            edit(profile);
+            self.save_profiles(profiles);
+        }
+    }
+
+    fn save_profiles(&mut self, profiles: Vec<Profile>) {
+        self.save_setting(profiles, Config::set_profiles, |config| {
+            &mut config.profiles
+        });
+        self.refresh_profiles();
+    }
+
+    /// Selects the profile used on the next start.
+    fn save_selected_profile(&mut self, profile: String) {
+        self.save_setting(profile, Config::set_profile, |config| &mut config.profile);
+        self.refresh_profiles();
+    }
+
+    fn save_networks(&mut self, networks: NetworkConfig) {
+        self.save_setting(networks, Config::set_networks, |config| {
+            &mut config.networks
+        });
+    }
+
+    /// Puts the text fields back in line with the settings, e.g. after a reset.
+    fn refresh_inputs(&mut self) {
+        let config = &self.config;
+        self.snapcast_port_input = config.audio.snapcast.port.to_string();
+        self.mqtt_port_input = config.integrations.mqtt.port.to_string();
+        self.grace_period_input = config.notifications.grace_period.to_string();
+        self.max_bitrate_input = match config.video.max_bitrate {
+            0 => String::new(),
+            max_bitrate => max_bitrate.to_string(),
+        };
+        self.session_limit_input = match config.session_limit {
+            0 => String::new(),
+            minutes => minutes.to_string(),
+        };
+        self.schedule_start_input = config.schedule.start.format("%H:%M").to_string();
+        self.schedule_end_input = config.schedule.end.format("%H:%M").to_string();
+        self.webhook_url_input = config.integrations.webhook.url.clone();
+        self.udp_port_input = match config.ports.udp {
+            0 => String::new(),
+            port => port.to_string(),
+        };
+        self.search_paths_input = std::env::join_paths(&config.launch.search_paths)
+            .map(|paths| paths.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.sink_delay_input = match self.delay_sink.and_then(|index| self.sinks.get(index)) {
+            Some(sink) => config.audio.sink_delay(&sink.name).to_string(),
+            None => String::new(),
+        };
+    }
+
+    fn refresh_profiles(&mut self) {
+        self.profile_labels = std::iter::once(fl!("profile-none"))
+            .chain(self.config.profiles.iter().map(|profile| profile.name.clone()))
+            .collect();
+        self.device_profile_labels = std::iter::once(fl!("device-profile-selected"))
+            .chain(self.config.profiles.iter().map(|profile| profile.name.clone()))
+            .collect();
+        self.profile_fps_input = match self.config.active_profile() {
+            Some(profile) if profile.fps > 0 => profile.fps.to_string(),
+            _ => String::new(),
+        };
+    }
+
+    fn save_video(&mut self, video: VideoConfig) {
+        self.save_setting(video, Config::set_video, |config| &mut config.video);
+    }
+
+    fn save_picture(&mut self, picture: PictureConfig) {
+        self.save_video(VideoConfig {
+            picture,
+            ..self.config.video.clone()
+        });
+    }
+
+    fn refresh_outputs(&mut self) {
+        self.outputs = video::outputs();
+        self.output_labels = std::iter::once(fl!("output-automatic"))
+            .chain(self.outputs.iter().cloned())
+            .collect();
+        self.gpus = gpu::gpus();
+        self.gpu_labels = std::iter::once(fl!("gpu-default"))
+            .chain(self.gpus.iter().map(|gpu| gpu.name.clone()))
+            .collect();
+    }
+
+    fn save_schedule(&mut self, schedule: ScheduleConfig) {
+        self.save_setting(schedule, Config::set_schedule, |config| {
+            &mut config.schedule
+        });
+    }
+
+    /// Asks the UXPlay that will be started what it supports.
+    fn probe_capabilities(&self) -> Task<Message> {
+        app_task(cosmic::iced::Task::perform(
+            capabilities::probe(self.config.launch.clone()),
+            Message::Capabilities,
+        ))
+    }
+
+    fn save_ports(&mut self, ports: PortConfig) {
+        self.save_setting(ports, Config::set_ports, |config| &mut config.ports);
+    }
+
+    fn save_launch(&mut self, launch: LaunchConfig) {
+        self.save_setting(launch, Config::set_launch, |config| &mut config.launch);
+    }
+
+    fn save_lock(&mut self, lock: LockConfig) {
+        self.save_setting(lock, Config::set_lock, |config| &mut config.lock);
+    }
+
+    fn save_mqtt(&mut self, mqtt: MqttConfig) {
+        self.save_integrations(IntegrationsConfig {
+            mqtt,
+            ..self.config.integrations.clone()
+        });
+    }
+
+    fn save_integrations(&mut self, integrations: IntegrationsConfig) {
+        self.save_setting(integrations, Config::set_integrations, |config| {
+            &mut config.integrations
+        });
+    }
+
+    /// Persists one setting through its generated setter, or only keeps it in
+    /// memory without a config to save to, as in tests.
+    fn save_setting<T>(
+        &mut self,
+        value: T,
+        set: fn(&mut Config, &cosmic_config::Config, T) -> Result<bool, cosmic_config::Error>,
+        field: fn(&mut Config) -> &mut T,
+    ) {
+        let result = match &self.config_handler {
+            Some(handler) => set(&mut self.config, handler, value).map(|_| ()),
+            None => {
+                *field(&mut self.config) = value;
+                Ok(())
+            }
+        };
+        self.saved(result);
+    }
+
+    /// Hands changed settings to the receiver and reports a failed save.
+    fn saved(&mut self, result: Result<(), cosmic_config::Error>) {
+        self.receiver.config = self.effective_config();
+        self.error = match result {
+            Ok(()) => None,
+            Err(e) => {
+                let error = AirTrayError::from(e);
+                eprintln!("Failed to save settings: {}", error);
+                Some(error)
+            }
+        };
+    }
+}
+
+/// Lets the arrow keys move through the popup's controls like Tab does.
+/// Keys a focused control uses itself, e.g. in a slider, never get here.
+fn arrow_navigation(key: Key, _modifiers: Modifiers) -> Option<Message> {
+    match key {
+        Key::Named(Named::ArrowDown) => Some(Message::FocusNext),
+        Key::Named(Named::ArrowUp) => Some(Message::FocusPrevious),
+        _ => None,
+    }
+}
 
+/// Lifts a task producing applet messages into one the COSMIC runtime accepts.
+fn app_task(task: cosmic::iced::Task<Message>) -> Task<Message> {
+    task.map(cosmic::app::Message::App)
 }
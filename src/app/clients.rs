@@ -0,0 +1,309 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Notifications about senders connecting and leaving. A sender that leaves
+//! gets a grace period to come back, e.g. after its screen locked, before it
+//! counts as gone; until then the session carries on as if it were there.
+//! Repeated notifications of a kind are merged into one, so a flaky sender
+//! reconnecting over and over doesn't flood the desktop. The user's hooks
+//! run on the same events.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use cosmic::app::Task;
+
+use super::{app_task, AirTray, Message};
+use crate::config::{ConcurrentPolicy, Config, Hook};
+use crate::dbus::notifications;
+use crate::fl;
+use crate::plugins::PluginEvent;
+use crate::receiver::{Client, ReceiverState};
+use crate::sound;
+
+/// Key of the notification button letting a refused sender take over.
+pub(super) const TAKE_OVER: &str = "take-over";
+
+/// Key of the notification button turning the receiver off.
+pub(super) const STOP_RECEIVER: &str = "airtray-stop-receiver";
+
+/// Start of the key of the notification button blocking a device, followed
+/// by the device id.
+pub(super) const BLOCK_DEVICE: &str = "airtray-block-device:";
+
+/// How long notifications of the same kind are merged into the first one.
+const COALESCE_WINDOW: Duration = Duration::from_secs(60);
+
+/// What a notification is about, for merging repeated ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum NotificationKind {
+    Connected,
+    Disconnected,
+}
+
+/// The notification of a kind shown last, updated in place with a count
+/// while more of the kind follow within the window.
+#[derive(Debug, Default)]
+pub(super) struct Coalesced {
+    recent: HashMap<NotificationKind, Recent>,
+}
+
+#[derive(Debug)]
+struct Recent {
+    /// Id of the shown notification, 0 until the server answered.
+    id: u32,
+    count: u32,
+    since: Instant,
+}
+
+impl Coalesced {
+    /// Remembers the id the server gave the notification of `kind`.
+    pub(super) fn shown(&mut self, kind: NotificationKind, id: u32) {
+        if let Some(recent) = self.recent.get_mut(&kind) {
+            recent.id = id;
+        }
+    }
+}
+
+/// A sender that left less than a grace period ago.
+#[derive(Debug)]
+pub(super) struct Departed {
+    client: Client,
+    until: Instant,
+}
+
+impl AirTray {
+    /// The connected senders.
+    pub(super) fn clients(&self) -> &[Client] {
+        match self.receiver.state() {
+            ReceiverState::Running { clients } => clients,
+            _ => &[],
+        }
+    }
+
+    /// Whether a session is going on, counting senders that may still come back.
+    pub(super) fn in_grace_or_session(&self) -> bool {
+        self.has_clients() || !self.departed.is_empty()
+    }
+
+    /// Notifies about senders that connected since `before`, and starts the
+    /// grace period of those that left on their own. Those disconnected by
+    /// stopping the receiver are gone right away.
+    pub(super) fn on_clients_change(&mut self, before: &[Client], graceful: bool) -> Task<Message> {
+        let after = self.clients().to_vec();
+        let mut tasks = Vec::new();
+        let grace = Duration::from_secs(self.config.notifications.grace_period.into());
+
+        for client in after.iter().filter(|client| !contains(before, client)) {
+            // Back within the grace period, as if it never left
+            if let Some(index) = self
+                .departed
+                .iter()
+                .position(|departed| departed.client.id == client.id)
+            {
+                self.departed.remove(index);
+                continue;
+            }
+            if self.use_device_profile(&client.id) {
+                // It comes back to the restarted receiver, which needs no notification
+                tasks.push(self.depart(client, grace));
+                continue;
+            }
+            tasks.push(self.announce(NotificationKind::Connected, &client.id, &client.name));
+        }
+
+        for client in before.iter().filter(|client| !contains(&after, client)) {
+            if graceful {
+                tasks.push(self.depart(client, grace));
+            } else {
+                tasks.push(self.announce(NotificationKind::Disconnected, &client.id, &client.name));
+            }
+        }
+
+        Task::batch(tasks)
+    }
+
+    /// Starts the grace period of a sender that left.
+    fn depart(&mut self, client: &Client, grace: Duration) -> Task<Message> {
+        self.departed.push(Departed {
+            client: client.clone(),
+            until: Instant::now() + grace,
+        });
+        app_task(cosmic::iced::Task::perform(
+            tokio::time::sleep(grace),
+            |()| Message::GraceExpired,
+        ))
+    }
+
+    /// Switches to the profile of a device that just connected, restarting
+    /// the receiver for it if nobody else is streaming. The device reconnects
+    /// to the new process on its own. Returns whether it restarted.
+    fn use_device_profile(&mut self, id: &str) -> bool {
+        let wanted = self.config.device_profile(id).to_string();
+        if wanted == self.receiver.config.profile || self.clients().len() > 1 {
+            return false;
+        }
+
+        self.device_profile = (wanted != self.config.profile).then_some(wanted);
+        self.receiver.config = self.effective_config();
+        self.receiver.restart();
+        true
+    }
+
+    /// The settings the receiver runs with: the saved ones, with the
+    /// profile of the device the receiver last switched for.
+    pub(super) fn effective_config(&self) -> Config {
+        let mut config = self.config.clone();
+        if let Some(profile) = &self.device_profile {
+            config.profile = profile.clone();
+        }
+        config
+    }
+
+    /// Notifies about the senders whose grace period ran out.
+    pub(super) fn on_grace_expired(&mut self) -> Task<Message> {
+        let now = Instant::now();
+        let (expired, waiting) = std::mem::take(&mut self.departed)
+            .into_iter()
+            .partition::<Vec<_>, _>(|departed| departed.until <= now);
+        self.departed = waiting;
+
+        let tasks: Vec<_> = expired
+            .into_iter()
+            .map(|departed| {
+                let client = &departed.client;
+                self.announce(NotificationKind::Disconnected, &client.id, &client.name)
+            })
+            .collect();
+        Task::batch(tasks)
+    }
+
+    /// Handles a sender that was turned away because another one streams.
+    /// A priority device takes over from those without priority right away;
+    /// otherwise it may take over if the policy says to ask.
+    pub(super) fn refused(&mut self, id: &str, name: &str) -> Task<Message> {
+        if self.config.is_priority(id)
+            && !self.clients().iter().any(|client| self.config.is_priority(&client.id))
+        {
+            // The priority device gets in when it retries
+            self.receiver.restart();
+            return Task::none();
+        }
+        if self.config.concurrent_clients != ConcurrentPolicy::Prompt {
+            return Task::none();
+        }
+
+        let name = self.config.device_name(id, name);
+        let ask = notifications::ask(
+            fl!("notification-waiting", name = name.as_str()),
+            fl!("notification-waiting-body"),
+            vec![(TAKE_OVER, fl!("notification-take-over"))],
+        );
+        app_task(cosmic::iced::Task::perform(ask, |result| {
+            Message::NotificationAction(result.unwrap_or_else(|e| {
+                eprintln!("Failed to show notification: {}", e);
+                None
+            }))
+        }))
+    }
+
+    /// Runs the hook for the sender with `id` and `name` connecting or
+    /// leaving, tells the output modules and notifies about it.
+    fn announce(&mut self, kind: NotificationKind, id: &str, name: &str) -> Task<Message> {
+        let hook = match kind {
+            NotificationKind::Connected => Hook::ClientConnect,
+            NotificationKind::Disconnected => Hook::ClientDisconnect,
+        };
+        let (id, name) = (id.to_string(), name.to_string());
+        self.tell_outputs(match kind {
+            NotificationKind::Connected => PluginEvent::ClientConnected {
+                id: id.clone(),
+                name: name.clone(),
+            },
+            NotificationKind::Disconnected => PluginEvent::ClientDisconnected {
+                id: id.clone(),
+                name: name.clone(),
+            },
+        });
+        let hook = self.run_hook(hook, Some((&id, &name)));
+        Task::batch([hook, self.notify(kind, &id, &name)])
+    }
+
+    /// Shows a desktop notification about the sender with `id` and `name`, if
+    /// enabled. Within a minute of the last one of its kind, that one is
+    /// updated with a count instead. Connections can be stopped from it, and
+    /// a device never seen before blocked.
+    pub(super) fn notify(&mut self, kind: NotificationKind, id: &str, name: &str) -> Task<Message> {
+        let config = &self.config.notifications;
+        let sound = match kind {
+            NotificationKind::Connected => &config.connect_sound,
+            NotificationKind::Disconnected => &config.disconnect_sound,
+        };
+        let sound = if config.sounds {
+            app_task(cosmic::iced::Task::future(sound::play(sound.clone())).discard())
+        } else {
+            Task::none()
+        };
+        if !config.enabled {
+            return sound;
+        }
+
+        let now = Instant::now();
+        let recent = &mut self.notifications.recent;
+        recent.retain(|_, recent| now - recent.since <= COALESCE_WINDOW);
+        let recent = recent
+            .entry(kind)
+            .and_modify(|recent| recent.count += 1)
+            .or_insert(Recent {
+                id: 0,
+                count: 1,
+                since: now,
+            });
+
+        let summary = match (kind, recent.count) {
+            (NotificationKind::Connected, 1) => fl!("notification-connected", name = name),
+            (NotificationKind::Disconnected, 1) => fl!("notification-disconnected", name = name),
+            (NotificationKind::Connected, count) => {
+                fl!("notification-connected-repeated", name = name, count = count)
+            }
+            (NotificationKind::Disconnected, count) => {
+                fl!("notification-disconnected-repeated", name = name, count = count)
+            }
+        };
+        let replaces = recent.id;
+
+        let mut actions = Vec::new();
+        if kind == NotificationKind::Connected {
+            actions.push((STOP_RECEIVER.to_string(), fl!("notification-stop")));
+            if self.is_new_device(id) {
+                actions.push((format!("{}{}", BLOCK_DEVICE, id), fl!("notification-block")));
+            }
+        }
+
+        let notification = app_task(cosmic::iced::Task::perform(
+            notifications::notify(summary, String::new(), replaces, actions),
+            move |result| match result {
+                Ok(id) => Message::Notified(kind, id),
+                Err(e) => {
+                    eprintln!("Failed to show notification: {}", e);
+                    Message::Notified(kind, 0)
+                }
+            },
+        ));
+        Task::batch([sound, notification])
+    }
+
+    /// Whether the device with `id` has neither been seen nor set up before.
+    fn is_new_device(&self, id: &str) -> bool {
+        if self.config.devices.contains_key(id) {
+            return false;
+        }
+        match self.history.load() {
+            Ok(sessions) => !sessions.iter().any(|session| session.device_id == id),
+            Err(_) => false,
+        }
+    }
+}
+
+fn contains(clients: &[Client], client: &Client) -> bool {
+    clients.iter().any(|other| other.id == client.id)
+}
@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The embedded mirror: a regular window owned by the applet that draws the
+//! frames UXPlay streams to it, with the frame rate and media controls on top.
+
+use std::time::{Duration, Instant};
+
+use cosmic::app::Task;
+use cosmic::iced::widget::image::FilterMethod;
+use cosmic::iced::window::{self, Id};
+use cosmic::iced::{Alignment, Length, Size};
+use cosmic::widget;
+use cosmic::Element;
+
+use super::pages::controls;
+use super::{app_task, AirTray, Message};
+use crate::config::ScaleMethod;
+use crate::fl;
+
+/// Frames counted over this long make up the displayed frame rate.
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Frame rate measurement for the overlay.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameRate {
+    window_start: Instant,
+    frames: u32,
+    /// Frames per second over the last full window.
+    pub fps: u32,
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        Self {
+            window_start: Instant::now(),
+            frames: 0,
+            fps: 0,
+        }
+    }
+}
+
+impl FrameRate {
+    pub fn tick(&mut self) {
+        self.frames += 1;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= FPS_WINDOW {
+            self.fps = (self.frames as f32 / elapsed.as_secs_f32()).round() as u32;
+            self.frames = 0;
+            self.window_start = Instant::now();
+        }
+    }
+}
+
+impl AirTray {
+    /// Opens the window once frames arrive and closes it when the session ends.
+    pub(super) fn sync_mirror(&mut self) -> Task<Message> {
+        let video = &self.config.video;
+        let wanted = video.embedded
+            && !video.pip.enabled
+            && !self.mirror_dismissed
+            && self.has_clients()
+            && self.frame.is_some();
+
+        match self.mirror_window {
+            None if wanted => {
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(1280.0, 720.0),
+                    min_size: Some(Size::new(320.0, 180.0)),
+                    ..Default::default()
+                });
+                self.mirror_window = Some(id);
+                app_task(open.discard())
+            }
+            Some(id) if !wanted => {
+                self.mirror_window = None;
+                app_task(window::close(id))
+            }
+            _ => Task::none(),
+        }
+    }
+
+    /// The latest frame, scaled to the available space.
+    pub(super) fn view_frame(&self) -> Element<Message> {
+        let Some((frame, size)) = &self.frame else {
+            return widget::Space::new(Length::Fill, Length::Fill).into();
+        };
+
+        let scaling = self.config.video.scaling;
+        let filter = match scaling.method {
+            ScaleMethod::Nearest => FilterMethod::Nearest,
+            ScaleMethod::Bilinear => FilterMethod::Linear,
+        };
+        if !scaling.integer {
+            return widget::image(frame.clone())
+                .filter_method(filter)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into();
+        }
+
+        let (frame, size) = (frame.clone(), *size);
+        cosmic::iced::widget::responsive(move |bounds| {
+            // Largest whole multiple that fits, or shrunk to fit if even 1:1 doesn't
+            let fit = (bounds.width / size.width as f32).min(bounds.height / size.height as f32);
+            let scale = if fit >= 1.0 { fit.floor() } else { fit };
+            widget::container(
+                widget::image(frame.clone())
+                    .filter_method(filter)
+                    .width(Length::Fixed(size.width as f32 * scale))
+                    .height(Length::Fixed(size.height as f32 * scale)),
+            )
+            .center(Length::Fill)
+            .into()
+        })
+        .into()
+    }
+
+    /// Contents of the embedded mirror window.
+    pub(super) fn view_mirror(&self) -> Element<Message> {
+        let video = self.view_frame();
+
+        let overlay = widget::column()
+            .padding(12)
+            .push(
+                widget::container(widget::text::caption(fl!(
+                    "mirror-fps",
+                    fps = self.frame_rate.fps
+                )))
+                .class(cosmic::style::Container::Card)
+                .padding(6),
+            )
+            .push(widget::vertical_space())
+            .push(
+                widget::container(controls())
+                    .class(cosmic::style::Container::Card)
+                    .padding(6),
+            )
+            .align_x(Alignment::Start)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        cosmic::iced::widget::stack![video, overlay].into()
+    }
+}
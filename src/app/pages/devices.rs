@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+
+use super::{back_button, day_label};
+use crate::app::{AirTray, Message};
+use crate::fl;
+
+impl AirTray {
+    /// Every sender seen so far, with its profile and whether it may connect.
+    pub(super) fn view_devices(&self) -> Element<Message> {
+        let mut section = settings::section()
+            .title(fl!("devices"))
+            .add(widget::text::caption(fl!("devices-description")));
+        if self.known_devices.is_empty() {
+            section = section.add(widget::text::body(fl!("devices-none")));
+        }
+
+        for device in &self.known_devices {
+            let id = device.id.clone();
+            let profile_id = device.id.clone();
+            let priority_id = device.id.clone();
+            let profile = self.config.devices.get(&device.id).map_or("", |d| d.profile.as_str());
+            let selected_profile = self
+                .config
+                .profiles
+                .iter()
+                .position(|p| p.name == profile)
+                .map_or(0, |index| index + 1);
+            let last_seen = fl!(
+                "device-last-seen",
+                time = device.last_seen.format("%Y-%m-%d %H:%M").to_string()
+            );
+            let mut controls = widget::row()
+                .spacing(8)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(widget::dropdown(
+                    &self.device_profile_labels,
+                    Some(selected_profile),
+                    move |index| Message::SelectDeviceProfile(profile_id.clone(), index),
+                ))
+                .push(widget::text::caption(fl!("device-priority")))
+                .push(
+                    widget::toggler(self.config.is_priority(&device.id)).on_toggle(
+                        move |priority| Message::SetDevicePriority(priority_id.clone(), priority),
+                    ),
+                );
+            // Refusing devices needs UXPlay's -block
+            if self.capabilities.block() {
+                controls = controls
+                    .push(widget::text::caption(fl!("device-allowed")))
+                    .push(
+                        widget::toggler(!self.config.is_blocked(&device.id))
+                            .on_toggle(move |allowed| Message::SetDeviceAllowed(id.clone(), allowed)),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("alarm-symbolic"))
+                            .on_press(Message::EditDeviceHours(device.id.clone())),
+                    );
+            }
+            controls = controls.push(
+                widget::button::icon(widget::icon::from_name("user-trash-symbolic"))
+                    .on_press(Message::ForgetDevice(device.id.clone())),
+            );
+            section = section.add(
+                settings::item::builder(self.config.device_name(&device.id, &device.name))
+                    .description(last_seen)
+                    .control(controls),
+            );
+            if self.editing_hours.as_ref() == Some(&device.id) {
+                section = section.add(self.view_device_hours(&device.id));
+            }
+        }
+
+        widget::column()
+            .spacing(8)
+            .padding(5)
+            .push(back_button())
+            .push(section)
+            .into()
+    }
+
+    /// The hours the device with `id` may connect in.
+    fn view_device_hours(&self, id: &str) -> Element<Message> {
+        let hours = self.device_hours(id);
+        let mut column = widget::column().spacing(8).push(
+            settings::item::builder(fl!("device-hours"))
+                .description(fl!("device-hours-description"))
+                .toggler(hours.enabled, Message::ToggleDeviceHours),
+        );
+
+        if hours.enabled {
+            let days = (0..7).fold(widget::row().spacing(4), |row, day| {
+                let button = if hours.days.contains(&day) {
+                    widget::button::suggested(day_label(day))
+                } else {
+                    widget::button::standard(day_label(day))
+                };
+                row.push(button.on_press(Message::ToggleDeviceHoursDay(day)))
+            });
+
+            column = column
+                .push(days)
+                .push(settings::item(
+                    fl!("schedule-start"),
+                    widget::text_input("16:00", &self.hours_start_input)
+                        .on_input(Message::DeviceHoursStartChanged),
+                ))
+                .push(settings::item(
+                    fl!("schedule-end"),
+                    widget::text_input("19:00", &self.hours_end_input)
+                        .on_input(Message::DeviceHoursEndChanged),
+                ));
+        }
+
+        column.into()
+    }
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::iced::widget::qr_code;
+use cosmic::iced::{Alignment, Length};
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+
+use super::back_button;
+use crate::app::{AirTray, Message};
+use crate::companion;
+use crate::fl;
+
+impl AirTray {
+    /// QR code for sending files from the phone, and what it has sent so far.
+    pub(super) fn view_files(&self) -> Element<Message> {
+        let companion = &self.config.companion;
+        let mut upload = widget::column().spacing(8).align_x(Alignment::Center).width(Length::Fill);
+
+        if !self.has_clients() {
+            upload = upload.push(widget::text::body(fl!("files-session-only")));
+        } else if let Some(qr) = &self.upload_qr {
+            upload = upload
+                .push(qr_code(qr).cell_size(4))
+                .push(widget::text::caption(companion::upload_url(
+                    self.lan_address(),
+                    companion.port,
+                    &companion.token,
+                )));
+        }
+
+        let mut received = settings::section().title(fl!("files-received"));
+        if self.received_files.is_empty() {
+            received = received.add(widget::text::body(fl!("files-none")));
+        }
+        for (index, path) in self.received_files.iter().enumerate() {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            received = received.add(settings::item(
+                name,
+                widget::button::icon(widget::icon::from_name("document-open-symbolic"))
+                    .on_press(Message::OpenReceivedFile(index)),
+            ));
+        }
+
+        widget::column()
+            .spacing(8)
+            .padding(5)
+            .push(back_button())
+            .push(upload)
+            .push(received)
+            .into()
+    }
+}
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+
+use super::back_button;
+use crate::app::{AirTray, Message};
+use crate::fl;
+use crate::log::Level;
+
+impl AirTray {
+    /// UXPlay's recent output, filtered by how serious it is.
+    pub(super) fn view_log(&self) -> Element<Message> {
+        let selected = match self.log_level {
+            Level::Error => 0,
+            Level::Warning => 1,
+            Level::Info => 2,
+        };
+        let mut section = settings::section().title(fl!("log")).add(settings::item(
+            fl!("log-show"),
+            widget::dropdown(&self.log_level_labels, Some(selected), Message::SelectLogLevel),
+        ));
+
+        if self.log_lines.is_empty() {
+            section = section.add(widget::text::body(fl!("log-empty")));
+        }
+        // Newest first, as that's usually what's being looked for
+        for line in self.log_lines.iter().rev() {
+            section = section.add(widget::text::caption(format!(
+                "{} {}",
+                line.time.format("%H:%M:%S"),
+                line.text
+            )));
+        }
+
+        widget::column()
+            .spacing(8)
+            .padding(5)
+            .push(back_button())
+            .push(section)
+            .into()
+    }
+}
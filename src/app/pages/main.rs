@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::Local;
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+
+use super::{controls, format_clock, link, Page};
+use crate::app::{AirTray, Message};
+use crate::error::AirTrayError;
+use crate::fl;
+use crate::metadata::NowPlaying;
+use crate::receiver::ReceiverState;
+use crate::video::QualityPreset;
+
+impl AirTray {
+    /// The main page: receiver toggle, status and links to the other pages.
+    pub(super) fn view_main(&self) -> Element<Message> {
+        if self.config.kiosk && !self.unlocked {
+            return self.view_kiosk();
+        }
+
+        let status = self.status_text();
+        let mut content_list = widget::list_column()
+            .padding(5)
+            .spacing(0)
+            .add(settings::item(
+                fl!("airplay"),
+                // Screen readers announce the status along with the switch
+                widget::toggler(self.receiver.state().is_active())
+                    .name(fl!("airplay"))
+                    .description(status.clone())
+                    .on_toggle(Message::ToggleAirPlay),
+            ))
+            .add(settings::item(fl!("status"), widget::text::body(status)));
+
+        if let Some(pin) = &self.session_pin {
+            content_list = content_list.add(settings::item(
+                fl!("session-pin"),
+                widget::text::title3(pin.as_str()),
+            ));
+        }
+
+        if let ReceiverState::Failed {
+            reason: AirTrayError::ProcessExited { stderr, .. },
+        } = self.receiver.state()
+        {
+            if !stderr.is_empty() {
+                content_list = content_list.add(widget::text::caption(stderr.join("\n")));
+            }
+        }
+
+        if let ReceiverState::Running { clients } = self.receiver.state() {
+            for client in clients {
+                let detail = match self.phones.get(&client.id) {
+                    Some(battery) if battery.charging => {
+                        fl!("client-battery-charging", charge = battery.charge)
+                    }
+                    Some(battery) => fl!("client-battery", charge = battery.charge),
+                    None => client.model.clone(),
+                };
+                let streaming = format_clock(Local::now() - client.connected_at);
+                let row = match &self.renaming {
+                    Some((id, nickname)) if id == &client.id => settings::item(
+                        fl!("nickname"),
+                        widget::text_input(client.announced_name.as_str(), nickname)
+                            .on_input(Message::NicknameChanged)
+                            .on_submit(|_| Message::SaveNickname),
+                    ),
+                    _ => settings::item(
+                        client.name.clone(),
+                        widget::row()
+                            .spacing(8)
+                            .align_y(cosmic::iced::Alignment::Center)
+                            .push(widget::text::body(detail.clone()))
+                            .push(widget::text::body(streaming))
+                            .push(
+                                widget::button::icon(widget::icon::from_name(
+                                    "document-edit-symbolic",
+                                ))
+                                .name(fl!("a11y-rename", name = client.name.as_str()))
+                                .description(detail)
+                                .on_press(Message::RenameClient(client.id.clone())),
+                            ),
+                    ),
+                };
+                content_list = content_list.add(row);
+            }
+        }
+
+        if let Some(host) = &self.name_conflict {
+            content_list = content_list.add(
+                settings::item::builder(fl!("name-taken", host = host.as_str()))
+                    .description(fl!("name-taken-description"))
+                    .control(
+                        widget::button::standard(fl!(
+                            "name-taken-rename",
+                            name = self.config.alternative_name()
+                        ))
+                        .on_press(Message::RenameForConflict),
+                    ),
+            );
+        }
+
+        if let Some(impostor) = self.duplicates.iter().find(|service| service.has_local_id()) {
+            let warning = fl!("duplicate-id", host = impostor.host.as_str());
+            content_list = content_list.add(link(warning, Page::Nearby));
+        }
+
+        if let Some(left) = self.session_left() {
+            let left = widget::text::body(format_clock(left));
+            content_list = content_list.add(settings::item(fl!("session-left"), left));
+        }
+
+        if self.has_clients() && self.health.is_poor() {
+            content_list = content_list.add(link(fl!("poor-connection"), Page::Statistics));
+        }
+
+        if let Some(now_playing) = &self.now_playing {
+            content_list = content_list.add(now_playing_row(now_playing));
+        }
+
+        if self.has_clients() {
+            let volume = self.config.stream_volume();
+            let (mute_icon, mute_name) = if volume.muted {
+                ("audio-volume-muted-symbolic", fl!("a11y-unmute"))
+            } else {
+                ("audio-volume-high-symbolic", fl!("a11y-mute"))
+            };
+
+            content_list = content_list
+                .add(settings::item(
+                    fl!("stream-volume"),
+                    widget::row()
+                        .spacing(8)
+                        .align_y(cosmic::iced::Alignment::Center)
+                        .push(
+                            widget::button::icon(widget::icon::from_name(mute_icon))
+                                .name(mute_name)
+                                .on_press(Message::ToggleStreamMute),
+                        )
+                        .push(
+                            widget::slider(0..=150, volume.percent, Message::StreamVolumeChanged)
+                                .width(cosmic::iced::Length::Fixed(120.0)),
+                        ),
+                ))
+                .add(settings::item(
+                    fl!("audio-level"),
+                    widget::progress_bar(0.0..=1.0, self.audio_level)
+                        .height(6)
+                        .width(cosmic::iced::Length::Fixed(120.0)),
+                ))
+                .add(controls());
+        }
+
+        let schedule = &self.config.schedule;
+        if schedule.enabled {
+            let now = Local::now();
+            let next = match schedule.next_change(now) {
+                Some(time) if schedule.is_open(now) => {
+                    fl!("schedule-off-at", time = time.format("%a %H:%M").to_string())
+                }
+                Some(time) => fl!("schedule-on-at", time = time.format("%a %H:%M").to_string()),
+                None => fl!("schedule-never"),
+            };
+            content_list = content_list.add(settings::item(
+                fl!("schedule"),
+                widget::text::body(next),
+            ));
+        }
+
+        let preset = QualityPreset::matching(&self.config.stream_quality())
+            .map_or(QualityPreset::ALL.len(), |preset| preset as usize);
+        content_list = content_list.add(settings::item(
+            fl!("quality"),
+            widget::dropdown(
+                &self.quality_labels,
+                Some(preset),
+                Message::SelectQualityPreset,
+            ),
+        ));
+
+        content_list = content_list.add(
+            settings::item::builder(fl!("low-latency"))
+                .description(fl!("low-latency-description"))
+                .toggler(self.config.low_latency, Message::ToggleLowLatency),
+        );
+
+        if let Some(enabled) = self.bluetooth_sink {
+            content_list = content_list.add(
+                settings::item::builder(fl!("bluetooth-sink"))
+                    .description(fl!("bluetooth-sink-description"))
+                    .toggler(enabled, Message::ToggleBluetoothSink),
+            );
+        }
+
+        content_list = content_list
+            .add(link(fl!("settings"), Page::Settings))
+            .add(link(fl!("statistics"), Page::Statistics))
+            .add(link(fl!("remote"), Page::Remote))
+            .add(link(fl!("nearby"), Page::Nearby))
+            .add(link(fl!("devices"), Page::Devices));
+
+        if self.config.companion.files {
+            content_list = content_list.add(link(fl!("files-received"), Page::Files));
+        }
+
+        if let Some(error) = &self.error {
+            content_list = content_list.add(widget::text::body(error.user_message()));
+        }
+
+        content_list.into()
+    }
+
+    /// The main page in kiosk mode: the status and who is connected, with
+    /// the settings behind authentication.
+    fn view_kiosk(&self) -> Element<Message> {
+        let mut content_list = widget::list_column()
+            .padding(5)
+            .spacing(0)
+            .add(settings::item(fl!("status"), widget::text::body(self.status_text())));
+
+        if let Some(pin) = &self.session_pin {
+            content_list = content_list.add(settings::item(
+                fl!("session-pin"),
+                widget::text::title3(pin.as_str()),
+            ));
+        }
+
+        for client in self.clients() {
+            let streaming = format_clock(Local::now() - client.connected_at);
+            content_list = content_list.add(settings::item(
+                client.name.clone(),
+                widget::row()
+                    .spacing(8)
+                    .push(widget::text::body(client.model.clone()))
+                    .push(widget::text::body(streaming)),
+            ));
+        }
+
+        if let Some(now_playing) = &self.now_playing {
+            content_list = content_list.add(now_playing_row(now_playing));
+        }
+
+        content_list = content_list.add(link(fl!("settings"), Page::Settings));
+
+        if let Some(error) = &self.error {
+            content_list = content_list.add(widget::text::body(error.user_message()));
+        }
+
+        content_list.into()
+    }
+}
+
+/// Cover art thumbnail next to the title and artist.
+fn now_playing_row(now_playing: &NowPlaying) -> Element<'_, Message> {
+    let subtitle = match (now_playing.artist.is_empty(), now_playing.album.is_empty()) {
+        (false, false) => format!("{} — {}", now_playing.artist, now_playing.album),
+        (false, true) => now_playing.artist.clone(),
+        (true, _) => now_playing.album.clone(),
+    };
+
+    let mut row = widget::row()
+        .spacing(12)
+        .align_y(cosmic::iced::Alignment::Center);
+    if let Some(artwork) = &now_playing.artwork {
+        row = row.push(widget::image(artwork.clone()).width(48).height(48));
+    }
+
+    row.push(
+        widget::column()
+            .push(widget::text::body(now_playing.title.as_str()))
+            .push(widget::text::caption(subtitle)),
+    )
+    .into()
+}
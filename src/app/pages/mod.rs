@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The pages of the popup. The main page links to all others.
+
+mod devices;
+mod files;
+mod log;
+mod main;
+mod nearby;
+mod remote;
+mod settings;
+mod statistics;
+mod unlock;
+
+use cosmic::widget;
+use cosmic::Element;
+
+use crate::app::{AirTray, Message};
+use crate::dacp::DacpCommand;
+use crate::fl;
+
+/// The page currently shown in the popup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Page {
+    #[default]
+    Main,
+    Settings,
+    Statistics,
+    Remote,
+    Files,
+    Devices,
+    Nearby,
+    Log,
+    /// Asks for authentication before opening a protected page.
+    Unlock,
+}
+
+impl Page {
+    /// Whether the page is behind the settings lock, if one is set.
+    pub fn is_protected(self) -> bool {
+        matches!(self, Self::Settings | Self::Devices)
+    }
+}
+
+impl AirTray {
+    /// The widget tree of the current page.
+    pub(super) fn view_page(&self) -> Element<Message> {
+        match self.page {
+            Page::Main => self.view_main(),
+            Page::Settings => self.view_settings(),
+            Page::Statistics => {
+                let exported = self.history_exported.as_deref();
+                statistics::view(&self.stats, &self.health, self.audio_codec, self.usage, exported)
+            }
+            Page::Remote => self.view_remote(),
+            Page::Files => self.view_files(),
+            Page::Devices => self.view_devices(),
+            Page::Nearby => self.view_nearby(),
+            Page::Log => self.view_log(),
+            Page::Unlock => self.view_unlock(),
+        }
+    }
+}
+
+/// Row on the main page that opens `page`.
+pub fn link<'a>(label: String, page: Page) -> Element<'a, Message> {
+    widget::settings::item(
+        label.clone(),
+        widget::button::icon(widget::icon::from_name("go-next-symbolic"))
+            .name(label)
+            .on_press(Message::OpenPage(page)),
+    )
+    .into()
+}
+
+/// Button returning to the main page.
+pub fn back_button<'a>() -> Element<'a, Message> {
+    widget::button::text(fl!("back"))
+        .leading_icon(widget::icon::from_name("go-previous-symbolic"))
+        .on_press(Message::OpenPage(Page::Main))
+        .into()
+}
+
+/// Media controls for the connected sender.
+pub fn controls<'a>() -> Element<'a, Message> {
+    let button = |icon: &'static str, name: String, command: DacpCommand| {
+        widget::button::icon(widget::icon::from_name(icon))
+            .name(name)
+            .on_press(Message::Control(command))
+    };
+
+    widget::row()
+        .spacing(8)
+        .push(button(
+            "media-skip-backward-symbolic",
+            fl!("a11y-previous"),
+            DacpCommand::Previous,
+        ))
+        .push(button(
+            "media-playback-start-symbolic",
+            fl!("a11y-play-pause"),
+            DacpCommand::PlayPause,
+        ))
+        .push(button("media-skip-forward-symbolic", fl!("a11y-next"), DacpCommand::Next))
+        .push(widget::horizontal_space())
+        .push(button(
+            "audio-volume-low-symbolic",
+            fl!("a11y-volume-down"),
+            DacpCommand::VolumeDown,
+        ))
+        .push(button(
+            "audio-volume-high-symbolic",
+            fl!("a11y-volume-up"),
+            DacpCommand::VolumeUp,
+        ))
+        .into()
+}
+
+/// Formats a duration as hours and minutes for display.
+pub fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    if hours > 0 {
+        fl!("duration-hours", hours = hours, minutes = minutes)
+    } else {
+        fl!("duration-minutes", minutes = minutes)
+    }
+}
+
+/// Formats a byte count in MB, or GB from 1000 MB, for display.
+pub fn format_bytes(bytes: u64) -> String {
+    let megabytes = bytes as f64 / 1_000_000.0;
+    if megabytes >= 1000.0 {
+        fl!("data-gb", gigabytes = format!("{:.1}", megabytes / 1000.0))
+    } else {
+        fl!("data-mb", megabytes = format!("{:.0}", megabytes))
+    }
+}
+
+/// Formats a duration like a clock, e.g. "4:05" or "1:04:05", for timers
+/// that tick every second.
+pub fn format_clock(duration: chrono::Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Short name of a day of the week, 0 being Monday.
+pub fn day_label(day: u8) -> String {
+    match day {
+        0 => fl!("day-mon"),
+        1 => fl!("day-tue"),
+        2 => fl!("day-wed"),
+        3 => fl!("day-thu"),
+        4 => fl!("day-fri"),
+        5 => fl!("day-sat"),
+        _ => fl!("day-sun"),
+    }
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+
+use super::back_button;
+use crate::app::{AirTray, Message};
+use crate::fl;
+use crate::mdns::Service;
+
+impl AirTray {
+    /// Other AirPlay receivers and senders announced on the network.
+    pub(super) fn view_nearby(&self) -> Element<Message> {
+        let status = if self.scanning_nearby {
+            fl!("nearby-scanning")
+        } else {
+            fl!("nearby-refresh")
+        };
+        let search = settings::section()
+            .title(fl!("nearby"))
+            .add(widget::text::caption(fl!("nearby-description")))
+            .add(settings::item(
+                status,
+                widget::button::icon(widget::icon::from_name("view-refresh-symbolic"))
+                    .on_press_maybe((!self.scanning_nearby).then_some(Message::ScanNearby)),
+            ));
+
+        let mut content = widget::column().spacing(8).padding(5).push(back_button());
+
+        if !self.duplicates.is_empty() {
+            content = content.push(self.view_duplicates());
+        }
+        content = content.push(search);
+
+        if let Some(nearby) = &self.nearby {
+            content = content
+                .push(services(fl!("nearby-receivers"), &nearby.receivers))
+                .push(services(fl!("nearby-senders"), &nearby.senders));
+        }
+
+        content.into()
+    }
+
+    /// Receivers on other machines claiming to be this one, and how.
+    fn view_duplicates(&self) -> Element<Message> {
+        let mut section = settings::section()
+            .title(fl!("duplicates"))
+            .add(widget::text::caption(fl!("duplicates-description")));
+
+        for service in &self.duplicates {
+            let reason = if service.has_local_id() {
+                fl!("duplicate-same-id")
+            } else {
+                fl!("duplicate-same-name")
+            };
+            let address = fl!(
+                "nearby-address",
+                host = service.host.clone(),
+                address = service.address.clone()
+            );
+            section = section.add(
+                settings::item::builder(service.name.clone())
+                    .description(address)
+                    .control(widget::text::caption(reason)),
+            );
+        }
+
+        section.into()
+    }
+}
+
+/// Section listing `services` with their address and model.
+fn services<'a>(title: String, services: &[Service]) -> Element<'a, Message> {
+    let mut section = settings::section().title(title);
+    if services.is_empty() {
+        section = section.add(widget::text::body(fl!("nearby-none")));
+    }
+
+    for service in services {
+        let address = fl!(
+            "nearby-address",
+            host = service.host.clone(),
+            address = service.address.clone()
+        );
+        section = section.add(
+            settings::item::builder(service.name.clone())
+                .description(address)
+                .control(widget::text::caption(
+                    service.txt("model").unwrap_or_default().to_string(),
+                )),
+        );
+    }
+
+    section.into()
+}
@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+
+use super::back_button;
+use crate::app::{AirTray, Message};
+use crate::appletv::RemoteKey;
+use crate::fl;
+
+impl AirTray {
+    /// Remote control for an Apple TV on the network.
+    pub(super) fn view_remote(&self) -> Element<Message> {
+        let refresh = widget::button::icon(widget::icon::from_name("view-refresh-symbolic"));
+        let mut devices = settings::section().title(fl!("remote"));
+
+        if self.apple_tvs.is_empty() {
+            let status = if self.scanning_apple_tvs {
+                fl!("remote-scanning")
+            } else {
+                fl!("remote-none")
+            };
+            devices = devices.add(settings::item(
+                status,
+                refresh.on_press_maybe((!self.scanning_apple_tvs).then_some(Message::ScanAppleTvs)),
+            ));
+        } else {
+            devices = devices.add(settings::item(
+                fl!("remote-device"),
+                widget::row()
+                    .spacing(8)
+                    .push(widget::dropdown(
+                        &self.apple_tv_labels,
+                        self.selected_apple_tv,
+                        Message::SelectAppleTv,
+                    ))
+                    .push(refresh.on_press_maybe(
+                        (!self.scanning_apple_tvs).then_some(Message::ScanAppleTvs),
+                    )),
+            ));
+        }
+
+        let mut content = widget::column()
+            .spacing(8)
+            .padding(5)
+            .push(back_button())
+            .push(devices);
+
+        if self.selected_apple_tv.is_some() {
+            content = content.push(remote_pad());
+        }
+
+        content.into()
+    }
+}
+
+/// Directional pad and the buttons around it.
+fn remote_pad<'a>() -> Element<'a, Message> {
+    let key = |icon: &'static str, key: RemoteKey| {
+        widget::button::icon(widget::icon::from_name(icon)).on_press(Message::PressRemoteKey(key))
+    };
+    let labeled = |label: String, key: RemoteKey| {
+        widget::button::text(label).on_press(Message::PressRemoteKey(key))
+    };
+
+    let pad = widget::column()
+        .spacing(4)
+        .align_x(cosmic::iced::Alignment::Center)
+        .push(key("go-up-symbolic", RemoteKey::Up))
+        .push(
+            widget::row()
+                .spacing(4)
+                .align_y(cosmic::iced::Alignment::Center)
+                .push(key("go-previous-symbolic", RemoteKey::Left))
+                .push(labeled(fl!("remote-select"), RemoteKey::Select))
+                .push(key("go-next-symbolic", RemoteKey::Right)),
+        )
+        .push(key("go-down-symbolic", RemoteKey::Down));
+
+    let buttons = widget::row()
+        .spacing(8)
+        .push(labeled(fl!("remote-menu"), RemoteKey::Menu))
+        .push(key("go-home-symbolic", RemoteKey::Home))
+        .push(key("media-playback-start-symbolic", RemoteKey::PlayPause));
+
+    widget::column()
+        .spacing(12)
+        .align_x(cosmic::iced::Alignment::Center)
+        .width(cosmic::iced::Length::Fill)
+        .push(pad)
+        .push(buttons)
+        .into()
+}
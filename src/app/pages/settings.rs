@@ -0,0 +1,963 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::widget;
+use cosmic::widget::settings::{self, Section};
+use cosmic::Element;
+
+use super::{back_button, day_label, Page};
+use crate::app::{AirTray, Message};
+use crate::audio::snapcast::SnapcastStatus;
+use crate::companion;
+use crate::config::validation::{self, Invalid};
+use crate::config::{Codec, CompanionConfig, Hook, LockMethod, RecordingConfig, SettingsSection};
+use crate::fl;
+use crate::ports;
+use crate::rest;
+use crate::viewer;
+use crate::webhook;
+
+impl AirTray {
+    /// Receiver, audio and integration settings.
+    pub(super) fn view_settings(&self) -> Element<Message> {
+        let general = settings::section()
+            .add(settings::item(
+                fl!("receiver-name"),
+                widget::text_input(self.config.receiver_name(), &self.config.name)
+                    .on_input(Message::NameChanged),
+            ))
+            .add(
+                settings::item::builder(fl!("auto-rename"))
+                    .description(fl!("auto-rename-description"))
+                    .toggler(self.config.auto_rename, Message::ToggleAutoRename),
+            )
+            .add(
+                settings::item::builder(fl!("wake-on-demand"))
+                    .description(fl!("wake-on-demand-description"))
+                    .toggler(self.config.wake_on_demand, Message::ToggleWakeOnDemand),
+            )
+            .add(
+                settings::item::builder(fl!("performance-profile"))
+                    .description(fl!("performance-profile-description"))
+                    .toggler(self.config.performance_profile, Message::TogglePerformanceProfile),
+            )
+            .add(settings::item(
+                fl!("concurrent-clients"),
+                widget::dropdown(
+                    &self.concurrent_labels,
+                    Some(self.config.concurrent_clients as usize),
+                    Message::SelectConcurrentPolicy,
+                ),
+            ))
+            .add(
+                settings::item::builder(fl!("session-limit"))
+                    .description(fl!("session-limit-description"))
+                    .control(
+                        widget::text_input(fl!("session-limit-none"), &self.session_limit_input)
+                            .on_input(Message::SessionLimitChanged),
+                    ),
+            )
+            .add(settings::item(
+                fl!("notifications"),
+                widget::toggler(self.config.notifications.enabled)
+                    .on_toggle(Message::ToggleNotifications),
+            ))
+            .add(
+                settings::item::builder(fl!("weekly-summary"))
+                    .description(fl!("weekly-summary-description"))
+                    .toggler(
+                        self.config.notifications.weekly_summary,
+                        Message::ToggleWeeklySummary,
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("sounds"))
+                    .description(fl!("sounds-description"))
+                    .toggler(self.config.notifications.sounds, Message::ToggleSounds),
+            );
+
+        let notifications = &self.config.notifications;
+        let general = if notifications.sounds {
+            general
+                .add(settings::item(
+                    fl!("connect-sound"),
+                    widget::text_input("device-added", &notifications.connect_sound)
+                        .on_input(Message::ConnectSoundChanged),
+                ))
+                .add(settings::item(
+                    fl!("disconnect-sound"),
+                    widget::text_input("device-removed", &notifications.disconnect_sound)
+                        .on_input(Message::DisconnectSoundChanged),
+                ))
+        } else {
+            general
+        };
+
+        let general = if notifications.enabled || notifications.sounds {
+            general.add(
+                settings::item::builder(fl!("grace-period"))
+                    .description(fl!("grace-period-description"))
+                    .control(
+                        widget::text_input("10", &self.grace_period_input)
+                            .on_input(Message::GracePeriodChanged),
+                    ),
+            )
+        } else {
+            general
+        };
+
+        widget::column()
+            .spacing(8)
+            .padding(5)
+            .push(back_button())
+            .push(general)
+            .push(self.profiles_section())
+            .push(self.networks_section())
+            .push(self.schedule_section())
+            .push(self.audio_section())
+            .push(self.viewer_section())
+            .push(self.phone_section())
+            .push(self.integrations_section())
+            .push(self.lock_section())
+            .push(self.advanced_section())
+            .push(self.troubleshooting_section())
+            .into()
+    }
+
+    fn profiles_section(&self) -> Section<'_, Message> {
+        let selected = self
+            .config
+            .profiles
+            .iter()
+            .position(|profile| profile.name == self.config.profile)
+            .map_or(0, |index| index + 1);
+
+        let mut section = settings::section().title(fl!("profiles")).add(settings::item(
+            fl!("profile"),
+            widget::row()
+                .spacing(8)
+                .push(widget::dropdown(&self.profile_labels, Some(selected), Message::SelectProfile))
+                .push(
+                    widget::button::icon(widget::icon::from_name("list-add-symbolic"))
+                        .on_press(Message::AddProfile),
+                ),
+        ));
+
+        if let Some(profile) = self.config.active_profile() {
+            section = section.add(settings::item(
+                fl!("profile-name"),
+                widget::text_input("", &profile.name).on_input(Message::ProfileNameChanged),
+            ));
+            if self.capabilities.pin() {
+                section = section.add(settings::item(
+                    fl!("profile-pin"),
+                    widget::text_input(fl!("profile-pin-none"), &profile.pin)
+                        .on_input(Message::ProfilePinChanged),
+                ));
+            }
+            let mut resolution = settings::item::builder(fl!("profile-resolution"));
+            if !validation::resolution(&profile.resolution) {
+                resolution = resolution.description(fl!("invalid-resolution"));
+            }
+            section = section
+                .add(
+                    resolution.control(
+                        widget::text_input("1920x1080", &profile.resolution)
+                            .on_input(Message::ProfileResolutionChanged),
+                    ),
+                )
+                .add(settings::item(
+                    fl!("profile-fps"),
+                    widget::text_input("30", &self.profile_fps_input)
+                        .on_input(Message::ProfileFpsChanged),
+                ))
+                .add(widget::text::caption(fl!("pipeline-warning")))
+                .add(settings::item(
+                    fl!("video-pipeline"),
+                    widget::text_input("autovideosink", &profile.video_pipeline)
+                        .on_input(Message::ProfileVideoPipelineChanged),
+                ))
+                .add(settings::item(
+                    fl!("audio-pipeline"),
+                    widget::text_input("autoaudiosink", &profile.audio_pipeline)
+                        .on_input(Message::ProfileAudioPipelineChanged),
+                ));
+            if !self.missing_elements.is_empty() {
+                section = section.add(widget::text::caption(fl!(
+                    "pipeline-missing",
+                    elements = self.missing_elements.join(", ")
+                )));
+            }
+            section = section.add(
+                    widget::button::destructive(fl!("profile-remove"))
+                        .on_press(Message::RemoveProfile),
+                );
+        }
+
+        section
+    }
+
+    fn networks_section(&self) -> Section<'_, Message> {
+        let networks = &self.config.networks;
+        let mut section = settings::section().title(fl!("networks")).add(
+            settings::item::builder(fl!("networks-enabled"))
+                .description(fl!("networks-description"))
+                .toggler(networks.enabled, Message::ToggleNetworkProfiles),
+        );
+
+        if !networks.enabled {
+            return section;
+        }
+
+        if let Some(network) = &self.current_network {
+            section = section.add(settings::item(
+                network.clone(),
+                widget::button::text(fl!("networks-use-profile"))
+                    .on_press(Message::UseProfileOnNetwork),
+            ));
+        }
+
+        for (index, rule) in networks.rules.iter().enumerate() {
+            let profile = if rule.profile.is_empty() {
+                fl!("profile-none")
+            } else {
+                rule.profile.clone()
+            };
+            section = section.add(settings::item(
+                fl!("networks-rule", network = rule.connection.as_str(), profile = profile),
+                widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                    .on_press(Message::RemoveNetworkRule(index)),
+            ));
+        }
+
+        section
+    }
+
+    fn schedule_section(&self) -> Section<'_, Message> {
+        let schedule = &self.config.schedule;
+        let mut section = settings::section().title(fl!("schedule")).add(
+            settings::item::builder(fl!("schedule-enabled"))
+                .description(fl!("schedule-description"))
+                .toggler(schedule.enabled, Message::ToggleSchedule),
+        );
+
+        if schedule.enabled {
+            let days = (0..7).fold(widget::row().spacing(4), |row, day| {
+                let button = if schedule.days.contains(&day) {
+                    widget::button::suggested(day_label(day))
+                } else {
+                    widget::button::standard(day_label(day))
+                };
+                row.push(button.on_press(Message::ToggleScheduleDay(day)))
+            });
+
+            section = section
+                .add(days)
+                .add(settings::item(
+                    fl!("schedule-start"),
+                    widget::text_input("09:00", &self.schedule_start_input)
+                        .on_input(Message::ScheduleStartChanged),
+                ))
+                .add(settings::item(
+                    fl!("schedule-end"),
+                    widget::text_input("18:00", &self.schedule_end_input)
+                        .on_input(Message::ScheduleEndChanged),
+                ));
+        }
+
+        section
+    }
+
+    fn audio_section(&self) -> Section<'_, Message> {
+        let audio = &self.config.audio;
+        let selected_sink = audio
+            .secondary_sink
+            .as_ref()
+            .and_then(|name| self.sinks.iter().position(|sink| &sink.name == name));
+
+        let mut section = settings::section()
+            .title(fl!("audio"))
+            .add(settings::item(
+                fl!("pause-local-media"),
+                widget::toggler(audio.pause_local_media).on_toggle(Message::TogglePauseLocalMedia),
+            ));
+
+        if audio.pause_local_media {
+            section = section.add(settings::item(
+                fl!("resume-local-media"),
+                widget::toggler(audio.resume_local_media)
+                    .on_toggle(Message::ToggleResumeLocalMedia),
+            ));
+        }
+
+        let equalizer = self.config.equalizer();
+        section = section.add(
+            settings::item::builder(fl!("equalizer"))
+                .description(fl!("equalizer-description"))
+                .toggler(equalizer.enabled, Message::ToggleEqualizer),
+        );
+
+        if equalizer.enabled {
+            section = section
+                .add(settings::item(
+                    fl!("equalizer-bass"),
+                    widget::slider(-12..=12, equalizer.bass, Message::BassChanged),
+                ))
+                .add(settings::item(
+                    fl!("equalizer-treble"),
+                    widget::slider(-12..=12, equalizer.treble, Message::TrebleChanged),
+                ))
+                .add(
+                    settings::item::builder(fl!("loudness"))
+                        .description(fl!("loudness-description"))
+                        .toggler(equalizer.loudness, Message::ToggleLoudness),
+                );
+        }
+
+        let recording = &audio.recording;
+        section = section.add(
+            settings::item::builder(fl!("recording"))
+                .description(fl!("recording-description"))
+                .toggler(recording.enabled, Message::ToggleRecording),
+        );
+
+        if recording.enabled {
+            let default_dir = RecordingConfig::default().directory();
+            let dir = recording
+                .directory
+                .as_ref()
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            section = section
+                .add(settings::item(
+                    fl!("recording-format"),
+                    widget::dropdown(
+                        &self.recording_format_labels,
+                        Some(recording.format as usize),
+                        Message::SelectRecordingFormat,
+                    ),
+                ))
+                .add(settings::item(
+                    fl!("recording-dir"),
+                    widget::text_input(default_dir.to_string_lossy().into_owned(), dir)
+                        .on_input(Message::RecordingDirChanged),
+                ));
+        }
+
+        if !audio.snapcast.enabled {
+            section = section.add(
+                settings::item::builder(fl!("output-sink"))
+                    .description(fl!("output-sink-description"))
+                    .control(widget::dropdown(
+                        &self.audio_output_labels,
+                        Some(self.selected_output()),
+                        Message::SelectOutputSink,
+                    )),
+            );
+        }
+
+        if !audio.snapcast.enabled && !self.sinks.is_empty() {
+            section = section.add(
+                settings::item::builder(fl!("sink-delay"))
+                    .description(fl!("sink-delay-description"))
+                    .control(
+                        widget::row()
+                            .spacing(8)
+                            .push(widget::dropdown(
+                                &self.sink_labels,
+                                self.delay_sink,
+                                Message::SelectDelaySink,
+                            ))
+                            .push(
+                                widget::text_input("0", &self.sink_delay_input)
+                                    .on_input(Message::SinkDelayChanged)
+                                    .width(60),
+                            ),
+                    ),
+            );
+        }
+
+        section = section.add(settings::item(
+            fl!("duplicate-audio"),
+            widget::toggler(audio.duplicate).on_toggle(Message::ToggleDuplicateAudio),
+        ));
+
+        if audio.duplicate {
+            section = section.add(settings::item(
+                fl!("secondary-sink"),
+                widget::dropdown(&self.sink_labels, selected_sink, Message::SelectSecondarySink),
+            ));
+        }
+
+        section = section.add(settings::item(
+            fl!("snapcast"),
+            widget::toggler(audio.snapcast.enabled).on_toggle(Message::ToggleSnapcast),
+        ));
+
+        if audio.snapcast.enabled {
+            let status = match self.snapcast_status {
+                Some(SnapcastStatus::Reachable) => fl!("snapcast-reachable"),
+                Some(SnapcastStatus::Unreachable) => fl!("snapcast-unreachable"),
+                None => String::new(),
+            };
+
+            section = section
+                .add(settings::item(
+                    fl!("snapcast-host"),
+                    widget::text_input("127.0.0.1", &audio.snapcast.host)
+                        .on_input(Message::SnapcastHostChanged),
+                ))
+                .add(settings::item(
+                    fl!("snapcast-port"),
+                    widget::text_input("4953", &self.snapcast_port_input)
+                        .on_input(Message::SnapcastPortChanged),
+                ))
+                .add(settings::item(fl!("snapcast-status"), widget::text::body(status)));
+        }
+
+        section.add(self.reset_item(SettingsSection::Audio, fl!("reset-audio-description")))
+    }
+
+    fn viewer_section(&self) -> Section<'_, Message> {
+        let viewer = &self.config.viewer;
+        let selected_output = self
+            .outputs
+            .iter()
+            .position(|output| output == &self.config.video.output)
+            .map_or(0, |index| index + 1);
+
+        let selected_gpu = self
+            .gpus
+            .iter()
+            .position(|gpu| gpu.node == self.config.video.gpu)
+            .map_or(0, |index| index + 1);
+
+        let codec_description = match self.h265_available {
+            Some(false) if self.config.stream_quality().codec == Codec::H265 => {
+                fl!("codec-h265-unavailable")
+            }
+            _ => fl!("codec-description"),
+        };
+
+        let pip = &self.config.video.pip;
+        let mut section = settings::section()
+            .title(fl!("video"))
+            .add(
+                settings::item::builder(fl!("output"))
+                    .description(fl!("output-description"))
+                    .control(widget::dropdown(
+                        &self.output_labels,
+                        Some(selected_output),
+                        Message::SelectOutput,
+                    )),
+            )
+            .add(
+                settings::item::builder(fl!("gpu"))
+                    .description(fl!("gpu-description"))
+                    .control(widget::dropdown(
+                        &self.gpu_labels,
+                        Some(selected_gpu),
+                        Message::SelectGpu,
+                    )),
+            )
+            .add(
+                settings::item::builder(fl!("display-server"))
+                    .description(fl!("display-server-description"))
+                    .control(widget::dropdown(
+                        &self.display_server_labels,
+                        Some(self.config.video.display_server as usize),
+                        Message::SelectDisplayServer,
+                    )),
+            );
+
+        if self.capabilities.h265() {
+            section = section.add(
+                settings::item::builder(fl!("codec"))
+                    .description(codec_description)
+                    .control(widget::dropdown(
+                        &self.codec_labels,
+                        Some(self.config.stream_quality().codec as usize),
+                        Message::SelectCodec,
+                    )),
+            );
+        }
+
+        section = section
+            .add(
+                settings::item::builder(fl!("max-bitrate"))
+                    .description(fl!("max-bitrate-description"))
+                    .control(
+                        widget::text_input(fl!("max-bitrate-unlimited"), &self.max_bitrate_input)
+                            .on_input(Message::MaxBitrateChanged),
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("embedded-mirror"))
+                    .description(fl!("embedded-mirror-description"))
+                    .toggler(self.config.video.embedded, Message::ToggleEmbeddedMirror),
+            )
+            .add(
+                settings::item::builder(fl!("extended-display"))
+                    .description(fl!("extended-display-description"))
+                    .toggler(self.config.video.extended_display, Message::ToggleExtendedDisplay),
+            )
+            .add(
+                settings::item::builder(fl!("pip"))
+                    .description(fl!("pip-description"))
+                    .toggler(pip.enabled, Message::TogglePip),
+            )
+            .add(
+                settings::item::builder(fl!("signage"))
+                    .description(fl!("signage-description"))
+                    .toggler(self.config.video.signage, Message::ToggleSignage),
+            );
+
+        if pip.enabled {
+            section = section
+                .add(settings::item(
+                    fl!("pip-corner"),
+                    widget::dropdown(
+                        &self.corner_labels,
+                        Some(pip.corner as usize),
+                        Message::SelectPipCorner,
+                    ),
+                ))
+                .add(settings::item(
+                    fl!("pip-size"),
+                    widget::dropdown(
+                        &self.pip_size_labels,
+                        Some(pip.size as usize),
+                        Message::SelectPipSize,
+                    ),
+                ));
+        }
+
+        if self.config.video.embedded || pip.enabled {
+            let scaling = &self.config.video.scaling;
+            section = section
+                .add(settings::item(
+                    fl!("scale-method"),
+                    widget::dropdown(
+                        &self.scale_method_labels,
+                        Some(scaling.method as usize),
+                        Message::SelectScaleMethod,
+                    ),
+                ))
+                .add(
+                    settings::item::builder(fl!("integer-scaling"))
+                        .description(fl!("integer-scaling-description"))
+                        .toggler(scaling.integer, Message::ToggleIntegerScaling),
+                );
+        }
+
+        let picture = &self.config.video.picture;
+        section = section
+            .add(
+                settings::item::builder(fl!("brightness"))
+                    .description(fl!("picture-description"))
+                    .control(widget::slider(
+                        -100..=100,
+                        picture.brightness,
+                        Message::BrightnessChanged,
+                    )),
+            )
+            .add(settings::item(
+                fl!("contrast"),
+                widget::slider(0..=200, picture.contrast, Message::ContrastChanged),
+            ))
+            .add(settings::item(
+                fl!("saturation"),
+                widget::slider(0..=200, picture.saturation, Message::SaturationChanged),
+            ));
+
+        if !picture.is_neutral() {
+            section = section.add(settings::item(
+                fl!("picture"),
+                widget::button::standard(fl!("picture-reset")).on_press(Message::ResetPicture),
+            ));
+        }
+
+        let viewer_url = viewer::url(viewer, self.lan_address());
+        section
+            .add(
+                settings::item::builder(fl!("browser-viewer"))
+                    .description(fl!("browser-viewer-description", url = viewer_url))
+                    .toggler(viewer.enabled, Message::ToggleViewer),
+            )
+            .add(self.reset_item(SettingsSection::Video, fl!("reset-video-description")))
+    }
+
+    fn phone_section(&self) -> Section<'_, Message> {
+        let companion = &self.config.companion;
+        let description = if companion.clipboard {
+            let url = companion::url(self.lan_address(), companion.port);
+            fl!("clipboard-sharing-address", url = format!("{url}clipboard?token={}", companion.token))
+        } else {
+            fl!("clipboard-sharing-description")
+        };
+
+        let mut section = settings::section()
+            .title(fl!("phone"))
+            .add(
+                settings::item::builder(fl!("clipboard-sharing"))
+                    .description(description)
+                    .toggler(companion.clipboard, Message::ToggleClipboardSharing),
+            )
+            .add(
+                settings::item::builder(fl!("file-receiving"))
+                    .description(fl!("file-receiving-description"))
+                    .toggler(companion.files, Message::ToggleFileReceiving),
+            );
+
+        if companion.files {
+            let default_dir = CompanionConfig::default().download_dir();
+            let dir = companion
+                .download_dir
+                .as_ref()
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            section = section.add(settings::item(
+                fl!("download-dir"),
+                widget::text_input(default_dir.to_string_lossy().into_owned(), dir)
+                    .on_input(Message::DownloadDirChanged),
+            ));
+        }
+
+        section
+    }
+
+    fn integrations_section(&self) -> Section<'_, Message> {
+        let mqtt = &self.config.integrations.mqtt;
+        let mut section = settings::section()
+            .title(fl!("integrations"))
+            .add(
+                settings::item::builder(fl!("kdeconnect"))
+                    .description(fl!("kdeconnect-description"))
+                    .toggler(self.config.integrations.kdeconnect, Message::ToggleKdeConnect),
+            )
+            .add(
+                settings::item::builder(fl!("mqtt"))
+                    .description(fl!("mqtt-description"))
+                    .toggler(mqtt.enabled, Message::ToggleMqtt),
+            );
+
+        if mqtt.enabled {
+            section = section
+                .add(settings::item(
+                    fl!("mqtt-host"),
+                    widget::text_input("localhost", &mqtt.host).on_input(Message::MqttHostChanged),
+                ))
+                .add(settings::item(
+                    fl!("mqtt-port"),
+                    widget::text_input("1883", &self.mqtt_port_input)
+                        .on_input(Message::MqttPortChanged),
+                ))
+                .add(settings::item(
+                    fl!("mqtt-username"),
+                    widget::text_input("", &mqtt.username).on_input(Message::MqttUsernameChanged),
+                ))
+                .add(settings::item(
+                    fl!("mqtt-password"),
+                    widget::secure_input("", &mqtt.password, None, true)
+                        .on_input(Message::MqttPasswordChanged),
+                ));
+        }
+
+        let rest = &self.config.integrations.rest;
+        let rest_description = match self.rest_api() {
+            Some(api) => fl!("rest-address", url = rest::url(&api, self.lan_address())),
+            None => fl!("rest-description"),
+        };
+        section = section.add(
+            settings::item::builder(fl!("rest"))
+                .description(rest_description)
+                .toggler(rest.enabled, Message::ToggleRest),
+        );
+        if rest.enabled {
+            section = section.add(
+                settings::item::builder(fl!("rest-lan"))
+                    .description(fl!("rest-lan-description"))
+                    .toggler(rest.lan, Message::ToggleRestLan),
+            );
+        }
+
+        let webhook = &self.config.integrations.webhook;
+        section = section.add(
+            settings::item::builder(fl!("webhook"))
+                .description(fl!("webhook-description"))
+                .toggler(webhook.enabled, Message::ToggleWebhook),
+        );
+        if webhook.enabled {
+            // Invalid values are pointed out where they are entered
+            let input = self.webhook_url_input.trim();
+            let url = settings::item::builder(fl!("webhook-url"));
+            let url = match webhook::parse_url(input) {
+                Err(_) if !input.is_empty() => url.description(fl!("invalid-webhook-url")),
+                _ => url,
+            };
+            section = section.add(url.control(
+                widget::text_input("http://localhost:5678/webhook/airtray", &self.webhook_url_input)
+                    .on_input(Message::WebhookUrlChanged),
+            ));
+        }
+
+        let hooks = &self.config.integrations.hooks;
+        for (hook, title, description) in [
+            (Hook::Start, fl!("hook-start"), fl!("hook-start-description")),
+            (Hook::Stop, fl!("hook-stop"), fl!("hook-stop-description")),
+            (
+                Hook::ClientConnect,
+                fl!("hook-client-connect"),
+                fl!("hook-client-connect-description"),
+            ),
+            (
+                Hook::ClientDisconnect,
+                fl!("hook-client-disconnect"),
+                fl!("hook-client-disconnect-description"),
+            ),
+        ] {
+            section = section.add(
+                settings::item::builder(title)
+                    .description(description)
+                    .control(
+                        widget::text_input("", hooks.command(hook))
+                            .on_input(move |command| Message::HookChanged(hook, command)),
+                    ),
+            );
+        }
+
+        section
+    }
+
+    fn lock_section(&self) -> Section<'_, Message> {
+        let lock = &self.config.lock;
+        let mut section = settings::section().title(fl!("lock")).add(
+            settings::item::builder(fl!("lock-method"))
+                .description(fl!("lock-description"))
+                .control(widget::dropdown(
+                    &self.lock_labels,
+                    Some(lock.method as usize),
+                    Message::SelectLockMethod,
+                )),
+        );
+
+        if lock.method == LockMethod::Pin {
+            section = section.add(settings::item(
+                fl!("lock-pin"),
+                widget::secure_input("", &lock.pin, None, true).on_input(Message::LockPinChanged),
+            ));
+        }
+
+        section = section
+            .add(
+                settings::item::builder(fl!("random-pin"))
+                    .description(fl!("random-pin-description"))
+                    .toggler(self.config.random_pin, Message::ToggleRandomPin),
+            )
+            .add(
+                settings::item::builder(fl!("kiosk"))
+                    .description(fl!("kiosk-description"))
+                    .toggler(self.config.kiosk, Message::ToggleKiosk),
+            )
+            .add(
+                settings::item::builder(fl!("active-seat-only"))
+                    .description(fl!("active-seat-only-description"))
+                    .toggler(self.config.active_seat_only, Message::ToggleActiveSeatOnly),
+            );
+
+        section.add(self.reset_item(SettingsSection::Security, fl!("reset-security-description")))
+    }
+
+    fn advanced_section(&self) -> Section<'_, Message> {
+        let import_description = match &self.uxplayrc_skipped {
+            Some(skipped) if skipped.is_empty() => fl!("uxplayrc-imported"),
+            Some(skipped) => fl!("uxplayrc-imported-skipped", options = skipped.join(", ")),
+            None => fl!("uxplayrc-import-description"),
+        };
+        let export_description = match &self.uxplayrc_exported {
+            Some(path) => fl!("uxplayrc-exported", path = path.display().to_string()),
+            None => fl!("uxplayrc-export-description"),
+        };
+        let backup_description = match &self.keys_backed_up {
+            Some(path) => fl!("keys-backed-up", path = path.display().to_string()),
+            None => fl!("keys-backup-description"),
+        };
+        let restore_description = match self.keys_restored {
+            true => fl!("keys-restored"),
+            false => fl!("keys-restore-description"),
+        };
+
+        // Invalid values are pointed out where they are entered
+        let udp_port_valid = match self.udp_port_input.trim() {
+            "" => true,
+            port => port.parse().is_ok_and(validation::udp_port),
+        };
+        let udp_ports_description = if udp_port_valid {
+            fl!("udp-ports-description")
+        } else {
+            fl!("invalid-udp-port", last = u16::MAX - ports::UDP_COUNT + 1)
+        };
+        let missing_dirs: Vec<String> = self
+            .invalid
+            .iter()
+            .filter_map(|invalid| match invalid {
+                Invalid::SearchPath(dir) => Some(dir.display().to_string()),
+                _ => None,
+            })
+            .collect();
+        let search_paths_description = if !missing_dirs.is_empty() {
+            fl!("invalid-search-paths", dirs = missing_dirs.join(", "))
+        } else if self.invalid.contains(&Invalid::Uxplay) {
+            fl!("invalid-uxplay")
+        } else {
+            fl!("search-paths-description")
+        };
+        let container_description = self
+            .invalid
+            .iter()
+            .find_map(|invalid| match invalid {
+                Invalid::Container(program) => {
+                    Some(fl!("invalid-container", program = program.as_str()))
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| fl!("container-description"));
+
+        settings::section()
+            .title(fl!("advanced"))
+            .add(
+                settings::item::builder(fl!("uxplayrc-import"))
+                    .description(import_description)
+                    .control(
+                        widget::button::standard(fl!("uxplayrc-import-button"))
+                            .on_press(Message::ImportUxplayrc),
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("uxplayrc-export"))
+                    .description(export_description)
+                    .control(
+                        widget::button::standard(fl!("uxplayrc-export-button"))
+                            .on_press(Message::ExportUxplayrc),
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("keys-backup"))
+                    .description(backup_description)
+                    .control(
+                        widget::button::standard(fl!("keys-backup-button"))
+                            .on_press(Message::BackupKeys),
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("keys-restore"))
+                    .description(restore_description)
+                    .control(
+                        widget::button::standard(fl!("keys-restore-button"))
+                            .on_press(Message::RestoreKeys),
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("ip-version"))
+                    .description(fl!("ip-version-description"))
+                    .control(widget::dropdown(
+                        &self.ip_version_labels,
+                        Some(self.config.ip_version as usize),
+                        Message::SelectIpVersion,
+                    )),
+            )
+            .add(
+                settings::item::builder(fl!("mdns"))
+                    .description(fl!("mdns-description"))
+                    .control(widget::dropdown(
+                        &self.mdns_labels,
+                        Some(self.config.mdns as usize),
+                        Message::SelectMdnsResponder,
+                    )),
+            )
+            .add(
+                settings::item::builder(fl!("udp-ports"))
+                    .description(udp_ports_description)
+                    .control(
+                        widget::text_input(fl!("udp-ports-automatic"), &self.udp_port_input)
+                            .on_input(Message::UdpPortChanged),
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("search-paths"))
+                    .description(search_paths_description)
+                    .control(
+                        widget::text_input("/run/current-system/sw/bin", &self.search_paths_input)
+                            .on_input(Message::SearchPathsChanged),
+                    ),
+            )
+            .add(
+                settings::item::builder(fl!("container"))
+                    .description(container_description)
+                    .control(
+                        widget::text_input(
+                            "distrobox enter media --",
+                            &self.config.launch.container,
+                        )
+                        .on_input(Message::ContainerChanged),
+                    ),
+            )
+    }
+
+    fn troubleshooting_section(&self) -> Section<'_, Message> {
+        let description = match &self.debug_bundle {
+            Some(path) => fl!("debug-bundle-saved", path = path.display().to_string()),
+            None => fl!("debug-bundle-description"),
+        };
+
+        settings::section()
+            .title(fl!("troubleshooting"))
+            .add(
+                settings::item::builder(fl!("debug-mode"))
+                    .description(fl!("debug-mode-description"))
+                    .toggler(self.config.debug, Message::ToggleDebug),
+            )
+            .add(settings::item(
+                fl!("log"),
+                widget::button::standard(fl!("log-show"))
+                    .on_press(Message::OpenPage(Page::Log)),
+            ))
+            .add(
+                settings::item::builder(fl!("debug-bundle"))
+                    .description(description)
+                    .control(
+                        widget::button::standard(fl!("debug-bundle"))
+                            .on_press(Message::SaveDebugBundle),
+                    ),
+            )
+            .add(self.reset_item(SettingsSection::All, fl!("reset-all-description")))
+    }
+
+    /// Resets the settings of `section`, asking again first.
+    fn reset_item(&self, section: SettingsSection, description: String) -> Element<'_, Message> {
+        if self.confirm_reset != Some(section) {
+            return settings::item::builder(fl!("reset"))
+                .description(description)
+                .control(
+                    widget::button::standard(fl!("reset-button"))
+                        .on_press(Message::AskReset(section)),
+                )
+                .into();
+        }
+
+        settings::item::builder(fl!("reset-confirm"))
+            .description(fl!("reset-confirm-description"))
+            .control(
+                widget::row()
+                    .spacing(8)
+                    .push(
+                        widget::button::standard(fl!("reset-cancel"))
+                            .on_press(Message::CancelReset),
+                    )
+                    .push(
+                        widget::button::destructive(fl!("reset-button"))
+                            .on_press(Message::Reset(section)),
+                    ),
+            )
+            .into()
+    }
+}
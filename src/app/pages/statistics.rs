@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::Path;
+
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+
+use super::{back_button, format_bytes, format_duration};
+use crate::app::Message;
+use crate::backend::AudioCodec;
+use crate::fl;
+use crate::health::StreamHealth;
+use crate::history::{ExportFormat, Stats};
+use crate::usage::Usage;
+
+/// Totals aggregated from the session history, the current stream's health
+/// and audio format, and the receiver's resource use. `exported` is where the
+/// history was last exported to.
+pub fn view<'a>(
+    stats: &'a Stats,
+    health: &StreamHealth,
+    audio_codec: Option<AudioCodec>,
+    usage: Option<Usage>,
+    exported: Option<&Path>,
+) -> Element<'a, Message> {
+    let average = stats
+        .average
+        .map(format_duration)
+        .unwrap_or_else(|| fl!("stats-none"));
+    let top_device = match &stats.top_device {
+        Some((name, count)) => fl!("stats-top-device-value", name = name.as_str(), count = *count),
+        None => fl!("stats-none"),
+    };
+
+    let section = settings::section()
+        .title(fl!("statistics"))
+        .add(settings::item(
+            fl!("stats-this-week"),
+            widget::text::body(format_duration(stats.this_week)),
+        ))
+        .add(settings::item(
+            fl!("stats-sessions"),
+            widget::text::body(stats.sessions.to_string()),
+        ))
+        .add(settings::item(fl!("stats-average"), widget::text::body(average)))
+        .add(settings::item(fl!("stats-top-device"), widget::text::body(top_device)));
+
+    let note = match stats.recent.is_empty() {
+        true => fl!("stats-none"),
+        false => fl!("stats-recent-description"),
+    };
+    let export_description = match exported {
+        Some(path) => fl!("history-exported", path = path.display().to_string()),
+        None => fl!("history-export-description"),
+    };
+    let mut recent = settings::section()
+        .title(fl!("stats-recent"))
+        .add(widget::text::caption(note))
+        .add(
+            settings::item::builder(fl!("history-export"))
+                .description(export_description)
+                .control(
+                    widget::row()
+                        .spacing(8)
+                        .push(
+                            widget::button::standard("CSV")
+                                .on_press(Message::ExportHistory(ExportFormat::Csv)),
+                        )
+                        .push(
+                            widget::button::standard("JSON")
+                                .on_press(Message::ExportHistory(ExportFormat::Json)),
+                        ),
+                ),
+        );
+    for session in &stats.recent {
+        let when = fl!(
+            "stats-recent-session",
+            start = session.start.format("%a %d %b %H:%M").to_string(),
+            duration = format_duration(session.duration())
+        );
+        // Not known for sessions from before it was recorded
+        let data = match session.bytes {
+            0 => fl!("stats-none"),
+            bytes => format_bytes(bytes),
+        };
+        recent = recent.add(
+            settings::item::builder(session.device_name.as_str())
+                .description(when)
+                .control(widget::text::body(data)),
+        );
+    }
+
+    let (dropped, resent) = health.counts();
+    let audio_codec = match audio_codec {
+        Some(codec) => fl!("stream-audio-codec-value", codec = codec.name()),
+        None => fl!("stats-none"),
+    };
+    let health = settings::section()
+        .title(fl!("stream-health"))
+        .add(settings::item(fl!("stream-audio-codec"), widget::text::body(audio_codec)))
+        .add(settings::item(
+            fl!("stream-health-dropped"),
+            widget::text::body(dropped.to_string()),
+        ))
+        .add(settings::item(
+            fl!("stream-health-resent"),
+            widget::text::body(resent.to_string()),
+        ));
+
+    let (cpu, memory) = match usage {
+        Some(usage) => (
+            fl!("usage-cpu-value", percent = format!("{:.0}", usage.cpu)),
+            fl!("usage-memory-value", mib = usage.memory),
+        ),
+        None => (fl!("stats-none"), fl!("stats-none")),
+    };
+    let usage = settings::section()
+        .title(fl!("usage"))
+        .add(settings::item(fl!("usage-cpu"), widget::text::body(cpu)))
+        .add(settings::item(fl!("usage-memory"), widget::text::body(memory)));
+
+    widget::column()
+        .spacing(8)
+        .padding(5)
+        .push(back_button())
+        .push(section)
+        .push(recent)
+        .push(health)
+        .push(usage)
+        .into()
+}
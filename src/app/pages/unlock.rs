@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::widget::{self, settings};
+use cosmic::Element;
+
+use super::back_button;
+use crate::app::{AirTray, Message};
+use crate::config::LockMethod;
+use crate::fl;
+
+impl AirTray {
+    /// PIN field or polkit button guarding the settings.
+    pub(super) fn view_unlock(&self) -> Element<Message> {
+        let mut section = settings::section()
+            .title(fl!("locked"))
+            .add(widget::text::caption(fl!("locked-description")));
+
+        section = match self.config.lock_method() {
+            LockMethod::Pin => section.add(settings::item(
+                fl!("lock-pin"),
+                widget::secure_input("", &self.unlock_input, None, true)
+                    .on_input(Message::UnlockInputChanged)
+                    .on_submit(|_| Message::Unlock),
+            )),
+            LockMethod::Polkit | LockMethod::None => section,
+        };
+
+        let button = widget::button::suggested(fl!("unlock"));
+        section = section.add(if self.unlocking {
+            button
+        } else {
+            button.on_press(Message::Unlock)
+        });
+
+        if self.unlock_failed {
+            section = section.add(widget::text::body(fl!("unlock-failed")));
+        }
+
+        widget::column()
+            .spacing(8)
+            .padding(5)
+            .push(back_button())
+            .push(section)
+            .into()
+    }
+}
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The picture-in-picture mirror: an overlay layer surface in a screen corner
+//! showing the frames UXPlay streams to the applet.
+
+use cosmic::app::Task;
+use cosmic::iced::window::Id;
+use cosmic::iced_runtime::platform_specific::wayland::layer_surface::{
+    IcedOutput, SctkLayerSurfaceSettings,
+};
+use cosmic::iced_winit::commands::layer_surface::{
+    destroy_layer_surface, get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+};
+use cosmic::Element;
+
+use super::{AirTray, Message};
+use crate::config::Corner;
+
+impl AirTray {
+    /// Opens the window once frames arrive and closes it when the session ends.
+    pub(super) fn sync_pip(&mut self) -> Task<Message> {
+        let wanted = self.config.video.pip.enabled && self.has_clients() && self.frame.is_some();
+
+        match self.pip_window {
+            None if wanted => {
+                let id = Id::unique();
+                self.pip_window = Some(id);
+
+                let pip = &self.config.video.pip;
+                let (width, height) = pip.size.dimensions();
+                let anchor = match pip.corner {
+                    Corner::TopLeft => Anchor::TOP | Anchor::LEFT,
+                    Corner::TopRight => Anchor::TOP | Anchor::RIGHT,
+                    Corner::BottomLeft => Anchor::BOTTOM | Anchor::LEFT,
+                    Corner::BottomRight => Anchor::BOTTOM | Anchor::RIGHT,
+                };
+
+                get_layer_surface(SctkLayerSurfaceSettings {
+                    id,
+                    layer: Layer::Overlay,
+                    keyboard_interactivity: KeyboardInteractivity::None,
+                    anchor,
+                    output: IcedOutput::Active,
+                    namespace: String::from("airtray-pip"),
+                    size: Some((Some(width), Some(height))),
+                    ..Default::default()
+                })
+            }
+            Some(id) if !wanted => {
+                self.pip_window = None;
+                destroy_layer_surface(id)
+            }
+            _ => Task::none(),
+        }
+    }
+
+    /// Contents of the picture-in-picture window.
+    pub(super) fn view_pip(&self) -> Element<Message> {
+        self.view_frame()
+    }
+}
@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Connect instructions on screen, like conference room signage: a layer
+//! surface in the middle of the screen showing the receiver's name and PIN
+//! while it waits for a sender, gone as soon as one connects.
+
+use cosmic::app::Task;
+use cosmic::iced::window::Id;
+use cosmic::iced::{Alignment, Length};
+use cosmic::iced_runtime::platform_specific::wayland::layer_surface::{
+    IcedOutput, SctkLayerSurfaceSettings,
+};
+use cosmic::iced_winit::commands::layer_surface::{
+    destroy_layer_surface, get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+};
+use cosmic::widget;
+use cosmic::Element;
+
+use super::{AirTray, Message};
+use crate::fl;
+use crate::receiver::ReceiverState;
+
+/// Size of the surface, in logical pixels.
+const SIZE: (u32, u32) = (640, 240);
+
+impl AirTray {
+    /// Shows the instructions while the receiver waits for a sender.
+    pub(super) fn sync_signage(&mut self) -> Task<Message> {
+        let waiting = matches!(
+            self.receiver.state(),
+            ReceiverState::Standby | ReceiverState::Running { .. }
+        );
+        let wanted = self.config.video.signage && waiting && !self.in_grace_or_session();
+
+        match self.signage_window {
+            None if wanted => {
+                let id = Id::unique();
+                self.signage_window = Some(id);
+                get_layer_surface(SctkLayerSurfaceSettings {
+                    id,
+                    // Under the windows, like the wallpaper
+                    layer: Layer::Bottom,
+                    keyboard_interactivity: KeyboardInteractivity::None,
+                    anchor: Anchor::empty(),
+                    output: IcedOutput::Active,
+                    namespace: String::from("airtray-signage"),
+                    size: Some((Some(SIZE.0), Some(SIZE.1))),
+                    ..Default::default()
+                })
+            }
+            Some(id) if !wanted => {
+                self.signage_window = None;
+                destroy_layer_surface(id)
+            }
+            _ => Task::none(),
+        }
+    }
+
+    /// Contents of the instructions surface.
+    pub(super) fn view_signage(&self) -> Element<Message> {
+        let name = self.config.receiver_name();
+        let mut column = widget::column()
+            .push(widget::text::title1(fl!("signage-connect", name = name)))
+            .spacing(12)
+            .align_x(Alignment::Center);
+
+        let pin = match self.config.random_pin {
+            true => self.session_pin.as_deref(),
+            false => self
+                .receiver
+                .config
+                .active_profile()
+                .map(|profile| profile.pin.as_str())
+                .filter(|pin| !pin.is_empty()),
+        };
+        if let Some(pin) = pin {
+            column = column.push(widget::text::title3(fl!("signage-pin", pin = pin)));
+        }
+
+        widget::container(column)
+            .center(Length::Fill)
+            .class(cosmic::style::Container::Background)
+            .into()
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Remote control of Apple TVs over the Companion protocol, through pyatv's
+//! `atvremote`. Devices have to be paired once with
+//! `atvremote --id <id> --protocol companion pair`; pyatv keeps the credentials.
+
+use std::process::Output;
+
+use tokio::process::Command;
+
+use crate::error::AirTrayError;
+use crate::host;
+
+/// An Apple TV found on the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppleTv {
+    /// Identifier pyatv addresses the device by.
+    pub id: String,
+    pub name: String,
+    pub address: String,
+}
+
+/// A button of the Apple TV remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Menu,
+    Home,
+    PlayPause,
+}
+
+impl RemoteKey {
+    fn command(self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Select => "select",
+            Self::Menu => "menu",
+            Self::Home => "home",
+            Self::PlayPause => "play_pause",
+        }
+    }
+}
+
+/// Lists the Apple TVs on the network that support the Companion protocol.
+pub async fn scan() -> Result<Vec<AppleTv>, AirTrayError> {
+    let output = atvremote(&["scan"]).await?;
+    Ok(parse_scan(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Presses `key` on the Apple TV with the given id.
+pub async fn press(id: String, key: RemoteKey) -> Result<(), AirTrayError> {
+    atvremote(&["--id", &id, key.command()]).await.map(|_| ())
+}
+
+async fn atvremote(args: &[&str]) -> Result<Output, AirTrayError> {
+    let output = Command::from(host::command("atvremote"))
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| AirTrayError::spawn("atvremote", e))?;
+
+    if output.status.success() {
+        Ok(output)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(AirTrayError::ControlError(
+            stderr.lines().last().unwrap_or_default().trim().to_string(),
+        ))
+    }
+}
+
+/// Parses the device blocks printed by `atvremote scan`:
+///
+/// ```text
+///        Name: Living Room
+///     Address: 192.168.1.30
+/// Identifiers:
+///  - 6D9D4E6A-9D3B-4B8C-A1F2-0C5E0A7E3C11
+/// Services:
+///  - Protocol: Companion, Port: 49153, Credentials: None, ...
+/// ```
+fn parse_scan(output: &str) -> Vec<AppleTv> {
+    let mut devices = Vec::new();
+
+    for block in output.split("\n\n") {
+        let mut name = None;
+        let mut address = None;
+        let mut id = None;
+        let mut companion = false;
+        let mut in_identifiers = false;
+
+        for line in block.lines().map(str::trim) {
+            if let Some(value) = line.strip_prefix("Name:") {
+                name = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("Address:") {
+                address = Some(value.trim().to_string());
+            } else if line == "Identifiers:" {
+                in_identifiers = true;
+                continue;
+            } else if let Some(value) = line.strip_prefix("- ") {
+                if in_identifiers && id.is_none() {
+                    id = Some(value.trim().to_string());
+                }
+                companion |= value.starts_with("Protocol: Companion");
+                continue;
+            }
+            in_identifiers = false;
+        }
+
+        if let (Some(name), Some(address), Some(id), true) = (name, address, id, companion) {
+            devices.push(AppleTv { id, name, address });
+        }
+    }
+
+    devices
+}
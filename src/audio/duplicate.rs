@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use super::sinks::pactl;
+use crate::error::AirTrayError;
+
+/// Name of the combined sink the receiver plays into while duplication is active.
+const DUPLICATE_SINK: &str = "airtray_duplicate";
+
+/// A combine-sink that tees everything played into it to two sinks.
+///
+/// The module is unloaded again when this value is dropped.
+#[derive(Debug)]
+pub struct DuplicateOutput {
+    module: u32,
+}
+
+impl DuplicateOutput {
+    /// Loads a combine-sink forwarding to both `primary` and `secondary`.
+    pub fn new(primary: &str, secondary: &str) -> Result<Self, AirTrayError> {
+        let stdout = pactl(&[
+            "load-module",
+            "module-combine-sink",
+            &format!("sink_name={DUPLICATE_SINK}"),
+            &format!("slaves={primary},{secondary}"),
+            "sink_properties=device.description=AirTray",
+        ])?;
+
+        let module = stdout.trim().parse().map_err(|_| {
+            AirTrayError::AudioError(format!("unexpected module index from pactl: {stdout}"))
+        })?;
+
+        Ok(Self { module })
+    }
+
+    /// The sink name the receiver should play into.
+    pub fn sink_name(&self) -> &'static str {
+        DUPLICATE_SINK
+    }
+}
+
+impl Drop for DuplicateOutput {
+    fn drop(&mut self) {
+        if let Err(e) = pactl(&["unload-module", &self.module.to_string()]) {
+            println!("Failed to unload audio duplication module: {}", e);
+        }
+    }
+}
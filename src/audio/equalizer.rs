@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::PathBuf;
+use std::process::{Child, Stdio};
+
+use crate::config::Equalizer;
+use crate::error::AirTrayError;
+use crate::host;
+
+/// Name of the filtered sink the receiver plays into while the equalizer is on.
+const EQUALIZER_SINK: &str = "airtray_equalizer";
+
+/// Corner frequencies of the bass and treble shelves, in Hz.
+const BASS_FREQUENCY: f32 = 100.0;
+const TREBLE_FREQUENCY: f32 = 8000.0;
+
+/// Extra gain of the bass and treble shelves with loudness on, in dB.
+const LOUDNESS_BASS: i32 = 6;
+const LOUDNESS_TREBLE: i32 = 3;
+
+/// A PipeWire filter chain adjusting bass and treble of everything played
+/// into it, run as a `pipewire` instance of its own.
+///
+/// The filter goes away when this value is dropped.
+#[derive(Debug)]
+pub struct EqualizerOutput {
+    process: Child,
+}
+
+impl EqualizerOutput {
+    /// Starts the filter for `equalizer`, playing into the sink `target` or
+    /// the default one. `None` if it wouldn't change anything.
+    pub fn new(equalizer: Equalizer, target: Option<&str>) -> Result<Option<Self>, AirTrayError> {
+        let (mut bass, mut treble) = (equalizer.bass, equalizer.treble);
+        if equalizer.loudness {
+            bass += LOUDNESS_BASS;
+            treble += LOUDNESS_TREBLE;
+        }
+        if !equalizer.enabled || (bass == 0 && treble == 0) {
+            return Ok(None);
+        }
+
+        std::fs::write(config_path(), filter_chain(bass, treble, target))
+            .map_err(|e| AirTrayError::AudioError(format!("writing filter chain: {e}")))?;
+        let process = host::command("pipewire")
+            .arg("-c")
+            .arg(config_path())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|e| AirTrayError::spawn("pipewire", e))?;
+
+        Ok(Some(Self { process }))
+    }
+
+    /// The sink name the receiver should play into.
+    pub fn sink_name(&self) -> &'static str {
+        EQUALIZER_SINK
+    }
+}
+
+impl Drop for EqualizerOutput {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+fn config_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("airtray-equalizer.conf")
+}
+
+/// A PipeWire configuration with a bass and a treble shelf between a sink
+/// and its playback stream.
+fn filter_chain(bass: i32, treble: i32, target: Option<&str>) -> String {
+    let target = target
+        .map(|target| format!(" target.object = \"{target}\""))
+        .unwrap_or_default();
+
+    format!(
+        r#"context.properties = {{ log.level = 0 }}
+context.spa-libs = {{
+    audio.convert.* = audioconvert/libspa-audioconvert
+    support.* = support/libspa-support
+}}
+context.modules = [
+    {{ name = libpipewire-module-rt flags = [ ifexists nofail ] }}
+    {{ name = libpipewire-module-protocol-native }}
+    {{ name = libpipewire-module-client-node }}
+    {{ name = libpipewire-module-adapter }}
+    {{ name = libpipewire-module-filter-chain
+        args = {{
+            node.description = "AirTray equalizer"
+            media.name = "AirTray equalizer"
+            filter.graph = {{
+                nodes = [
+                    {{ type = builtin name = bass label = bq_lowshelf
+                       control = {{ "Freq" = {BASS_FREQUENCY} "Q" = 0.7 "Gain" = {bass} }} }}
+                    {{ type = builtin name = treble label = bq_highshelf
+                       control = {{ "Freq" = {TREBLE_FREQUENCY} "Q" = 0.7 "Gain" = {treble} }} }}
+                ]
+                links = [ {{ output = "bass:Out" input = "treble:In" }} ]
+            }}
+            audio.channels = 2
+            audio.position = [ FL FR ]
+            capture.props = {{ node.name = "{EQUALIZER_SINK}" media.class = Audio/Sink }}
+            playback.props = {{ node.name = "{EQUALIZER_SINK}.output" node.passive = true{target} }}
+        }}
+    }}
+]
+"#
+    )
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Level meter for the received audio, read by monitoring the receiver's
+//! playback stream itself, so it works even if the sink is muted.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use cosmic::iced::futures::{SinkExt, Stream};
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+use super::{find_sink_input, stream_title};
+use crate::host;
+
+/// Sample rate the stream is monitored at; plenty for a level meter.
+const RATE: usize = 8000;
+/// Samples per reported level, i.e. 20 updates per second.
+const CHUNK: usize = RATE / 20;
+/// How often to look for the stream while nothing is playing.
+const RETRY: Duration = Duration::from_secs(1);
+
+/// Yields the peak level of the receiver's audio, from 0.0 to 1.0, for as
+/// long as the stream is polled.
+pub fn levels(receiver_name: String) -> impl Stream<Item = f32> {
+    cosmic::iced::stream::channel(4, move |mut output| async move {
+        let title = stream_title(&receiver_name);
+
+        loop {
+            let lookup = title.clone();
+            let index = tokio::task::spawn_blocking(move || find_sink_input(&lookup))
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .flatten();
+
+            let Some(index) = index else {
+                let _ = output.send(0.0).await;
+                tokio::time::sleep(RETRY).await;
+                continue;
+            };
+
+            let child = Command::from(host::command("parec"))
+                .arg(format!("--monitor-stream={index}"))
+                .args(["--raw", "--format=s16le", "--channels=1", "--latency-msec=50"])
+                .arg(format!("--rate={RATE}"))
+                .stdout(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn();
+            let mut child = match child {
+                Ok(child) => child,
+                Err(e) => {
+                    eprintln!("Failed to monitor the AirPlay stream: {}", e);
+                    return;
+                }
+            };
+
+            let Some(mut samples) = child.stdout.take() else {
+                return;
+            };
+            let mut buffer = [0u8; CHUNK * 2];
+            // Ends when the stream goes away, e.g. between tracks
+            while samples.read_exact(&mut buffer).await.is_ok() {
+                let peak = buffer
+                    .chunks_exact(2)
+                    .map(|sample| i16::from_le_bytes([sample[0], sample[1]]).unsigned_abs())
+                    .max()
+                    .unwrap_or(0);
+                if output.send(f32::from(peak) / f32::from(i16::MAX)).await.is_err() {
+                    return;
+                }
+            }
+            let _ = child.kill().await;
+        }
+    })
+}
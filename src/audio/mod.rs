@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Audio routing for the received AirPlay stream.
+//!
+//! Everything here talks to the PipeWire server through its PulseAudio
+//! compatibility layer (`pactl`), which is available on every COSMIC install,
+//! except the equalizer, which runs a filter chain in a `pipewire` of its own.
+
+mod duplicate;
+mod equalizer;
+pub mod meter;
+pub mod pin;
+pub mod record;
+pub mod snapcast;
+mod sinks;
+mod stream;
+pub mod volume;
+
+pub use duplicate::DuplicateOutput;
+pub use equalizer::EqualizerOutput;
+pub use sinks::{default_sink, find_sink_input, list_sinks, Sink};
+pub use stream::{stream_env, stream_title};
@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Keeps the receiver's playback stream on a chosen sink, moving it back
+//! whenever the sink disappears and comes back, e.g. a USB DAC being replugged.
+
+use std::time::Duration;
+
+use cosmic::iced::futures::channel::mpsc;
+use cosmic::iced::futures::Stream;
+
+use super::sinks::pactl;
+use super::{find_sink_input, list_sinks, stream_title};
+use crate::error::AirTrayError;
+
+/// How often to check the playback stream and the sink.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Moves the receiver's playback stream to the sink named `sink` each time
+/// either appears, for as long as the stream is polled.
+pub fn hold<T: Send + 'static>(receiver_name: String, sink: String) -> impl Stream<Item = T> {
+    cosmic::iced::stream::channel(1, move |_: mpsc::Sender<T>| async move {
+        let title = stream_title(&receiver_name);
+        // Playback stream last moved, and whether the sink was there
+        let mut moved = None;
+        let mut present = false;
+
+        loop {
+            let (title, sink) = (title.clone(), sink.clone());
+            let result = tokio::task::spawn_blocking(move || {
+                let available = list_sinks()?.iter().any(|known| known.name == sink);
+                let index = find_sink_input(&title)?;
+                if let Some(index) = index.filter(|_| available) {
+                    if Some(index) != moved || !present {
+                        pactl(&["move-sink-input", &index.to_string(), &sink])?;
+                    }
+                }
+                Ok::<_, AirTrayError>((index, available))
+            })
+            .await;
+
+            match result {
+                Ok(Ok((index, available))) => {
+                    moved = index;
+                    present = available;
+                }
+                Ok(Err(e)) => eprintln!("Failed to move the AirPlay stream: {}", e),
+                Err(_) => {}
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+}
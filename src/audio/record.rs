@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Records the receiver's playback stream to audio files.
+
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::Local;
+use cosmic::iced::futures::Stream;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use tokio::process::{Child, Command};
+
+use super::{find_sink_input, stream_title};
+use crate::companion::files::create_unique;
+use crate::config::RecordingFormat;
+use crate::host;
+
+/// How often to look for the stream while nothing is playing.
+const RETRY: Duration = Duration::from_secs(1);
+
+/// A running `parec`, asked to finish its file when dropped. Killing it
+/// outright would leave the file without its final header.
+struct Recorder(Child);
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0.id() {
+            let _ = kill(Pid::from_raw(pid as i32), Signal::SIGTERM);
+        }
+    }
+}
+
+/// Records the receiver's audio into `dir` for as long as the stream is
+/// polled. Files are named after `track`, or the time recording started if
+/// it is empty. If the stream goes away and comes back, recording continues
+/// in a new file.
+pub fn record(
+    receiver_name: String,
+    dir: PathBuf,
+    track: String,
+    format: RecordingFormat,
+) -> impl Stream<Item = Infallible> {
+    cosmic::iced::stream::channel(1, move |_output| async move {
+        let title = stream_title(&receiver_name);
+        let stem = match file_stem(&track) {
+            Some(stem) => stem,
+            None => Local::now().format("AirPlay %Y-%m-%d %H-%M-%S").to_string(),
+        };
+
+        if let Err(e) = tokio::fs::create_dir_all(&dir).await {
+            eprintln!("Failed to create {}: {}", dir.display(), e);
+            return;
+        }
+
+        loop {
+            let lookup = title.clone();
+            let index = tokio::task::spawn_blocking(move || find_sink_input(&lookup))
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .flatten();
+
+            let Some(index) = index else {
+                tokio::time::sleep(RETRY).await;
+                continue;
+            };
+
+            // Reserves the name, parec then writes over the empty file
+            let path = match create_unique(&dir, &format!("{stem}.{}", format.extension())).await {
+                Ok((_, path)) => path,
+                Err(e) => {
+                    eprintln!("Failed to record the AirPlay stream: {}", e);
+                    return;
+                }
+            };
+            let child = Command::from(host::command("parec"))
+                .arg(format!("--monitor-stream={index}"))
+                .arg(format!("--file-format={}", format.extension()))
+                .arg(&path)
+                .spawn();
+            let mut recorder = match child {
+                Ok(child) => Recorder(child),
+                Err(e) => {
+                    eprintln!("Failed to record the AirPlay stream: {}", e);
+                    return;
+                }
+            };
+
+            println!("Recording to {}", path.display());
+            // Ends when the stream goes away
+            let _ = recorder.0.wait().await;
+        }
+    })
+}
+
+/// Turns "Artist - Title" into something safe to use as a file name.
+fn file_stem(track: &str) -> Option<String> {
+    let stem: String = track
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':') { '-' } else { c })
+        .collect();
+    let stem = stem.trim().trim_start_matches('.');
+    (!stem.is_empty()).then(|| stem.to_string())
+}
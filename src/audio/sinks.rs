@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::error::AirTrayError;
+use crate::host;
+
+/// An audio output as reported by the sound server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sink {
+    /// Node name, used to address the sink.
+    pub name: String,
+    /// Human readable description shown in the UI.
+    pub description: String,
+}
+
+/// Runs `pactl` with the given arguments and returns its stdout.
+pub(super) fn pactl(args: &[&str]) -> Result<String, AirTrayError> {
+    // pactl translates its field labels, which would break parsing
+    let output = host::command_with_env("pactl", [("LC_ALL", "C")])
+        .args(args)
+        .output()
+        .map_err(|e| AirTrayError::spawn("pactl", e))?;
+
+    if !output.status.success() {
+        return Err(AirTrayError::AudioError(format!(
+            "pactl {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Lists the audio sinks known to the sound server.
+pub fn list_sinks() -> Result<Vec<Sink>, AirTrayError> {
+    let stdout = pactl(&["list", "sinks"])?;
+    let mut sinks = Vec::new();
+    let mut name = None;
+
+    for line in stdout.lines().map(str::trim) {
+        if let Some(value) = line.strip_prefix("Name: ") {
+            name = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("Description: ") {
+            if let Some(name) = name.take() {
+                sinks.push(Sink {
+                    name,
+                    description: value.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(sinks)
+}
+
+/// Returns the node name of the current default sink.
+pub fn default_sink() -> Result<String, AirTrayError> {
+    Ok(pactl(&["get-default-sink"])?.trim().to_string())
+}
+
+/// Index of the playback stream with the given media name, if it is playing.
+pub fn find_sink_input(media_name: &str) -> Result<Option<u32>, AirTrayError> {
+    let stdout = pactl(&["list", "sink-inputs"])?;
+    let wanted = format!("media.name = \"{media_name}\"");
+    let mut index = None;
+
+    for line in stdout.lines().map(str::trim) {
+        if let Some(value) = line.strip_prefix("Sink Input #") {
+            index = value.parse().ok();
+        } else if line == wanted {
+            return Ok(index);
+        }
+    }
+
+    Ok(None)
+}
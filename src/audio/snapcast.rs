@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+
+use crate::config::SnapcastConfig;
+
+/// Sample format expected by the default Snapcast TCP source (`48000:16:2`).
+const SNAPCAST_CAPS: &str = "audio/x-raw,rate=48000,format=S16LE,channels=2";
+
+/// How long to wait for the Snapcast server when checking its status.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Reachability of the configured Snapcast server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapcastStatus {
+    Reachable,
+    Unreachable,
+}
+
+/// Builds the GStreamer audio sink handed to UXPlay (`-as`) so the decoded
+/// stream is pushed to a Snapcast `tcp://` source in client mode instead of
+/// being played locally. Local playback then happens through snapclient,
+/// which keeps this room in sync with the others.
+pub fn audio_sink(config: &SnapcastConfig) -> String {
+    format!(
+        "audioconvert ! audioresample ! {SNAPCAST_CAPS} ! tcpclientsink host={} port={}",
+        config.host, config.port
+    )
+}
+
+/// Checks whether the Snapcast TCP source accepts connections.
+pub async fn probe(config: SnapcastConfig) -> SnapcastStatus {
+    let address = (config.host.as_str(), config.port);
+    let connect = tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(address));
+
+    if matches!(connect.await, Ok(Ok(_))) {
+        SnapcastStatus::Reachable
+    } else {
+        SnapcastStatus::Unreachable
+    }
+}
@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+/// Environment that names the receiver's playback stream in the sound
+/// settings, so it shows up as "AirPlay: <name>" and can be routed per app.
+///
+/// `PULSE_PROP` is picked up by `pulsesink` and `PIPEWIRE_PROPS` by
+/// `pipewiresink`, whichever GStreamer ends up autoplugging.
+pub fn stream_env(receiver_name: &str) -> [(&'static str, String); 2] {
+    let title = stream_title(receiver_name);
+    let icon = "com.github.introini.airtray";
+
+    [
+        (
+            "PULSE_PROP",
+            format!(
+                "application.name='{title}' media.name='{title}' application.icon_name='{icon}'"
+            ),
+        ),
+        (
+            "PIPEWIRE_PROPS",
+            format!(
+                "{{ application.name = \"{title}\" media.name = \"{title}\" node.description = \"{title}\" application.icon-name = \"{icon}\" }}"
+            ),
+        ),
+    ]
+}
+
+/// Media name of the receiver's playback stream.
+pub fn stream_title(receiver_name: &str) -> String {
+    // Neither property syntax supports escaping, so keep quotes out of the name
+    format!("AirPlay: {}", receiver_name.replace(['"', '\''], ""))
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Volume of the receiver's playback stream only, leaving the sink alone.
+
+use std::time::Duration;
+
+use cosmic::iced::futures::channel::mpsc;
+use cosmic::iced::futures::Stream;
+
+use super::sinks::pactl;
+use super::{find_sink_input, stream_title};
+use crate::error::AirTrayError;
+
+/// How often to look for a new playback stream to apply the volume to.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Sets the volume of the playback stream with the given index.
+fn apply(index: u32, percent: u32, muted: bool) -> Result<(), AirTrayError> {
+    let index = index.to_string();
+    pactl(&["set-sink-input-volume", &index, &format!("{percent}%")])?;
+    pactl(&["set-sink-input-mute", &index, if muted { "1" } else { "0" }])?;
+    Ok(())
+}
+
+/// Keeps the receiver's playback stream at the given volume for as long as
+/// the stream is polled. UXPlay opens a new stream for every session and
+/// sometimes between tracks, so each new one is set as it appears.
+pub fn hold<T: Send + 'static>(
+    receiver_name: String,
+    percent: u32,
+    muted: bool,
+) -> impl Stream<Item = T> {
+    cosmic::iced::stream::channel(1, move |_: mpsc::Sender<T>| async move {
+        let title = stream_title(&receiver_name);
+        let mut applied = None;
+
+        loop {
+            let title = title.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let index = find_sink_input(&title)?;
+                if let Some(index) = index.filter(|index| Some(*index) != applied) {
+                    apply(index, percent, muted)?;
+                }
+                Ok::<_, AirTrayError>(index)
+            })
+            .await;
+
+            match result {
+                Ok(Ok(index)) => applied = index,
+                Ok(Err(e)) => eprintln!("Failed to set the AirPlay stream volume: {}", e),
+                Err(_) => {}
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Finds out which options the installed UXPlay understands. Distributions
+//! build it with different features, and older releases lack newer options,
+//! so anything beyond the basics is only passed when `uxplay -h` lists it.
+
+use std::collections::BTreeSet;
+use std::process::Stdio;
+
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+use crate::config::LaunchConfig;
+
+/// The options of the installed UXPlay.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Options listed by `uxplay -h`, e.g. "-pin", or `None` if it couldn't
+    /// be asked, in which case everything is assumed to work.
+    options: Option<BTreeSet<String>>,
+}
+
+impl Capabilities {
+    /// Reads the options from UXPlay's help text, where each is at the start
+    /// of a line, e.g. "-pin[xxxx] Use a 4-digit pin code…".
+    pub fn parse(help: &str) -> Self {
+        let options = help
+            .lines()
+            .filter_map(|line| line.trim_start().strip_prefix('-'))
+            .map(|rest| {
+                let name: String = rest
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                    .collect();
+                format!("-{name}")
+            })
+            .filter(|option| option.len() > 1)
+            .collect();
+        Self {
+            options: Some(options),
+        }
+    }
+
+    /// Whether UXPlay accepts `option`, e.g. "-h265".
+    pub fn supports(&self, option: &str) -> bool {
+        self.options
+            .as_ref()
+            .map_or(true, |options| options.contains(option))
+    }
+
+    /// Whether senders can be asked for a PIN.
+    pub fn pin(&self) -> bool {
+        self.supports("-pin")
+    }
+
+    /// Whether H.265 can be offered to senders.
+    pub fn h265(&self) -> bool {
+        self.supports("-h265")
+    }
+
+    /// Whether a new sender can take over from the current one.
+    pub fn nohold(&self) -> bool {
+        self.supports("-nohold")
+    }
+
+    /// Whether particular devices can be refused.
+    pub fn block(&self) -> bool {
+        self.supports("-block")
+    }
+}
+
+/// The last probe, with how UXPlay was started for it.
+static CAPABILITIES: Mutex<Option<(LaunchConfig, Capabilities)>> = Mutex::const_new(None);
+
+/// The options of the UXPlay started as `launch` says. Asked for once per
+/// run, and again when that changes.
+pub async fn probe(launch: LaunchConfig) -> Capabilities {
+    let mut cached = CAPABILITIES.lock().await;
+    if let Some((probed, capabilities)) = cached.as_ref() {
+        if *probed == launch {
+            return capabilities.clone();
+        }
+    }
+
+    // Prints its help to stdout and exits with an error code
+    let output = Command::from(super::launch(&launch, std::iter::empty::<(&str, &str)>()))
+        .arg("-h")
+        .stdin(Stdio::null())
+        .output()
+        .await;
+    let capabilities = match output {
+        Ok(output) => {
+            let mut help = String::from_utf8_lossy(&output.stdout).into_owned();
+            help.push_str(&String::from_utf8_lossy(&output.stderr));
+            Capabilities::parse(&help)
+        }
+        Err(e) => {
+            println!("Failed to ask UXPlay for its options: {}", e);
+            Capabilities::default()
+        }
+    };
+
+    *cached = Some((launch, capabilities.clone()));
+    capabilities
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use cosmic::iced::futures::stream;
+
+use super::{Backend, BackendEvent, BoxFuture, BoxStream};
+use crate::config::Config;
+use crate::error::AirTrayError;
+
+/// What the mock has been asked to do, shared with the test driving it.
+#[derive(Debug, Default)]
+pub struct MockState {
+    /// Number of successful spawns.
+    pub spawns: usize,
+    /// Number of stop requests for a running receiver.
+    pub kills: usize,
+    /// Whether the fake receiver is currently alive.
+    pub running: bool,
+    /// Make the next spawn fail, as if the binary were missing.
+    pub fail_next_spawn: bool,
+    /// Settings passed to the last spawn.
+    pub last_config: Option<Config>,
+}
+
+/// Backend that only records calls, used to test the lifecycle handling.
+///
+/// Its event stream never yields; tests script process events by feeding
+/// `ReceiverEvent`s to the applet.
+#[derive(Debug, Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inspect or script the mock.
+    pub fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Simulates the receiver process dying on its own.
+    pub fn crash(&self) {
+        self.state().running = false;
+    }
+}
+
+impl Backend for MockBackend {
+    fn spawn(&mut self, config: &Config) -> BoxFuture<Result<(), AirTrayError>> {
+        let mut state = self.state();
+        if std::mem::take(&mut state.fail_next_spawn) {
+            let error = AirTrayError::BinaryNotFound {
+                binary: String::from("uxplay"),
+            };
+            return Box::pin(async { Err(error) });
+        }
+
+        assert!(!state.running, "spawned while a receiver was already running");
+        state.spawns += 1;
+        state.running = true;
+        state.last_config = Some(config.clone());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn events(&mut self) -> BoxStream<BackendEvent> {
+        Box::pin(stream::pending())
+    }
+
+    fn stop(&mut self) {
+        let mut state = self.state();
+        if state.running {
+            state.kills += 1;
+            state.running = false;
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Receiver backends the applet can drive.
+
+pub mod capabilities;
+#[cfg(test)]
+mod mock;
+mod output;
+mod uxplay;
+
+use std::future::Future;
+use std::pin::Pin;
+
+use cosmic::iced::futures::Stream;
+
+#[cfg(test)]
+pub use mock::MockBackend;
+pub use uxplay::{launch, resolve, UxplayBackend, BINARY, LOW_LATENCY_AUDIO};
+
+use crate::config::Config;
+use crate::error::AirTrayError;
+
+/// A future the applet runs as a Task on its executor.
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// A stream the applet runs as a Task on its executor.
+pub type BoxStream<T> = Pin<Box<dyn Stream<Item = T> + Send>>;
+
+/// Something the running receiver reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendEvent {
+    /// A sender connected.
+    ClientConnected {
+        /// Stable device identifier the sender announced.
+        id: String,
+        /// Name the sender announced, e.g. "John's iPhone".
+        name: String,
+        /// Hardware model, e.g. "iPhone14,2".
+        model: String,
+    },
+    /// The last sender disconnected.
+    ClientsDisconnected,
+    /// The receiver is listening and has been announced on the network.
+    Ready,
+    /// The video pipeline dropped frames it couldn't show in time.
+    FramesDropped,
+    /// Missing packets were requested from the sender again.
+    PacketsResent,
+    /// The sender chose the format it streams audio in.
+    AudioFormat(AudioCodec),
+    /// A new sender has to enter this PIN, picked at random for it.
+    Pin(String),
+    /// The receiver exited with the given code and the last lines it wrote
+    /// to stderr. Always the last event.
+    Exited(Option<i32>, Vec<String>),
+}
+
+/// Format of the audio a sender streams. UXPlay decodes it to PCM before it
+/// reaches the sink; GStreamer can't pass ALAC or AAC through to PipeWire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    Pcm,
+    Alac,
+    AacLc,
+    /// Low-delay AAC, used while mirroring.
+    AacEld,
+}
+
+impl AudioCodec {
+    /// The codec's usual name, e.g. "AAC-ELD".
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pcm => "PCM",
+            Self::Alac => "ALAC",
+            Self::AacLc => "AAC",
+            Self::AacEld => "AAC-ELD",
+        }
+    }
+}
+
+/// A receiver implementation that can be started and stopped by the applet.
+///
+/// Nothing here may block: anything that waits on the receiver is returned as
+/// a future, so it runs on the executor instead of the UI thread.
+pub trait Backend: Send {
+    /// Starts the receiver with the given settings. The future resolves once
+    /// the receiver has been launched, or failed to.
+    fn spawn(&mut self, config: &Config) -> BoxFuture<Result<(), AirTrayError>>;
+
+    /// Events of the last spawned receiver. The stream ends with
+    /// [`BackendEvent::Exited`] once the receiver has exited, whether on its
+    /// own or after [`Backend::stop`].
+    fn events(&mut self) -> BoxStream<BackendEvent>;
+
+    /// Asks the receiver to shut down. Does nothing if it is not running.
+    fn stop(&mut self);
+}
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Recognizes the UXPlay log lines the applet cares about.
+
+use super::{AudioCodec, BackendEvent};
+
+/// Parses one line of UXPlay output.
+pub fn parse_line(line: &str) -> Option<BackendEvent> {
+    let line = line.trim();
+
+    // "connection request from John's iPhone (iPhone14,2) with deviceID = 12:34:56:78:9A:BC"
+    if let Some(rest) = line.split_once("connection request from ").map(|(_, rest)| rest) {
+        let (device, id) = rest.rsplit_once(" with deviceID = ")?;
+        let (name, model) = match device.rsplit_once(" (") {
+            Some((name, model)) => (name, model.trim_end_matches(')')),
+            None => (device, ""),
+        };
+
+        return Some(BackendEvent::ClientConnected {
+            id: id.trim().to_string(),
+            name: name.trim().to_string(),
+            model: model.to_string(),
+        });
+    }
+
+    // Logged once the listening sockets are up, after the services were registered
+    if line.starts_with("Initialized server socket") {
+        return Some(BackendEvent::Ready);
+    }
+
+    // GStreamer's QoS warning, e.g. "A lot of buffers are being dropped."
+    if line.contains("buffers are being dropped") {
+        return Some(BackendEvent::FramesDropped);
+    }
+
+    // "raop_rtp resend request ..." for lost audio packets
+    if line.contains("resend request") {
+        return Some(BackendEvent::PacketsResent);
+    }
+
+    // "ct=2 spf=352 usingScreen=0 isMedia=1  audioFormat=0x40000", ct being the compression type
+    if let Some(rest) = line.strip_prefix("ct=") {
+        let codec = match rest.split_whitespace().next()?.parse::<u8>().ok()? {
+            1 => AudioCodec::Pcm,
+            2 => AudioCodec::Alac,
+            4 => AudioCodec::AacLc,
+            8 => AudioCodec::AacEld,
+            _ => return None,
+        };
+        return Some(BackendEvent::AudioFormat(codec));
+    }
+
+    // "*** CLIENT MUST NOW ENTER PIN = "1234" AS AIRPLAY PASSWORD"
+    if let Some((_, rest)) = line.split_once("ENTER PIN = ") {
+        let pin = rest.trim_start_matches('"').split('"').next()?;
+        return Some(BackendEvent::Pin(pin.to_string()));
+    }
+
+    // "Open connections: 0"
+    if let Some(count) = line.strip_prefix("Open connections: ") {
+        if count.trim().parse::<usize>().ok()? == 0 {
+            return Some(BackendEvent::ClientsDisconnected);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,465 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::VecDeque;
+use std::io;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use chrono::Local;
+use cosmic::iced::futures::stream;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, oneshot};
+
+use super::capabilities::{self, Capabilities};
+use super::output::parse_line;
+use super::{Backend, BackendEvent, BoxFuture, BoxStream};
+use crate::audio::snapcast;
+use crate::audio::{self, DuplicateOutput, EqualizerOutput};
+use crate::config::{validation, Codec, ConcurrentPolicy, Config, LaunchConfig};
+use crate::dacp;
+use crate::dbus::networkmanager;
+use crate::error::AirTrayError;
+use crate::gpu;
+use crate::host;
+use crate::identity;
+use crate::log;
+use crate::metadata;
+use crate::ports;
+use crate::video;
+use crate::viewer;
+
+/// Name of the UXPlay executable.
+pub const BINARY: &str = "uxplay";
+
+/// Audio latency in seconds reported to senders in low-latency mode, down
+/// from UXPlay's 0.25.
+pub const LOW_LATENCY_AUDIO: &str = "0.05";
+
+/// UXPlay's audio latency in seconds reported to senders.
+const DEFAULT_AUDIO_LATENCY: f32 = 0.25;
+
+/// Largest sink delay in ms UXPlay can make up for, as it only delays the
+/// video by less than a second.
+const MAX_SINK_DELAY: u32 = 999;
+
+/// Lines of stderr kept to explain why UXPlay exited.
+const STDERR_TAIL: usize = 5;
+
+/// How long to wait for a network connection before starting UXPlay anyway,
+/// e.g. right after login.
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long UXPlay gets to shut down cleanly before it is killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runs the `uxplay` binary as a child process.
+///
+/// Each spawned process is owned by a supervisor task on the executor, which
+/// reports its exit and handles termination when asked to stop.
+#[derive(Debug, Default)]
+pub struct UxplayBackend {
+    /// Tells the supervisor of the current process to terminate it.
+    stop: Option<oneshot::Sender<()>>,
+    /// Receives the events of the current process.
+    events: Option<mpsc::UnboundedReceiver<BackendEvent>>,
+}
+
+/// Sinks set up for the receiver, removed again once it exits.
+#[derive(Debug, Default)]
+struct AudioOutputs {
+    duplicate: Option<DuplicateOutput>,
+    equalizer: Option<EqualizerOutput>,
+    /// Delay of the slowest sink the audio ends up on, in ms.
+    delay: u32,
+}
+
+impl AudioOutputs {
+    /// The sink the receiver plays into instead of the default one.
+    fn sink_name(&self) -> Option<&'static str> {
+        let equalizer = self.equalizer.as_ref().map(EqualizerOutput::sink_name);
+        equalizer.or(self.duplicate.as_ref().map(DuplicateOutput::sink_name))
+    }
+}
+
+/// Sets up the combined sink when duplication is enabled, and the equalizer
+/// in front of it. Snapcast bypasses both.
+fn prepare_audio(config: &Config) -> AudioOutputs {
+    if config.audio.snapcast.enabled {
+        return AudioOutputs::default();
+    }
+
+    let duplicate = prepare_duplicate(config);
+    let target = duplicate
+        .as_ref()
+        .map(DuplicateOutput::sink_name)
+        .or(config.audio.sink.as_deref());
+    let equalizer = EqualizerOutput::new(config.equalizer(), target).unwrap_or_else(|e| {
+        println!("Failed to set up the equalizer: {}", e);
+        None
+    });
+    let primary = config.audio.sink.clone().or_else(|| audio::default_sink().ok());
+    let secondary = duplicate.as_ref().and(config.audio.secondary_sink.as_deref());
+    let delay = primary
+        .as_deref()
+        .into_iter()
+        .chain(secondary)
+        .map(|sink| config.audio.sink_delay(sink))
+        .max()
+        .unwrap_or(0);
+
+    AudioOutputs {
+        duplicate,
+        equalizer,
+        delay,
+    }
+}
+
+fn prepare_duplicate(config: &Config) -> Option<DuplicateOutput> {
+    let audio = &config.audio;
+    if !audio.duplicate {
+        return None;
+    }
+
+    let secondary = audio.secondary_sink.as_deref()?;
+    let primary = match &audio.sink {
+        Some(sink) => Ok(sink.clone()),
+        None => audio::default_sink(),
+    };
+    let result = primary.and_then(|primary| {
+        if primary == secondary {
+            return Err(AirTrayError::AudioError(String::from(
+                "secondary sink is the default sink",
+            )));
+        }
+        DuplicateOutput::new(&primary, secondary)
+    });
+
+    match result {
+        Ok(duplicate) => Some(duplicate),
+        Err(e) => {
+            println!("Failed to set up audio duplication: {}", e);
+            None
+        }
+    }
+}
+
+/// Builds the UXPlay invocation for the given settings, offering H.265 if
+/// `h265` is set. Options the installed UXPlay lacks are left out.
+fn command(
+    config: &Config,
+    outputs: &AudioOutputs,
+    h265: bool,
+    capabilities: &Capabilities,
+) -> Command {
+    let mut env = audio::stream_env(&config.receiver_name()).to_vec();
+    env.extend(gpu::env(&config.video.gpu));
+    let sink = outputs.sink_name().or(config.audio.sink.as_deref());
+    if let Some(sink) = sink.filter(|_| !config.audio.snapcast.enabled) {
+        env.push(("PULSE_SINK", sink.to_string()));
+    }
+
+    let mut command = Command::from(launch(&config.launch, env));
+    command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // If the task owning the child is cancelled, don't leave UXPlay behind
+        .kill_on_drop(true);
+
+    if !config.name.is_empty() {
+        command.args(["-n", &config.name, "-nh"]);
+    }
+    if config.debug {
+        command.arg("-d");
+    }
+    // Lets the applet send play/pause and volume commands to the sender
+    if capabilities.supports("-dacp") {
+        command.arg("-dacp").arg(dacp::export_path());
+    }
+    if capabilities.supports("-md") {
+        command.arg("-md").arg(metadata::metadata_path());
+    }
+    if capabilities.supports("-ca") {
+        command.arg("-ca").arg(metadata::artwork_path());
+    }
+    // Keeps the key senders know the receiver by across starts, and backups
+    if capabilities.supports("-key") && std::fs::create_dir_all(identity::dir()).is_ok() {
+        command.arg("-key").arg(identity::key_path());
+    }
+
+    if config.ports.udp != 0
+        && validation::udp_port(config.ports.udp)
+        && capabilities.supports("-p")
+    {
+        command.args(["-p", "udp", &config.ports.udp.to_string()]);
+    }
+
+    // Expert overrides of the selected profile win over anything AirTray picks
+    let profile = config.active_profile();
+    let video_pipeline = profile.map_or("", |profile| profile.video_pipeline.trim());
+    let audio_pipeline = profile.map_or("", |profile| profile.audio_pipeline.trim());
+
+    if !audio_pipeline.is_empty() {
+        command.args(["-as", audio_pipeline]);
+    } else if config.audio.snapcast.enabled {
+        command.args(["-as", &snapcast::audio_sink(&config.audio.snapcast)]);
+    }
+
+    if config.concurrent_clients == ConcurrentPolicy::Allow && capabilities.nohold() {
+        // Let a new sender drop the current one instead of being turned away
+        command.arg("-nohold");
+    }
+
+    if capabilities.block() {
+        for id in config.blocked_devices(Local::now()) {
+            command.args(["-block", &id]);
+        }
+    }
+
+    if config.random_pin && capabilities.pin() {
+        // Without a register, every connection needs a fresh PIN
+        command.arg("-pin");
+    } else if let Some(profile) = config.active_profile().filter(|_| capabilities.pin()) {
+        if !profile.pin.is_empty() {
+            command.args(["-pin", &profile.pin]);
+            // Devices that entered the PIN once aren't asked again
+            if capabilities.supports("-reg") {
+                command.arg("-reg").arg(identity::register_path());
+            }
+        }
+    }
+
+    let mut quality = config.stream_quality();
+    if config.video.extended_display {
+        // The Mac sizes the virtual display after what is requested
+        if let Some(resolution) = video::native_resolution(&config.video.output) {
+            quality.resolution = resolution;
+        }
+        command.arg("-fs");
+    } else if video::uxplay_fullscreen(&config.video) {
+        command.arg("-fs");
+    }
+    let quality = video::within_bitrate(&quality, config.video.max_bitrate);
+    // Left to the sender rather than failing to start
+    if !quality.resolution.is_empty() && validation::resolution(&quality.resolution) {
+        command.args(["-s", &quality.resolution]);
+    }
+    if quality.fps > 0 {
+        command.args(["-fps", &quality.fps.to_string()]);
+    }
+    if h265 {
+        command.arg("-h265");
+    }
+
+    if config.low_latency {
+        // Show frames as they arrive instead of buffering them to the timestamps
+        command.args(["-vsync", "no", "-al", LOW_LATENCY_AUDIO]);
+    } else if outputs.delay > 0 {
+        // Hold the video back as long as the sink is late, and tell senders of
+        // audio-only streams, so lyrics and the like stay in sync
+        let delay = outputs.delay.min(MAX_SINK_DELAY);
+        let latency = DEFAULT_AUDIO_LATENCY + delay as f32 / 1000.0;
+        command
+            .args(["-vsync", &format!("-{delay}")])
+            .args(["-al", &format!("{latency:.3}")]);
+    }
+
+    if !video_pipeline.is_empty() {
+        command.args(["-vs", video_pipeline]);
+    } else if config.viewer.enabled {
+        command.args(["-vs", &viewer::video_sink(&config.viewer)]);
+    } else {
+        command.args(["-vs", &video::video_sink(&config.video)]);
+    }
+
+    command
+}
+
+/// Where UXPlay is started from: the first search path that has it, PATH,
+/// or the container.
+pub fn resolve(launch: &LaunchConfig) -> String {
+    if launch.container.is_empty() {
+        host::resolve(BINARY, &launch.search_paths)
+    } else {
+        format!("{} {}", launch.container, BINARY)
+    }
+}
+
+/// A command starting UXPlay as `launch` says, with `env` set for it.
+pub fn launch<V: AsRef<str>>(
+    launch: &LaunchConfig,
+    env: impl IntoIterator<Item = (&'static str, V)>,
+) -> std::process::Command {
+    if launch.container.is_empty() {
+        host::command_with_env(&host::resolve(BINARY, &launch.search_paths), env)
+    } else {
+        host::in_container(&launch.container, BINARY, env)
+    }
+}
+
+/// Owns a running UXPlay process until it exits, forwarding what it reports
+/// and terminating it on request. `verbose` also echoes its output.
+async fn supervise(
+    mut child: Child,
+    outputs: AudioOutputs,
+    verbose: bool,
+    mut stop: oneshot::Receiver<()>,
+    events: mpsc::UnboundedSender<BackendEvent>,
+) {
+    let mut stdout = child.stdout.take().map(|out| BufReader::new(out).lines());
+    let mut stderr = child.stderr.take().map(|err| BufReader::new(err).lines());
+    // UXPlay itself when started in a container, rather than what entered it
+    let mut inner = None;
+    let mut tail = VecDeque::with_capacity(STDERR_TAIL);
+
+    let status = loop {
+        tokio::select! {
+            status = child.wait() => break status,
+            // Also taken when the sender is dropped, i.e. the backend went away
+            _ = &mut stop => break terminate(&mut child, inner).await,
+            Some(Ok(Some(line))) = next_line(&mut stdout) => match host::container_pid(&line) {
+                // A sandbox can't signal host processes, flatpak-spawn has to pass it on
+                Some(pid) if !host::in_flatpak() => inner = Some(pid),
+                Some(_) => {}
+                None => forward(&events, &line, verbose),
+            },
+            Some(Ok(Some(line))) = next_line(&mut stderr) => {
+                forward(&events, &line, verbose);
+                if tail.len() == STDERR_TAIL {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+        }
+    };
+
+    let code = match status {
+        Ok(status) => {
+            println!("UXPlay process exited: {}", status);
+            status.code()
+        }
+        Err(e) => {
+            println!("Failed to wait for UXPlay process: {}", e);
+            None
+        }
+    };
+
+    // Tear down the combined sink and equalizer once nothing plays into them anymore
+    drop(outputs);
+    let _ = events.send(BackendEvent::Exited(code, tail.into()));
+}
+
+/// Reads the next line from an output pipe, if it was captured.
+async fn next_line<R>(
+    lines: &mut Option<tokio::io::Lines<BufReader<R>>>,
+) -> Option<io::Result<Option<String>>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    match lines {
+        Some(lines) => Some(lines.next_line().await),
+        None => None,
+    }
+}
+
+/// Keeps a line of output for the log page and passes it on to the applet if
+/// recognized.
+fn forward(events: &mpsc::UnboundedSender<BackendEvent>, line: &str, verbose: bool) {
+    log::record(line);
+    if verbose {
+        println!("uxplay: {}", line);
+    }
+    if let Some(event) = parse_line(line) {
+        let _ = events.send(event);
+    }
+}
+
+/// Sends SIGTERM and falls back to SIGKILL if UXPlay doesn't exit in time.
+/// `inner` is UXPlay's pid when `child` only entered the container it runs
+/// in; `child` exits along with it.
+async fn terminate(child: &mut Child, inner: Option<i32>) -> io::Result<ExitStatus> {
+    println!("Stopping UXPlay process");
+
+    if let Some(pid) = inner.or_else(|| child.id().map(|pid| pid as i32)) {
+        if let Err(e) = kill(Pid::from_raw(pid), Signal::SIGTERM) {
+            println!("Failed to signal UXPlay process: {}", e);
+        }
+    }
+
+    match tokio::time::timeout(STOP_TIMEOUT, child.wait()).await {
+        Ok(status) => status,
+        Err(_) => {
+            println!("UXPlay did not exit within {:?}, killing it", STOP_TIMEOUT);
+            if let Some(pid) = inner {
+                let _ = kill(Pid::from_raw(pid), Signal::SIGKILL);
+            }
+            child.kill().await?;
+            child.wait().await
+        }
+    }
+}
+
+impl Backend for UxplayBackend {
+    fn spawn(&mut self, config: &Config) -> BoxFuture<Result<(), AirTrayError>> {
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        self.stop = Some(stop_tx);
+        self.events = Some(event_rx);
+
+        let config = config.clone();
+        Box::pin(async move {
+            // UXPlay can't announce itself or bind its ports without a network
+            if !networkmanager::wait_online(NETWORK_TIMEOUT).await {
+                println!("No network after {:?}, starting UXPlay anyway", NETWORK_TIMEOUT);
+            }
+
+            println!("Starting UXPlay process");
+
+            // pactl blocks, keep it off the async worker threads
+            let audio_config = config.clone();
+            let outputs = tokio::task::spawn_blocking(move || prepare_audio(&audio_config))
+                .await
+                .unwrap_or_default();
+
+            let capabilities = capabilities::probe(config.launch.clone()).await;
+            let h265 = config.stream_quality().codec == Codec::H265 && capabilities.h265();
+            let h265 = h265 && {
+                let available = video::h265_available().await;
+                if !available {
+                    println!("No H.265 decoder found in GStreamer, falling back to H.264");
+                }
+                available
+            };
+
+            // Walks /proc to name whoever holds a port, keep it off the async
+            // worker threads as well
+            let ports = config.ports.clone();
+            tokio::task::spawn_blocking(move || ports::check(&ports))
+                .await
+                .unwrap_or(Ok(()))?;
+
+            let child = command(&config, &outputs, h265, &capabilities)
+                .spawn()
+                .map_err(|e| AirTrayError::spawn("uxplay", e))?;
+            tokio::spawn(supervise(child, outputs, config.debug, stop_rx, event_tx));
+            Ok(())
+        })
+    }
+
+    fn events(&mut self) -> BoxStream<BackendEvent> {
+        match self.events.take() {
+            Some(events) => Box::pin(stream::unfold(events, |mut events| async move {
+                events.recv().await.map(|event| (event, events))
+            })),
+            None => Box::pin(stream::empty()),
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+    }
+}
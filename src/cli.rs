@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Command line actions that talk to the running applet instead of starting one.
+
+use cosmic::iced::futures::{stream, StreamExt};
+use serde_json::{json, Value};
+use zbus::fdo::{DBusProxy, PropertiesProxy};
+use zbus::proxy::CacheProperties;
+
+use crate::dbus::service::{ControlProxy, Request, BUS_NAME, OBJECT_PATH};
+use crate::error::AirTrayError;
+use crate::identity;
+
+/// Runs the action requested on the command line, if any, returning the
+/// process exit code. Returns `None` when the applet itself should start.
+pub fn run(mut args: impl Iterator<Item = String>) -> Option<i32> {
+    let request = match args.next()?.as_str() {
+        "--start" => Request::Start,
+        "--stop" => Request::Stop,
+        "--toggle" => Request::Toggle,
+        "--show" => Request::ShowPopup,
+        "watch" => return Some(watch(args.any(|arg| arg == "--waybar"))),
+        "config" => return Some(config(args.collect())),
+        "keys" => return Some(keys(args.collect())),
+        // Anything else, like --window or --tray, is left for the applet to start with
+        _ => return None,
+    };
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .ok()?;
+
+    let result = runtime.block_on(async {
+        let connection = zbus::Connection::session().await?;
+        let control = ControlProxy::new(&connection).await?;
+        match request {
+            Request::Start => control.start().await?,
+            Request::Stop => control.stop().await?,
+            Request::Toggle => control.toggle().await?,
+            Request::ShowPopup => control.show_popup().await?,
+        }
+        Ok::<_, AirTrayError>(())
+    });
+
+    match result {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("Could not reach AirTray: {}", e);
+            Some(1)
+        }
+    }
+}
+
+/// `airtray config get [KEY]` prints a setting as JSON, all of them without
+/// a key; `airtray config set KEY VALUE` changes one, e.g. `video.fps 60`.
+/// The running applet picks changes up without a restart. Secrets read as
+/// redacted, and while the settings are locked, changes ask for the password
+/// that unlocks them.
+fn config(args: Vec<String>) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    if !matches!(args.as_slice(), ["get"] | ["get", _] | ["set", _, _]) {
+        eprintln!("Usage: airtray config get [KEY] | airtray config set KEY VALUE");
+        return 2;
+    }
+    let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    else {
+        return 1;
+    };
+
+    let result = runtime.block_on(async {
+        let connection = zbus::Connection::session().await?;
+        let control = ControlProxy::new(&connection).await?;
+        match args.as_slice() {
+            ["set", key, value] => control.set_setting(key, value).await?,
+            ["get", key @ ..] => {
+                let value = control.get_setting(key.first().copied().unwrap_or_default()).await?;
+                // Sections read better spread over lines
+                match serde_json::from_str::<Value>(&value) {
+                    Ok(section @ (Value::Object(_) | Value::Array(_))) => println!("{:#}", section),
+                    _ => println!("{}", value),
+                }
+            }
+            _ => {}
+        }
+        Ok::<_, AirTrayError>(())
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("AirTray settings: {}", e);
+            1
+        }
+    }
+}
+
+/// `airtray keys backup FILE` saves the receiver's keys, `airtray keys
+/// restore FILE` brings them back, e.g. after reinstalling.
+fn keys(args: Vec<String>) -> i32 {
+    let result = match args.as_slice() {
+        [command, file] if command == "backup" => identity::backup(file.as_ref()),
+        [command, file] if command == "restore" => identity::restore(file.as_ref()).map(|()| {
+            println!("Restored. Turn AirPlay off and on again for UXPlay to use them.");
+        }),
+        _ => {
+            eprintln!("Usage: airtray keys backup FILE | airtray keys restore FILE");
+            return 2;
+        }
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Prints the applet's status line whenever it changes or, with `--waybar`,
+/// the JSON a custom Waybar module reads. Runs until it is killed, for bar
+/// modules to read from.
+fn watch(waybar: bool) -> i32 {
+    let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    else {
+        return 1;
+    };
+
+    let result = runtime.block_on(async {
+        let connection = zbus::Connection::session().await?;
+        // Read afresh on every change rather than racing the proxy's cache
+        let control = ControlProxy::builder(&connection)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        let properties = PropertiesProxy::builder(&connection)
+            .destination(BUS_NAME)?
+            .path(OBJECT_PATH)?
+            .build()
+            .await?;
+        let owners = DBusProxy::new(&connection)
+            .await?
+            .receive_name_owner_changed_with_args(&[(0, BUS_NAME)])
+            .await?;
+        let mut changes = stream::select(
+            properties.receive_properties_changed().await?.map(|_| ()),
+            owners.map(|_| ()),
+        );
+
+        loop {
+            print_status(&control, waybar).await;
+            if changes.next().await.is_none() {
+                return Ok::<_, AirTrayError>(());
+            }
+        }
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Could not watch AirTray: {}", e);
+            1
+        }
+    }
+}
+
+/// Prints the applet's status once, "offline" while it is not running.
+async fn print_status(control: &ControlProxy<'_>, waybar: bool) {
+    let status = async {
+        let clients = control.clients().await?;
+        Ok::<_, zbus::Error>((control.state().await?, clients, control.status_text().await?))
+    };
+    let (state, clients, text) = status.await.unwrap_or_else(|_| {
+        let text = String::from("AirTray is not running");
+        (String::from("offline"), Vec::new(), text)
+    });
+
+    if waybar {
+        let line = json!({
+            "text": clients.join(", "),
+            "alt": state,
+            "class": state,
+            "tooltip": text,
+        });
+        println!("{}", line);
+    } else {
+        println!("{}", text);
+    }
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Receiving files from the phone. The phone opens the upload page (via the
+//! QR code in the popup), which posts every picked file as a raw body to
+//! `/files?token=<token>&name=<file name>`.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+/// Largest file accepted, so a phone can't fill the disk.
+pub const MAX_FILE: usize = 2 * 1024 * 1024 * 1024;
+
+/// The upload form served to the phone's browser.
+pub fn upload_page(token: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><title>AirTray</title></head>
+<body style="font-family: sans-serif; padding: 2em">
+<input id="files" type="file" multiple>
+<p id="status"></p>
+<script>
+document.getElementById("files").onchange = async (event) => {{
+  const status = document.getElementById("status");
+  for (const file of event.target.files) {{
+    status.textContent = "Sending " + file.name + "…";
+    const response = await fetch("/files?token={token}&name=" + encodeURIComponent(file.name), {{ method: "POST", body: file }});
+    status.textContent = response.ok ? "Sent " + file.name : "Failed to send " + file.name;
+  }}
+}};
+</script>
+</body>
+</html>
+"#
+    )
+}
+
+/// Writes `length` bytes from `body` into a new file in `dir` and returns its
+/// path. Nothing beyond [`MAX_FILE`] is read.
+pub async fn receive<R>(body: &mut R, length: usize, dir: &Path, name: &str) -> io::Result<PathBuf>
+where
+    R: AsyncRead + Unpin,
+{
+    if length > MAX_FILE {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "upload is too large"));
+    }
+    tokio::fs::create_dir_all(dir).await?;
+    let (mut file, path) = create_unique(dir, &sanitize(name)).await?;
+
+    let copied = async {
+        let copied = tokio::io::copy(&mut body.take(length as u64), &mut file).await?;
+        file.flush().await?;
+        Ok::<_, io::Error>(copied)
+    }
+    .await;
+
+    // Leave nothing half-written behind
+    let error = match copied {
+        Ok(copied) if copied == length as u64 => return Ok(path),
+        Ok(_) => io::Error::new(io::ErrorKind::UnexpectedEof, "upload was cut short"),
+        Err(e) => e,
+    };
+    drop(file);
+    let _ = tokio::fs::remove_file(&path).await;
+    Err(error)
+}
+
+/// Decodes the name sent by the browser and strips anything path-like.
+fn sanitize(name: &str) -> String {
+    let name = percent_decode(name);
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+    if name.is_empty() || name.starts_with('.') {
+        String::from("received-file")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Creates a new file in `dir`, appending " (2)", " (3)", … to the name
+/// until one is free. Only ever creates the file, never opens an existing
+/// one, so two writers can't end up sharing it.
+pub async fn create_unique(dir: &Path, name: &str) -> io::Result<(File, PathBuf)> {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+        _ => (name, String::new()),
+    };
+    let names = std::iter::once(name.to_string())
+        .chain((2..=u16::MAX).map(|n| format!("{stem} ({n}){extension}")));
+
+    for name in names {
+        let path = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path).await {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, "no free file name"))
+}
+
+fn percent_decode(input: &str) -> String {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut rest = input.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'%' if rest.len() >= 2 => {
+                let decoded = std::str::from_utf8(&rest[..2])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match decoded {
+                    Some(decoded) => {
+                        bytes.push(decoded);
+                        rest = &rest[2..];
+                    }
+                    None => bytes.push(byte),
+                }
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A small HTTP endpoint on the LAN that a connected phone can reach during a
+//! session, for the features AirPlay itself has no channel for. Requests are
+//! only accepted with the token shown in the settings, e.g. from an iOS
+//! Shortcut posting to `http://<desktop>:<port>/clipboard?token=<token>`.
+
+pub mod files;
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use cosmic::iced::futures::channel::mpsc;
+use cosmic::iced::futures::{SinkExt, Stream};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::error::AirTrayError;
+
+/// Largest clipboard text accepted.
+const MAX_TEXT: usize = 64 * 1024;
+
+/// Most a request line and its headers may take together, read before the
+/// token is known, so a stranger can't have the endpoint hold on to more.
+pub const MAX_HEAD: u64 = 8 * 1024;
+
+/// How long a request line and its headers may take to arrive, so idle
+/// connections don't stay open.
+pub const HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Something the phone sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompanionEvent {
+    /// Text to put on the desktop clipboard.
+    Clipboard(String),
+    /// A file was saved to the download directory.
+    FileReceived(PathBuf),
+}
+
+/// Which features the endpoint serves.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Endpoint {
+    pub port: u16,
+    pub token: String,
+    pub clipboard: bool,
+    /// Where received files go, if receiving files is enabled.
+    pub download_dir: Option<PathBuf>,
+}
+
+/// A parsed request line and headers.
+struct Request {
+    method: String,
+    path: String,
+    token: Option<String>,
+    /// File name of an upload.
+    name: Option<String>,
+    content_length: usize,
+}
+
+/// Returns a new random token for the endpoint.
+pub fn new_token() -> Result<String, AirTrayError> {
+    let mut bytes = [0u8; 8];
+    File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut bytes))
+        .map_err(|e| AirTrayError::TokenError(e.to_string()))?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Whether `given` is `token`, compared in constant time so a guess can't be
+/// narrowed down by how long the refusal takes. An empty token matches nothing.
+pub fn token_matches(given: &str, token: &str) -> bool {
+    let difference = given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b));
+    !token.is_empty() && given.len() == token.len() && difference == 0
+}
+
+/// Address the endpoint can be reached at from the phone, given this
+/// machine's address on the LAN.
+pub fn url(address: IpAddr, port: u16) -> String {
+    format!("http://{}:{}/", address, port)
+}
+
+/// Address of the upload page, as encoded in the QR code.
+pub fn upload_url(address: IpAddr, port: u16, token: &str) -> String {
+    format!("{}files?token={}", url(address, port), token)
+}
+
+/// Listens for as long as the stream is polled, yielding what phones send.
+pub fn serve(endpoint: Endpoint) -> impl Stream<Item = CompanionEvent> {
+    cosmic::iced::stream::channel(16, move |events| async move {
+        let listener = match TcpListener::bind(("0.0.0.0", endpoint.port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to listen on port {}: {}", endpoint.port, e);
+                return;
+            }
+        };
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let (endpoint, events) = (endpoint.clone(), events.clone());
+                    tokio::spawn(async move {
+                        if let Err(e) = handle(stream, &endpoint, events).await {
+                            eprintln!("Companion request failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => eprintln!("Failed to accept connection: {}", e),
+            }
+        }
+    })
+}
+
+/// Answers one request.
+async fn handle(
+    stream: TcpStream,
+    endpoint: &Endpoint,
+    mut events: mpsc::Sender<CompanionEvent>,
+) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let request = match tokio::time::timeout(HEAD_TIMEOUT, read_request(&mut stream)).await {
+        Ok(request) => request?,
+        Err(_) => return respond(stream.get_mut(), "408 Request Timeout").await,
+    };
+    let Some(request) = request else {
+        return respond(stream.get_mut(), "400 Bad Request").await;
+    };
+
+    if !request.token.is_some_and(|given| token_matches(&given, &endpoint.token)) {
+        return respond(stream.get_mut(), "403 Forbidden").await;
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/clipboard") if endpoint.clipboard => {
+            if request.content_length > MAX_TEXT {
+                return respond(stream.get_mut(), "413 Payload Too Large").await;
+            }
+            let mut body = vec![0; request.content_length];
+            stream.read_exact(&mut body).await?;
+            let text = String::from_utf8_lossy(&body).into_owned();
+            let _ = events.send(CompanionEvent::Clipboard(text)).await;
+            respond(stream.get_mut(), "204 No Content").await
+        }
+        ("GET", "/files") if endpoint.download_dir.is_some() => {
+            let page = files::upload_page(&endpoint.token);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                page.len(),
+                page
+            );
+            stream.get_mut().write_all(response.as_bytes()).await
+        }
+        ("POST", "/files") => {
+            let Some(dir) = &endpoint.download_dir else {
+                return respond(stream.get_mut(), "404 Not Found").await;
+            };
+            if request.content_length > files::MAX_FILE {
+                return respond(stream.get_mut(), "413 Payload Too Large").await;
+            }
+            let name = request.name.as_deref().unwrap_or_default();
+            match files::receive(&mut stream, request.content_length, dir, name).await {
+                Ok(path) => {
+                    println!("Received {}", path.display());
+                    let _ = events.send(CompanionEvent::FileReceived(path)).await;
+                    respond(stream.get_mut(), "204 No Content").await
+                }
+                Err(e) => {
+                    eprintln!("Failed to receive file: {}", e);
+                    respond(stream.get_mut(), "500 Internal Server Error").await
+                }
+            }
+        }
+        _ => respond(stream.get_mut(), "404 Not Found").await,
+    }
+}
+
+/// Reads the request line and headers, leaving the body in the stream.
+/// `None` if they are malformed or longer than [`MAX_HEAD`].
+async fn read_request(stream: &mut BufReader<TcpStream>) -> io::Result<Option<Request>> {
+    let mut head = stream.take(MAX_HEAD);
+    let mut line = String::new();
+    head.read_line(&mut line).await?;
+
+    // "POST /clipboard?token=0123 HTTP/1.1"
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let param = |key: &str| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+            .map(str::to_string)
+    };
+    let mut request = Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        token: param("token"),
+        name: param("name"),
+        content_length: 0,
+    };
+
+    loop {
+        line.clear();
+        if head.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                request.content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    if head.limit() == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(request))
+}
+
+async fn respond(stream: &mut TcpStream, status: &str) -> io::Result<()> {
+    let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    stream.write_all(response.as_bytes()).await
+}
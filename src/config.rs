@@ -0,0 +1,926 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+pub mod keys;
+pub mod migrations;
+pub mod validation;
+
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, NaiveTime};
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AirTrayError;
+
+/// Persistent applet settings, stored through cosmic-config under the applet's APP_ID.
+///
+/// Renaming a field or changing its type needs a new `#[version]` and a
+/// migration in [`migrations`], or stored values of it are lost.
+#[derive(Debug, Default, Clone, CosmicConfigEntry, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+#[version = 1]
+pub struct Config {
+    /// Name advertised to senders. Empty keeps UXPlay's default naming.
+    pub name: String,
+    /// Switch to a name with the hostname when another receiver on the
+    /// network already uses the name.
+    pub auto_rename: bool,
+    /// Only start UXPlay when a sender tries to connect.
+    pub wake_on_demand: bool,
+    /// Trade smoothness for the lowest possible latency, e.g. for games.
+    pub low_latency: bool,
+    /// Hold the performance power profile while a sender is connected. Game
+    /// mode does this regardless.
+    pub performance_profile: bool,
+    /// Hours during which the receiver is available.
+    pub schedule: ScheduleConfig,
+    /// Named sets of receiver options.
+    pub profiles: Vec<Profile>,
+    /// Name of the profile used when starting. Empty uses no profile.
+    pub profile: String,
+    /// Profiles tied to networks.
+    pub networks: NetworkConfig,
+    /// Audio routing settings.
+    pub audio: AudioConfig,
+    /// Hooks into other desktop software.
+    pub integrations: IntegrationsConfig,
+    /// The mirror window.
+    pub video: VideoConfig,
+    /// Watching the mirror from a browser.
+    pub viewer: ViewerConfig,
+    /// The HTTP endpoint phones can reach during a session.
+    pub companion: CompanionConfig,
+    /// Desktop notifications about senders.
+    pub notifications: NotificationConfig,
+    /// Names shown instead of the ones senders announce, by device id.
+    pub nicknames: BTreeMap<String, String>,
+    /// Settings for individual senders, by device id.
+    pub devices: BTreeMap<String, DeviceConfig>,
+    /// What happens when a second sender connects during a session.
+    pub concurrent_clients: ConcurrentPolicy,
+    /// Minutes a session may last before it is ended, e.g. in a shared
+    /// space. 0 doesn't limit it.
+    pub session_limit: u32,
+    /// Have UXPlay pick a new PIN for every sender instead of the profile's,
+    /// so guests can't come back later without asking.
+    pub random_pin: bool,
+    /// Authentication needed to open the settings and devices pages.
+    pub lock: LockConfig,
+    /// Locked-down mode for shared and public machines, usually set by an
+    /// admin in the system-wide defaults: the receiver starts with the
+    /// applet, the popup only shows the status and the settings always need
+    /// authentication.
+    pub kiosk: bool,
+    /// Only run the receiver while this login session is the one on screen,
+    /// so users sharing the machine don't hold the ports for each other.
+    pub active_seat_only: bool,
+    /// How UXPlay is started.
+    pub launch: LaunchConfig,
+    /// Internet protocol versions the applet listens and announces on.
+    pub ip_version: IpVersion,
+    /// Ports UXPlay uses.
+    pub ports: PortConfig,
+    /// What announces the applet's own services and browses the network.
+    pub mdns: MdnsResponder,
+    /// Run UXPlay with its debug output, echoed into the applet's log.
+    pub debug: bool,
+}
+
+/// Where mDNS announcements and lookups go through.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum MdnsResponder {
+    /// The system's avahi-daemon.
+    #[default]
+    Avahi,
+    /// A responder inside the applet, for systems without avahi-daemon.
+    Builtin,
+}
+
+/// Fixed ports for UXPlay, e.g. to match a firewall rule.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortConfig {
+    /// First of the UDP ports for audio and timing. 0 lets UXPlay pick.
+    pub udp: u16,
+}
+
+impl PortConfig {
+    /// The UDP ports UXPlay will use, empty if it picks them.
+    pub fn udp_range(&self) -> std::ops::Range<u16> {
+        match self.udp {
+            0 => 0..0,
+            first => first..first.saturating_add(crate::ports::UDP_COUNT),
+        }
+    }
+}
+
+/// Where UXPlay is found and how it is started.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchConfig {
+    /// Command entering the container UXPlay is installed in, e.g.
+    /// "distrobox enter media --". Empty runs it on the host.
+    pub container: String,
+    /// Directories searched for UXPlay in order, before PATH. Only used
+    /// on the host.
+    pub search_paths: Vec<PathBuf>,
+    /// Receiver backend by the name it registered as, see [`crate::plugins`].
+    /// Empty uses UXPlay. Read when the applet starts.
+    pub backend: String,
+}
+
+/// Keeps casual users of a shared machine out of the pages that could
+/// weaken the receiver's protections. The receiver can still be toggled.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockConfig {
+    pub method: LockMethod,
+    /// Asked for with [`LockMethod::Pin`].
+    pub pin: String,
+}
+
+/// How the settings are unlocked.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum LockMethod {
+    /// The settings are not locked.
+    #[default]
+    None,
+    /// A PIN kept in the settings.
+    Pin,
+    /// The user's or an administrator's password, asked for by polkit.
+    Polkit,
+}
+
+/// How to treat a sender connecting while another one streams. UXPlay shows
+/// one sender at a time, so allowing it means it takes over.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ConcurrentPolicy {
+    #[default]
+    Reject,
+    /// The new sender replaces the current one.
+    Allow,
+    /// Ask through a notification whether the new sender may take over.
+    Prompt,
+}
+
+/// Internet protocol versions to use, for networks where one of them is
+/// broken, e.g. routers dropping IPv6 multicast.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum IpVersion {
+    #[default]
+    Both,
+    Ipv4,
+    Ipv6,
+}
+
+impl IpVersion {
+    /// Address listening on all interfaces. For [`IpVersion::Both`] this is
+    /// the IPv6 one, which takes IPv4 connections too unless made IPv6-only.
+    pub fn unspecified(self) -> IpAddr {
+        match self {
+            Self::Ipv4 => Ipv4Addr::UNSPECIFIED.into(),
+            Self::Both | Self::Ipv6 => Ipv6Addr::UNSPECIFIED.into(),
+        }
+    }
+
+    /// Address of this machine.
+    pub fn loopback(self) -> IpAddr {
+        match self {
+            Self::Both | Self::Ipv4 => Ipv4Addr::LOCALHOST.into(),
+            Self::Ipv6 => Ipv6Addr::LOCALHOST.into(),
+        }
+    }
+}
+
+/// What a particular sender may do.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceConfig {
+    /// Refuse connections from this device.
+    pub blocked: bool,
+    /// Profile used while this device streams. Empty keeps the selected one.
+    pub profile: String,
+    /// Connecting ends the session of any device without priority.
+    pub priority: bool,
+    /// Hours this device may connect in, if enabled.
+    pub schedule: ScheduleConfig,
+}
+
+impl Config {
+    /// Opens the settings store and loads the current settings, falling back
+    /// to defaults for anything that is missing or unreadable.
+    pub fn load(app_id: &str) -> (Option<cosmic_config::Config>, Self) {
+        if let Some(base) = migrations::base_dir(app_id) {
+            match migrations::upgrade(&base, Self::VERSION) {
+                Ok(Some(from)) => println!("Upgraded settings from version {}", from),
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to upgrade settings: {}", e),
+            }
+        }
+
+        let handler = match cosmic_config::Config::new(app_id, Self::VERSION) {
+            Ok(handler) => handler,
+            Err(e) => {
+                eprintln!("{}", AirTrayError::from(e));
+                return (None, Self::default());
+            }
+        };
+
+        let config = match Self::get_entry(&handler) {
+            Ok(config) => config,
+            Err((errors, config)) => {
+                for error in errors {
+                    eprintln!("{}", AirTrayError::from(error));
+                }
+                config
+            }
+        };
+        for invalid in validation::check(&config) {
+            eprintln!("Invalid setting: {}", invalid);
+        }
+
+        (Some(handler), config)
+    }
+
+    /// How the settings are locked. Kiosk mode always locks them, with the
+    /// system password unless a PIN is set.
+    pub fn lock_method(&self) -> LockMethod {
+        match self.lock.method {
+            LockMethod::None if self.kiosk => LockMethod::Polkit,
+            method => method,
+        }
+    }
+
+    /// The profile selected by [`Config::profile`], if it exists.
+    pub fn active_profile(&self) -> Option<&Profile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.name == self.profile)
+    }
+
+    /// Resolution and frame rate requested from senders for the selected profile.
+    pub fn stream_quality(&self) -> StreamQuality {
+        self.active_profile()
+            .map_or(self.video.quality.clone(), |profile| StreamQuality {
+                resolution: profile.resolution.clone(),
+                fps: profile.fps,
+                codec: profile.codec,
+            })
+    }
+
+    /// Volume of the AirPlay stream for the selected profile.
+    pub fn stream_volume(&self) -> StreamVolume {
+        self.active_profile()
+            .map_or(self.audio.volume, |profile| profile.volume)
+    }
+
+    /// Tone of the AirPlay stream for the selected profile.
+    pub fn equalizer(&self) -> Equalizer {
+        self.active_profile()
+            .map_or(self.audio.equalizer, |profile| profile.equalizer)
+    }
+
+    /// The name to show for a sender: its nickname, or else what it announced.
+    pub fn device_name(&self, id: &str, announced: &str) -> String {
+        self.nicknames
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| announced.to_string())
+    }
+
+    /// The profile to stream to the device with `id` with: its own if it has
+    /// one that still exists, else the selected one.
+    pub fn device_profile(&self, id: &str) -> &str {
+        self.devices
+            .get(id)
+            .map(|device| device.profile.as_str())
+            .filter(|name| self.profiles.iter().any(|profile| &profile.name == name))
+            .unwrap_or(&self.profile)
+    }
+
+    /// Whether the device with `id` is refused.
+    pub fn is_blocked(&self, id: &str) -> bool {
+        self.devices.get(id).is_some_and(|device| device.blocked)
+    }
+
+    /// The devices refused at `now`: those blocked, and those outside
+    /// their hours.
+    pub fn blocked_devices(&self, now: DateTime<Local>) -> Vec<String> {
+        self.devices
+            .iter()
+            .filter(|(_, device)| {
+                device.blocked || (device.schedule.enabled && !device.schedule.is_open(now))
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Whether the device with `id` preempts other sessions.
+    pub fn is_priority(&self, id: &str) -> bool {
+        self.devices.get(id).is_some_and(|device| device.priority)
+    }
+
+    /// The name senders see this receiver as.
+    pub fn receiver_name(&self) -> String {
+        if self.name.is_empty() {
+            format!("UxPlay@{}", hostname())
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Name to switch to when the current one is taken, e.g.
+    /// "Living Room (thinkpad)".
+    pub fn alternative_name(&self) -> String {
+        format!("{} ({})", self.receiver_name(), hostname())
+    }
+
+    /// Puts the settings of `section` back to their defaults, in the store
+    /// of `handler` too if there is one.
+    pub fn reset(
+        &mut self,
+        handler: Option<&cosmic_config::Config>,
+        section: SettingsSection,
+    ) -> Result<(), cosmic_config::Error> {
+        let defaults = Self::default();
+        match section {
+            SettingsSection::All => *self = defaults,
+            SettingsSection::Video => {
+                self.video = defaults.video;
+                self.viewer = defaults.viewer;
+                self.low_latency = defaults.low_latency;
+            }
+            SettingsSection::Audio => self.audio = defaults.audio,
+            SettingsSection::Security => {
+                self.lock = defaults.lock;
+                self.devices = defaults.devices;
+                self.concurrent_clients = defaults.concurrent_clients;
+                self.session_limit = defaults.session_limit;
+                self.active_seat_only = defaults.active_seat_only;
+                self.random_pin = defaults.random_pin;
+            }
+        }
+        match handler {
+            Some(handler) => self.write_entry(handler),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Settings that can be reset together.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SettingsSection {
+    /// Everything, as on a fresh install.
+    All,
+    /// The mirror window, the browser viewer and low latency.
+    Video,
+    /// Audio routing, volume and tone.
+    Audio,
+    /// The settings lock, what individual senders may do, and how a second
+    /// sender is treated. Nicknames stay.
+    Security,
+}
+
+/// Returns the machine's hostname, falling back to "localhost".
+pub fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|name| name.trim().to_string())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| String::from("localhost"))
+}
+
+/// Settings for desktop notifications.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Notify when senders connect and leave.
+    pub enabled: bool,
+    /// Seconds a sender has to reconnect before it counts as gone.
+    pub grace_period: u32,
+    /// Play a sound when senders connect and leave.
+    pub sounds: bool,
+    /// Sound theme name or file played when a sender connects.
+    pub connect_sound: String,
+    /// Sound theme name or file played when a sender leaves.
+    pub disconnect_sound: String,
+    /// Sum up the past week's streaming on Monday mornings.
+    pub weekly_summary: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            grace_period: 10,
+            sounds: false,
+            connect_sound: String::from("device-added"),
+            disconnect_sound: String::from("device-removed"),
+            weekly_summary: true,
+        }
+    }
+}
+
+/// Settings for the Audio section of the popup.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// Node name of the sink to play on instead of the default one.
+    pub sink: Option<String>,
+    /// Play the received audio on a second sink in addition to the default one.
+    pub duplicate: bool,
+    /// Node name of the second sink used when `duplicate` is enabled.
+    pub secondary_sink: Option<String>,
+    /// Multi-room output through a Snapcast server.
+    pub snapcast: SnapcastConfig,
+    /// Volume of the AirPlay stream when no profile is selected.
+    pub volume: StreamVolume,
+    /// Pause the desktop's media players when a sender connects.
+    pub pause_local_media: bool,
+    /// Resume the paused players when the session ends.
+    pub resume_local_media: bool,
+    /// Saving audio-only sessions to files.
+    pub recording: RecordingConfig,
+    /// Tone of the AirPlay stream when no profile is selected.
+    pub equalizer: Equalizer,
+    /// How late each sink plays audio in ms, by node name, e.g. for HDMI
+    /// soundbars.
+    pub sink_delays: BTreeMap<String, u32>,
+}
+
+impl AudioConfig {
+    /// How late the sink `name` plays audio in ms.
+    pub fn sink_delay(&self, name: &str) -> u32 {
+        self.sink_delays.get(name).copied().unwrap_or(0)
+    }
+}
+
+/// Settings for recording audio-only sessions, one file per track.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingConfig {
+    pub enabled: bool,
+    pub format: RecordingFormat,
+    /// Where recordings are saved. `None` uses the music directory.
+    pub directory: Option<PathBuf>,
+}
+
+impl RecordingConfig {
+    /// The directory recordings are saved to.
+    pub fn directory(&self) -> PathBuf {
+        self.directory.clone().unwrap_or_else(|| {
+            dirs::audio_dir()
+                .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join("Music"))
+                .join("AirPlay")
+        })
+    }
+}
+
+/// File format of recordings.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RecordingFormat {
+    #[default]
+    Flac,
+    Ogg,
+}
+
+impl RecordingFormat {
+    /// File extension, which is also the name libsndfile knows it by.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Flac => "flac",
+            Self::Ogg => "ogg",
+        }
+    }
+}
+
+/// Volume of the AirPlay playback stream, independent of the sink volume.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamVolume {
+    /// In percent, up to 150.
+    pub percent: u32,
+    pub muted: bool,
+}
+
+impl Default for StreamVolume {
+    fn default() -> Self {
+        Self {
+            percent: 100,
+            muted: false,
+        }
+    }
+}
+
+/// Bass and treble of the AirPlay stream, e.g. to help thin TV speakers.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Equalizer {
+    pub enabled: bool,
+    /// Gain of the low frequencies in dB, from -12 to 12.
+    pub bass: i32,
+    /// Gain of the high frequencies in dB, from -12 to 12.
+    pub treble: i32,
+    /// Boost both further, for listening at low volume.
+    pub loudness: bool,
+}
+
+/// Where to send the audio when multi-room output is enabled.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapcastConfig {
+    /// Feed the audio into Snapcast instead of playing it locally.
+    pub enabled: bool,
+    /// Host running snapserver.
+    pub host: String,
+    /// Port of the snapserver `tcp://` source.
+    pub port: u16,
+}
+
+impl Default for SnapcastConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::from("127.0.0.1"),
+            port: 4953,
+        }
+    }
+}
+
+/// Settings for the Integrations section of the settings page.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntegrationsConfig {
+    /// Look up connecting phones in KDE Connect to show their battery and ping them.
+    pub kdeconnect: bool,
+    /// Publish the receiver state to an MQTT broker.
+    pub mqtt: MqttConfig,
+    pub hooks: HooksConfig,
+    pub webhook: WebhookConfig,
+    /// An HTTP API for controlling the receiver from other devices.
+    pub rest: RestConfig,
+}
+
+/// Broker the receiver state is published to.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// Leave empty for brokers without authentication.
+    pub username: String,
+    pub password: String,
+    /// Topics are published below this prefix, e.g. `airtray/state`.
+    pub topic_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::from("localhost"),
+            port: 1883,
+            username: String::new(),
+            password: String::new(),
+            topic_prefix: String::from("airtray"),
+        }
+    }
+}
+
+/// Settings of the HTTP control API.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// Secret every request has to include.
+    pub token: String,
+    /// Accept requests from other devices, not only from this computer.
+    pub lan: bool,
+}
+
+impl Default for RestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8091,
+            token: String::new(),
+            lan: false,
+        }
+    }
+}
+
+/// HTTP endpoint called on session and error events.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub enabled: bool,
+    /// An `http://` URL, e.g. `http://localhost:5678/webhook/airtray`.
+    pub url: String,
+}
+
+/// Shell commands run on receiver events. Empty ones are skipped.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub on_start: String,
+    pub on_stop: String,
+    pub on_client_connect: String,
+    /// Run once a sender's grace period is over, like the notification.
+    pub on_client_disconnect: String,
+}
+
+/// A receiver event a hook runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    Start,
+    Stop,
+    ClientConnect,
+    ClientDisconnect,
+}
+
+impl HooksConfig {
+    /// The command run on `hook`.
+    pub fn command(&self, hook: Hook) -> &str {
+        match hook {
+            Hook::Start => &self.on_start,
+            Hook::Stop => &self.on_stop,
+            Hook::ClientConnect => &self.on_client_connect,
+            Hook::ClientDisconnect => &self.on_client_disconnect,
+        }
+    }
+
+    /// Sets the command run on `hook`.
+    pub fn set(&mut self, hook: Hook, command: String) {
+        match hook {
+            Hook::Start => self.on_start = command,
+            Hook::Stop => self.on_stop = command,
+            Hook::ClientConnect => self.on_client_connect = command,
+            Hook::ClientDisconnect => self.on_client_disconnect = command,
+        }
+    }
+}
+
+/// Settings for the mirror window.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoConfig {
+    /// Monitor to show the mirror fullscreen on. Empty lets the compositor place the window.
+    pub output: String,
+    /// Show the mirror in a small always-on-top window instead.
+    pub pip: PipConfig,
+    /// Show the mirror in a window drawn by the applet instead of UXPlay's own.
+    pub embedded: bool,
+    /// Brightness, contrast and saturation of the mirrored picture.
+    pub picture: PictureConfig,
+    /// How the mirror drawn by the applet is scaled.
+    pub scaling: ScalingConfig,
+    /// Quality requested from senders when no profile is selected.
+    pub quality: StreamQuality,
+    /// Estimated stream bitrate in Mbit/s to stay under. 0 doesn't limit it.
+    pub max_bitrate: u32,
+    /// Act as a second monitor for a Mac: fullscreen at the monitor's native
+    /// resolution, without anything drawn on top.
+    pub extended_display: bool,
+    /// Kind of session UXPlay's window is made for.
+    pub display_server: DisplayServer,
+    /// Render node of the GPU decoding and drawing the mirror, e.g.
+    /// "renderD129". Empty uses the default one.
+    pub gpu: String,
+    /// Show how to connect on screen while the receiver waits for a sender.
+    pub signage: bool,
+}
+
+/// Display server the video sink and fullscreen handling are chosen for.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DisplayServer {
+    /// Whatever the desktop session runs on.
+    #[default]
+    Automatic,
+    Wayland,
+    X11,
+}
+
+/// Resolution and frame rate requested from senders.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamQuality {
+    /// Maximum resolution, e.g. "1920x1080". Empty keeps UXPlay's default.
+    pub resolution: String,
+    /// Maximum frame rate. 0 keeps UXPlay's default.
+    pub fps: u32,
+    pub codec: Codec,
+}
+
+/// Video codec offered to senders.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Codec {
+    #[default]
+    H264,
+    /// Falls back to H.264 if GStreamer can't decode it.
+    H265,
+}
+
+/// Scaling of the mirror drawn by the applet.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScalingConfig {
+    pub method: ScaleMethod,
+    /// Only scale by whole multiples, for pixel-perfect low-resolution sources.
+    pub integer: bool,
+}
+
+/// Filter used to scale the mirrored picture.
+#[derive(Debug, Clone, Copy, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ScaleMethod {
+    Nearest,
+    #[default]
+    #[serde(alias = "Lanczos")]
+    Bilinear,
+}
+
+/// Adjustments applied to the mirrored picture.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PictureConfig {
+    /// Offset from -100 to 100.
+    pub brightness: i32,
+    /// Percent from 0 to 200.
+    pub contrast: u32,
+    /// Percent from 0 to 200.
+    pub saturation: u32,
+}
+
+impl Default for PictureConfig {
+    fn default() -> Self {
+        Self {
+            brightness: 0,
+            contrast: 100,
+            saturation: 100,
+        }
+    }
+}
+
+/// Settings for the picture-in-picture mirror.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipConfig {
+    pub enabled: bool,
+    pub corner: Corner,
+    pub size: PipSize,
+}
+
+/// Screen corner the picture-in-picture window sits in.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Size of the picture-in-picture window.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PipSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl PipSize {
+    /// Width and height in logical pixels, 16:9.
+    pub fn dimensions(self) -> (u32, u32) {
+        match self {
+            Self::Small => (320, 180),
+            Self::Medium => (480, 270),
+            Self::Large => (640, 360),
+        }
+    }
+}
+
+/// Settings for the browser viewer.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewerConfig {
+    /// Serve the mirrored screen over WebRTC instead of showing it locally.
+    pub enabled: bool,
+    /// Port of the web page.
+    pub port: u16,
+}
+
+impl Default for ViewerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 8080,
+        }
+    }
+}
+
+/// Settings for the companion endpoint.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompanionConfig {
+    /// Accept clipboard text from the phone.
+    pub clipboard: bool,
+    /// Accept files from the phone.
+    pub files: bool,
+    /// Where received files are saved. `None` uses the Downloads folder.
+    pub download_dir: Option<PathBuf>,
+    /// Port the endpoint listens on.
+    pub port: u16,
+    /// Secret phones have to include in their requests.
+    pub token: String,
+}
+
+impl Default for CompanionConfig {
+    fn default() -> Self {
+        Self {
+            clipboard: false,
+            files: false,
+            download_dir: None,
+            port: 8090,
+            token: String::new(),
+        }
+    }
+}
+
+impl CompanionConfig {
+    /// The directory received files are saved to.
+    pub fn download_dir(&self) -> PathBuf {
+        self.download_dir
+            .clone()
+            .or_else(dirs::download_dir)
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join("Downloads"))
+    }
+}
+
+/// A daily availability window.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    /// Turn the receiver on and off automatically.
+    pub enabled: bool,
+    /// Days the window opens on, 0 being Monday.
+    pub days: Vec<u8>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            days: vec![0, 1, 2, 3, 4],
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+            end: NaiveTime::from_hms_opt(18, 0, 0).unwrap_or_default(),
+        }
+    }
+}
+
+/// Receiver options that can be switched as a whole, e.g. per network.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    /// Four-digit PIN senders have to enter. Empty allows anyone to connect.
+    pub pin: String,
+    /// Maximum resolution requested from senders, e.g. "1920x1080". Empty keeps UXPlay's default.
+    pub resolution: String,
+    /// Maximum frame rate requested from senders. 0 keeps UXPlay's default.
+    pub fps: u32,
+    pub codec: Codec,
+    /// Volume of the AirPlay stream while this profile is selected.
+    pub volume: StreamVolume,
+    /// Tone of the AirPlay stream while this profile is selected.
+    pub equalizer: Equalizer,
+    /// GStreamer video sink given to UXPlay instead of the one AirTray picks,
+    /// e.g. "glimagesink sync=false". Empty keeps AirTray's.
+    pub video_pipeline: String,
+    /// GStreamer audio sink given to UXPlay, like `video_pipeline`.
+    pub audio_pipeline: String,
+}
+
+/// Settings for switching profiles by network.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Turn the receiver on with the matching profile on known networks, and off elsewhere.
+    pub enabled: bool,
+    pub rules: Vec<NetworkRule>,
+}
+
+/// A profile to use on a network.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkRule {
+    /// NetworkManager connection id, e.g. "Home-WiFi".
+    pub connection: String,
+    /// Name of the profile; empty uses no profile.
+    pub profile: String,
+}
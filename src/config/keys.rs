@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Settings addressed by dotted keys like `video.fps`, for changing them from
+//! the command line. Values are JSON, as the settings serialize to it.
+
+use serde_json::Value;
+
+use super::{validation, Config};
+use crate::error::AirTrayError;
+
+/// The value of the setting at `key` in `config`, or all settings for an
+/// empty key.
+pub fn get(config: &Config, key: &str) -> Result<Value, AirTrayError> {
+    let settings = to_json(config)?;
+    settings.pointer(&pointer(key)).cloned().ok_or_else(|| unknown(key))
+}
+
+/// `config` with the setting at `key` changed to `value`. Text that isn't
+/// JSON is taken as a string, so names don't need quoting twice.
+pub fn set(config: &Config, key: &str, value: &str) -> Result<Config, AirTrayError> {
+    let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+    let mut settings = to_json(config)?;
+    // Only existing keys, as unknown ones would be dropped without a word
+    *settings.pointer_mut(&pointer(key)).ok_or_else(|| unknown(key))? = value;
+
+    let changed: Config = serde_json::from_value(settings)
+        .map_err(|e| AirTrayError::ConfigError(format!("{}: {}", key, e)))?;
+
+    // Catch what UXPlay couldn't start with, unless it was wrong before
+    let before = validation::check(config);
+    match validation::check(&changed).into_iter().find(|invalid| !before.contains(invalid)) {
+        Some(invalid) => Err(AirTrayError::ConfigError(invalid.to_string())),
+        None => Ok(changed),
+    }
+}
+
+fn to_json(config: &Config) -> Result<Value, AirTrayError> {
+    serde_json::to_value(config).map_err(|e| AirTrayError::ConfigError(e.to_string()))
+}
+
+/// The JSON pointer for `key`, e.g. "/video/fps" for "video.fps".
+fn pointer(key: &str) -> String {
+    key.split('.')
+        .filter(|part| !part.is_empty())
+        .map(|part| format!("/{}", part.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+fn unknown(key: &str) -> AirTrayError {
+    AirTrayError::ConfigError(format!("no setting {}", key))
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Upgrades of settings written by older versions of the applet.
+//!
+//! cosmic-config keeps the settings of each `#[version]` of [`Config`] in a
+//! directory of its own, `v1`, `v2` and so on, with a file per key holding
+//! its value as RON. A new version starts out empty, so when one is
+//! introduced, e.g. to rename a key, the settings of the newest older
+//! version are carried over through the migrations in between.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cosmic::cosmic_config::CosmicConfigEntry;
+
+use super::Config;
+
+/// Values of settings as RON, by key.
+pub type Entries = BTreeMap<String, String>;
+
+/// Turns the entries of one version into those of the next:
+/// `MIGRATIONS[0]` upgrades version 1 to 2, and so on.
+const MIGRATIONS: &[fn(&mut Entries)] = &[];
+
+// Every version but the first needs a way there
+const _: () = assert!(MIGRATIONS.len() as u64 + 1 == Config::VERSION);
+
+/// The directory cosmic-config keeps all versions of `app_id`'s settings in.
+pub fn base_dir(app_id: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("cosmic").join(app_id))
+}
+
+/// Carries the settings of the newest version older than `version` over
+/// to it, unless it has settings already. Returns the version upgraded from.
+pub fn upgrade(base: &Path, version: u64) -> io::Result<Option<u64>> {
+    if version_dir(base, version).exists() {
+        return Ok(None);
+    }
+    let Some(from) = (1..version).rev().find(|old| version_dir(base, *old).is_dir()) else {
+        return Ok(None);
+    };
+
+    let mut entries = read(&version_dir(base, from))?;
+    migrate(&mut entries, from);
+    write(&version_dir(base, version), &entries)?;
+    Ok(Some(from))
+}
+
+/// Applies the migrations from version `from` on to the current one.
+pub fn migrate(entries: &mut Entries, from: u64) {
+    let done = from.saturating_sub(1) as usize;
+    for migration in MIGRATIONS.iter().skip(done) {
+        migration(entries);
+    }
+}
+
+/// The entries stored in the version directory `dir`.
+pub fn read(dir: &Path) -> io::Result<Entries> {
+    let mut entries = Entries::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            let key = entry.file_name().to_string_lossy().into_owned();
+            entries.insert(key, std::fs::read_to_string(entry.path())?);
+        }
+    }
+    Ok(entries)
+}
+
+fn write(dir: &Path, entries: &Entries) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for (key, value) in entries {
+        std::fs::write(dir.join(key), value)?;
+    }
+    Ok(())
+}
+
+fn version_dir(base: &Path, version: u64) -> PathBuf {
+    base.join(format!("v{version}"))
+}
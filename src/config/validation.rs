@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Checks of settings UXPlay would fail to start with, so they are caught
+//! where they are entered rather than when it exits.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use super::Config;
+use crate::backend::BINARY;
+use crate::host;
+use crate::ports;
+
+/// A setting UXPlay can't be started with.
+#[derive(Debug, Clone, Error, Eq, PartialEq)]
+pub enum Invalid {
+    #[error("UDP port {0} is privileged or leaves no room for the ports after it")]
+    UdpPort(u16),
+    #[error("resolution \"{0}\" is not WIDTHxHEIGHT")]
+    Resolution(String),
+    #[error("search path {} is not a directory", .0.display())]
+    SearchPath(PathBuf),
+    /// The program entering the container isn't installed.
+    #[error("container command {0} is not installed")]
+    Container(String),
+    /// UXPlay is neither in the search paths nor in PATH.
+    #[error("{BINARY} is not installed")]
+    Uxplay,
+}
+
+/// Everything wrong with `config`.
+pub fn check(config: &Config) -> Vec<Invalid> {
+    let mut invalid = Vec::new();
+
+    if !udp_port(config.ports.udp) {
+        invalid.push(Invalid::UdpPort(config.ports.udp));
+    }
+    let resolutions = config.profiles.iter().map(|profile| &profile.resolution);
+    for text in std::iter::once(&config.video.quality.resolution).chain(resolutions) {
+        if !resolution(text) {
+            invalid.push(Invalid::Resolution(text.clone()));
+        }
+    }
+
+    // The host's directories are out of sight of a sandbox
+    if !host::in_flatpak() {
+        invalid.extend(
+            config
+                .launch
+                .search_paths
+                .iter()
+                .filter(|dir| !dir.is_dir())
+                .map(|dir| Invalid::SearchPath(dir.clone())),
+        );
+    }
+    match config.launch.container.split_whitespace().next() {
+        Some(program) if !host::installed(program, &[]) => {
+            invalid.push(Invalid::Container(program.to_string()));
+        }
+        Some(_) => {}
+        None if !host::installed(BINARY, &config.launch.search_paths) => {
+            invalid.push(Invalid::Uxplay);
+        }
+        None => {}
+    }
+
+    invalid
+}
+
+/// Whether UXPlay can use the UDP ports from `port` on: unprivileged, with
+/// room for the ones that follow. 0 lets UXPlay pick.
+pub fn udp_port(port: u16) -> bool {
+    port == 0 || (1024..=u16::MAX - ports::UDP_COUNT + 1).contains(&port)
+}
+
+/// Whether `text` is a resolution like "1920x1080". Empty leaves it to
+/// the sender.
+pub fn resolution(text: &str) -> bool {
+    if text.is_empty() {
+        return true;
+    }
+    let Some((width, height)) = text.split_once('x') else {
+        return false;
+    };
+    matches!(
+        (width.parse::<u32>(), height.parse::<u32>()),
+        (Ok(width), Ok(height)) if width > 0 && height > 0
+    )
+}
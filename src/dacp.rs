@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Remote control of the sender through DACP, the protocol AirPlay senders
+//! expose for play/pause and volume.
+//!
+//! UXPlay exports the current sender's DACP-ID and Active-Remote token to a
+//! file (`-dacp`); the sender advertises itself as `iTunes_Ctrl_<DACP-ID>`
+//! over mDNS, and accepts commands as plain HTTP requests carrying the token.
+
+use std::path::PathBuf;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::error::AirTrayError;
+use crate::host;
+
+/// A control command understood by AirPlay senders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DacpCommand {
+    PlayPause,
+    Pause,
+    Next,
+    Previous,
+    VolumeUp,
+    VolumeDown,
+}
+
+impl DacpCommand {
+    fn path(self) -> &'static str {
+        match self {
+            Self::PlayPause => "playpause",
+            Self::Pause => "pause",
+            Self::Next => "nextitem",
+            Self::Previous => "previtem",
+            Self::VolumeUp => "volumeup",
+            Self::VolumeDown => "volumedown",
+        }
+    }
+}
+
+/// Where UXPlay is told to write the DACP details of the connected sender.
+pub fn export_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("airtray-dacp")
+}
+
+/// Sends `command` to the connected sender.
+pub async fn send(command: DacpCommand) -> Result<(), AirTrayError> {
+    let (dacp_id, active_remote) = read_export()?;
+    let (host, port) = tokio::task::spawn_blocking(move || resolve(&dacp_id))
+        .await
+        .map_err(|e| AirTrayError::ControlError(e.to_string()))??;
+
+    let request = format!(
+        "GET /ctrl-int/1/{} HTTP/1.1\r\nHost: {host}:{port}\r\nActive-Remote: {active_remote}\r\nConnection: close\r\n\r\n",
+        command.path()
+    );
+
+    let io = |e: std::io::Error| AirTrayError::ControlError(e.to_string());
+    let mut stream = TcpStream::connect((host.as_str(), port)).await.map_err(io)?;
+    stream.write_all(request.as_bytes()).await.map_err(io)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.map_err(io)?;
+
+    // "HTTP/1.1 204 No Content"
+    let status = response.split_whitespace().nth(1).unwrap_or_default();
+    if status.starts_with('2') {
+        Ok(())
+    } else {
+        Err(AirTrayError::ControlError(format!(
+            "sender answered {}",
+            response.lines().next().unwrap_or_default()
+        )))
+    }
+}
+
+/// Reads the DACP-ID and Active-Remote token UXPlay exported.
+fn read_export() -> Result<(String, String), AirTrayError> {
+    let contents = std::fs::read_to_string(export_path())
+        .map_err(|_| AirTrayError::ControlError(String::from("no sender to control")))?;
+
+    // One value per line, optionally prefixed with its name
+    let mut values = contents
+        .lines()
+        .map(|line| line.rsplit_once(':').map_or(line, |(_, value)| value).trim())
+        .filter(|value| !value.is_empty());
+
+    match (values.next(), values.next()) {
+        (Some(dacp_id), Some(active_remote)) => {
+            Ok((dacp_id.to_string(), active_remote.to_string()))
+        }
+        _ => Err(AirTrayError::ControlError(String::from("no sender to control"))),
+    }
+}
+
+/// Finds the address the sender's DACP service listens on through Avahi.
+fn resolve(dacp_id: &str) -> Result<(String, u16), AirTrayError> {
+    let output = host::command("avahi-browse")
+        .args(["--resolve", "--parsable", "--terminate", "_dacp._tcp"])
+        .output()
+        .map_err(|e| AirTrayError::spawn("avahi-browse", e))?;
+
+    let service = format!("iTunes_Ctrl_{dacp_id}");
+    // "=;wlan0;IPv4;iTunes_Ctrl_0123456789ABCDEF;_dacp._tcp;local;iPhone.local;192.168.1.20;49374;..."
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.starts_with('='))
+        .map(|line| line.split(';').collect::<Vec<_>>())
+        .filter(|fields| fields.len() > 8 && fields[2] == "IPv4" && fields[3] == service)
+        .find_map(|fields| Some((fields[7].to_string(), fields[8].parse().ok()?)))
+        .ok_or_else(|| AirTrayError::ControlError(String::from("sender not found on the network")))
+}
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Offers this machine as a Bluetooth audio sink through BlueZ.
+//!
+//! The A2DP sink endpoint itself is registered by PipeWire; all that is needed
+//! to receive audio from a phone is an adapter that is powered, pairable and
+//! discoverable.
+
+use zbus::zvariant::OwnedObjectPath;
+use zbus::{proxy, Connection};
+
+use crate::error::AirTrayError;
+
+#[proxy(interface = "org.bluez.Adapter1", default_service = "org.bluez")]
+trait Adapter1 {
+    #[zbus(property)]
+    fn powered(&self) -> zbus::Result<bool>;
+    #[zbus(property)]
+    fn set_powered(&self, value: bool) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn discoverable(&self) -> zbus::Result<bool>;
+    #[zbus(property)]
+    fn set_discoverable(&self, value: bool) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_discoverable_timeout(&self, value: u32) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn pairable(&self) -> zbus::Result<bool>;
+    #[zbus(property)]
+    fn set_pairable(&self, value: bool) -> zbus::Result<()>;
+}
+
+/// Finds the first Bluetooth adapter, if there is one.
+async fn adapter(connection: &Connection) -> Result<Option<Adapter1Proxy<'static>>, AirTrayError> {
+    let manager = zbus::fdo::ObjectManagerProxy::builder(connection)
+        .destination("org.bluez")?
+        .path("/")?
+        .build()
+        .await?;
+
+    let path: Option<OwnedObjectPath> = manager
+        .get_managed_objects()
+        .await?
+        .into_iter()
+        .find(|(_, interfaces)| interfaces.contains_key("org.bluez.Adapter1"))
+        .map(|(path, _)| path);
+
+    match path {
+        Some(path) => Ok(Some(
+            Adapter1Proxy::builder(connection).path(path)?.build().await?,
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Whether the machine is currently accepting Bluetooth audio, or `None` if
+/// there is no adapter.
+pub async fn sink_enabled() -> Result<Option<bool>, AirTrayError> {
+    let connection = Connection::system().await?;
+    let Some(adapter) = adapter(&connection).await? else {
+        return Ok(None);
+    };
+
+    let enabled =
+        adapter.powered().await? && adapter.pairable().await? && adapter.discoverable().await?;
+    Ok(Some(enabled))
+}
+
+/// Makes the adapter visible and pairable so phones can connect as an audio
+/// source, or hides it again.
+pub async fn set_sink_enabled(enabled: bool) -> Result<bool, AirTrayError> {
+    let connection = Connection::system().await?;
+    let adapter = adapter(&connection)
+        .await?
+        .ok_or_else(|| AirTrayError::DbusError(String::from("no Bluetooth adapter found")))?;
+
+    if enabled {
+        adapter.set_powered(true).await?;
+        // Stay visible for as long as the sink is on
+        adapter.set_discoverable_timeout(0).await?;
+    }
+    adapter.set_pairable(enabled).await?;
+    adapter.set_discoverable(enabled).await?;
+
+    Ok(enabled)
+}
@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Looks up connected senders in KDE Connect to show their battery and let
+//! them know mirroring started.
+
+use std::collections::HashMap;
+
+use zbus::{proxy, Connection};
+
+use crate::error::AirTrayError;
+
+#[proxy(
+    interface = "org.kde.kdeconnect.daemon",
+    default_service = "org.kde.kdeconnect",
+    default_path = "/modules/kdeconnect"
+)]
+trait Daemon {
+    /// Device ids mapped to their names.
+    #[zbus(name = "deviceNames")]
+    fn device_names(
+        &self,
+        only_reachable: bool,
+        only_paired: bool,
+    ) -> zbus::Result<HashMap<String, String>>;
+}
+
+#[proxy(
+    interface = "org.kde.kdeconnect.device.battery",
+    default_service = "org.kde.kdeconnect"
+)]
+trait Battery {
+    #[zbus(property, name = "charge")]
+    fn charge(&self) -> zbus::Result<i32>;
+    #[zbus(property, name = "isCharging")]
+    fn is_charging(&self) -> zbus::Result<bool>;
+}
+
+#[proxy(
+    interface = "org.kde.kdeconnect.device.ping",
+    default_service = "org.kde.kdeconnect"
+)]
+trait Ping {
+    #[zbus(name = "sendPing")]
+    fn send_ping(&self, message: &str) -> zbus::Result<()>;
+}
+
+/// Battery state of a phone, as reported by KDE Connect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatteryLevel {
+    /// Charge in percent.
+    pub charge: i32,
+    pub charging: bool,
+}
+
+fn device_path(id: &str, plugin: &str) -> String {
+    format!("/modules/kdeconnect/devices/{id}/{plugin}")
+}
+
+/// Finds the reachable, paired device called `name`. AirPlay senders announce
+/// the same name the phone uses everywhere else, so matching by name is enough.
+async fn find_device(connection: &Connection, name: &str) -> Result<Option<String>, AirTrayError> {
+    let devices = DaemonProxy::new(connection).await?.device_names(true, true).await?;
+
+    Ok(devices
+        .into_iter()
+        .find(|(_, device)| device.eq_ignore_ascii_case(name))
+        .map(|(id, _)| id))
+}
+
+/// Greets the phone called `name`, if KDE Connect knows it, and returns its
+/// battery level. Returns `None` when the phone is not paired with KDE Connect.
+pub async fn greet(name: String, message: String) -> Result<Option<BatteryLevel>, AirTrayError> {
+    let connection = Connection::session().await?;
+    let Some(id) = find_device(&connection, &name).await? else {
+        return Ok(None);
+    };
+
+    let ping = PingProxy::builder(&connection)
+        .path(device_path(&id, "ping"))?
+        .build()
+        .await?;
+    if let Err(e) = ping.send_ping(&message).await {
+        // Not fatal, the ping plugin may just be disabled on the phone
+        println!("Failed to ping {}: {}", name, e);
+    }
+
+    let battery = BatteryProxy::builder(&connection)
+        .path(device_path(&id, "battery"))?
+        .build()
+        .await?;
+
+    Ok(Some(BatteryLevel {
+        charge: battery.charge().await?,
+        charging: battery.is_charging().await?,
+    }))
+}
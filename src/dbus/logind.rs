@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Follows whether this login session is the one in front of the screen,
+//! so the receiver of a user who switched away can free the ports for the
+//! one who is there.
+
+use cosmic::iced::futures::{SinkExt, Stream, StreamExt};
+use zbus::{proxy, Connection};
+
+use crate::error::AirTrayError;
+
+#[proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1/session/auto"
+)]
+trait Session {
+    /// Whether the session is in the foreground of its seat.
+    #[zbus(property)]
+    fn active(&self) -> zbus::Result<bool>;
+}
+
+/// Yields whether the session is active now and whenever that changes.
+pub fn watch_active() -> impl Stream<Item = bool> {
+    cosmic::iced::stream::channel(4, |mut output| async move {
+        let result: Result<(), AirTrayError> = async {
+            let connection = Connection::system().await?;
+            let session = SessionProxy::new(&connection).await?;
+            let mut changes = session.receive_active_changed().await;
+
+            // The first change is the current value
+            while let Some(change) = changes.next().await {
+                let _ = output.send(change.get().await?).await;
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            eprintln!("Failed to follow the login session: {}", e);
+        }
+        std::future::pending::<()>().await;
+    })
+}
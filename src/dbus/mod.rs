@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Integrations with other desktop services over D-Bus.
+
+pub mod bluez;
+pub mod kdeconnect;
+pub mod logind;
+pub mod mpris;
+pub mod networkmanager;
+pub mod notifications;
+pub mod polkit;
+pub mod powerprofiles;
+pub mod service;
+#[cfg(feature = "tray")]
+pub mod tray;
+
+use crate::error::AirTrayError;
+
+impl From<zbus::Error> for AirTrayError {
+    fn from(error: zbus::Error) -> Self {
+        Self::DbusError(error.to_string())
+    }
+}
+
+impl From<zbus::fdo::Error> for AirTrayError {
+    fn from(error: zbus::fdo::Error) -> Self {
+        Self::DbusError(error.to_string())
+    }
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A minimal MPRIS player for the active session, so media keys and the
+//! desktop's media controls reach the sender, and control of the desktop's
+//! own players.
+
+use std::collections::HashMap;
+
+use cosmic::iced::futures::channel::mpsc;
+use cosmic::iced::futures::{SinkExt, Stream};
+use zbus::{interface, proxy, Connection};
+use zbus::zvariant::OwnedValue;
+
+use crate::dacp::DacpCommand;
+use crate::error::AirTrayError;
+
+/// Well-known name the player is registered under.
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.airtray";
+/// Path MPRIS clients look for players at.
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+
+#[proxy(
+    interface = "org.mpris.MediaPlayer2.Player",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+trait LocalPlayer {
+    fn pause(&self) -> zbus::Result<()>;
+    fn play(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> zbus::Result<String>;
+}
+
+struct Root;
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> &str {
+        "AirTray"
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+struct Player {
+    commands: mpsc::Sender<DacpCommand>,
+}
+
+impl Player {
+    async fn send(&self, command: DacpCommand) {
+        let _ = self.commands.clone().send(command).await;
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    async fn play_pause(&self) {
+        self.send(DacpCommand::PlayPause).await;
+    }
+
+    // The sender's state isn't known, so play and play/pause are the same
+    async fn play(&self) {
+        self.send(DacpCommand::PlayPause).await;
+    }
+
+    async fn pause(&self) {
+        self.send(DacpCommand::Pause).await;
+    }
+
+    async fn stop(&self) {
+        self.send(DacpCommand::Pause).await;
+    }
+
+    async fn next(&self) {
+        self.send(DacpCommand::Next).await;
+    }
+
+    async fn previous(&self) {
+        self.send(DacpCommand::Previous).await;
+    }
+
+    #[zbus(property)]
+    fn playback_status(&self) -> &str {
+        "Playing"
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        HashMap::new()
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+/// Registers the player for as long as the stream is polled, yielding the
+/// commands it receives.
+pub fn serve() -> impl Stream<Item = DacpCommand> {
+    cosmic::iced::stream::channel(16, |commands| async move {
+        let connection = zbus::connection::Builder::session()
+            .and_then(|builder| builder.name(BUS_NAME))
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, Root))
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, Player { commands }));
+
+        let _connection = match connection {
+            Ok(builder) => match builder.build().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    eprintln!("Failed to register {} on the session bus: {}", BUS_NAME, e);
+                    return;
+                }
+            },
+            Err(e) => {
+                eprintln!("Failed to set up the MPRIS player: {}", e);
+                return;
+            }
+        };
+
+        std::future::pending::<()>().await;
+    })
+}
+
+/// Pauses every other player on the session bus that is playing, returning
+/// their bus names so they can be resumed.
+pub async fn pause_players() -> Result<Vec<String>, AirTrayError> {
+    let connection = Connection::session().await?;
+    let names = zbus::fdo::DBusProxy::new(&connection)
+        .await?
+        .list_names()
+        .await?;
+
+    let mut paused = Vec::new();
+    for name in names {
+        let name = name.to_string();
+        if !name.starts_with("org.mpris.MediaPlayer2.") || name == BUS_NAME {
+            continue;
+        }
+
+        let player = LocalPlayerProxy::builder(&connection)
+            .destination(name.clone())?
+            .build()
+            .await?;
+        if player.playback_status().await.is_ok_and(|status| status == "Playing") {
+            match player.pause().await {
+                Ok(()) => paused.push(name),
+                Err(e) => eprintln!("Failed to pause {}: {}", name, e),
+            }
+        }
+    }
+
+    Ok(paused)
+}
+
+/// Resumes the players paused by [`pause_players`].
+pub async fn resume_players(names: Vec<String>) -> Result<(), AirTrayError> {
+    let connection = Connection::session().await?;
+    for name in names {
+        let player = LocalPlayerProxy::builder(&connection)
+            .destination(name.clone())?
+            .build()
+            .await?;
+        // The player may have quit in the meantime
+        if let Err(e) = player.play().await {
+            eprintln!("Failed to resume {}: {}", name, e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Follows the primary network connection through NetworkManager, to switch
+//! profiles by network and to wait for the network before starting.
+
+use std::time::Duration;
+
+use cosmic::iced::futures::{SinkExt, Stream, StreamExt};
+use zbus::zvariant::OwnedObjectPath;
+use zbus::{proxy, Connection};
+
+use crate::error::AirTrayError;
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager"
+)]
+trait NetworkManager {
+    #[zbus(property)]
+    fn primary_connection(&self) -> zbus::Result<OwnedObjectPath>;
+
+    /// Overall connectivity, see [`CONNECTED_LOCAL`].
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<u32>;
+}
+
+/// `NM_STATE_CONNECTED_LOCAL`: connected, maybe without internet access,
+/// which is all a receiver on the local network needs. Higher states add
+/// site-wide and internet access.
+const CONNECTED_LOCAL: u32 = 50;
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.Connection.Active",
+    default_service = "org.freedesktop.NetworkManager"
+)]
+trait ActiveConnection {
+    /// The connection's name, e.g. the Wi-Fi network name by default.
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+}
+
+/// Name of the connection at `path`, or `None` when offline ("/").
+async fn connection_id(
+    connection: &Connection,
+    path: OwnedObjectPath,
+) -> Result<Option<String>, AirTrayError> {
+    if path.as_str() == "/" {
+        return Ok(None);
+    }
+
+    let active = ActiveConnectionProxy::builder(connection)
+        .path(path)?
+        .build()
+        .await?;
+    Ok(Some(active.id().await?))
+}
+
+/// Yields the id of the primary connection now and whenever it changes.
+pub fn watch() -> impl Stream<Item = Option<String>> {
+    cosmic::iced::stream::channel(4, |mut output| async move {
+        let result: Result<(), AirTrayError> = async {
+            let connection = Connection::system().await?;
+            let manager = NetworkManagerProxy::new(&connection).await?;
+            let mut changes = manager.receive_primary_connection_changed().await;
+
+            // The first change is the current value
+            while let Some(change) = changes.next().await {
+                let id = connection_id(&connection, change.get().await?).await?;
+                let _ = output.send(id).await;
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            eprintln!("Failed to follow network changes: {}", e);
+        }
+        std::future::pending::<()>().await;
+    })
+}
+
+/// Waits up to `timeout` for a network connection. Also true when
+/// NetworkManager can't be asked, so the caller goes ahead as it would
+/// without it.
+pub async fn wait_online(timeout: Duration) -> bool {
+    let result: Result<bool, AirTrayError> = async {
+        let connection = Connection::system().await?;
+        let manager = NetworkManagerProxy::new(&connection).await?;
+        let mut changes = manager.receive_state_changed().await;
+
+        // The first change is the current value
+        let online = async {
+            while let Some(change) = changes.next().await {
+                if change.get().await? >= CONNECTED_LOCAL {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        };
+        tokio::time::timeout(timeout, online).await.unwrap_or(Ok(false))
+    }
+    .await;
+
+    result.unwrap_or_else(|e| {
+        eprintln!("Failed to check the network state: {}", e);
+        true
+    })
+}
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Desktop notifications through the freedesktop notification service.
+
+use std::collections::HashMap;
+
+use cosmic::iced::futures::{SinkExt, Stream, StreamExt};
+use zbus::zvariant::Value;
+use zbus::{proxy, Connection};
+
+use crate::error::AirTrayError;
+
+#[proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn notification_closed(&self, id: u32, reason: u32) -> zbus::Result<()>;
+}
+
+/// Shows a notification, or updates the one with id `replaces_id` if it is
+/// not 0, returning its id. Clicks on the `actions` buttons, pairs of key and
+/// label, arrive through [`invoked`].
+pub async fn notify(
+    summary: String,
+    body: String,
+    replaces_id: u32,
+    actions: Vec<(String, String)>,
+) -> Result<u32, AirTrayError> {
+    let actions: Vec<&str> = actions
+        .iter()
+        .flat_map(|(key, label)| [key.as_str(), label.as_str()])
+        .collect();
+    let connection = Connection::session().await?;
+    let id = NotificationsProxy::new(&connection)
+        .await?
+        .notify(
+            "AirTray",
+            replaces_id,
+            "com.github.introini.airtray",
+            &summary,
+            &body,
+            &actions,
+            HashMap::new(),
+            // The server's default
+            -1,
+        )
+        .await?;
+    Ok(id)
+}
+
+/// Shows a notification with buttons and waits for one to be clicked.
+/// `actions` pairs a key with the button's label; the key of the clicked one
+/// is returned, or `None` if it was dismissed.
+pub async fn ask(
+    summary: String,
+    body: String,
+    actions: Vec<(&'static str, String)>,
+) -> Result<Option<String>, AirTrayError> {
+    let connection = Connection::session().await?;
+    let proxy = NotificationsProxy::new(&connection).await?;
+    // Subscribed before showing it, so a quick click isn't missed
+    let mut invoked = proxy.receive_action_invoked().await?;
+    let mut closed = proxy.receive_notification_closed().await?;
+
+    let actions: Vec<&str> = actions
+        .iter()
+        .flat_map(|(key, label)| [*key, label.as_str()])
+        .collect();
+    let id = proxy
+        .notify(
+            "AirTray",
+            0,
+            "com.github.introini.airtray",
+            &summary,
+            &body,
+            &actions,
+            HashMap::new(),
+            // Until acted upon
+            0,
+        )
+        .await?;
+
+    loop {
+        tokio::select! {
+            Some(signal) = invoked.next() => {
+                let args = signal.args()?;
+                if args.id == id {
+                    return Ok(Some(args.action_key.to_string()));
+                }
+            }
+            Some(signal) = closed.next() => {
+                if signal.args()?.id == id {
+                    return Ok(None);
+                }
+            }
+            else => return Ok(None),
+        }
+    }
+}
+
+/// Keys of the buttons clicked on any notification, including those of
+/// other applications and those waited on with [`ask`].
+pub fn invoked() -> impl Stream<Item = String> {
+    cosmic::iced::stream::channel(4, |mut output| async move {
+        let result: Result<(), AirTrayError> = async {
+            let connection = Connection::session().await?;
+            let proxy = NotificationsProxy::new(&connection).await?;
+            let mut invoked = proxy.receive_action_invoked().await?;
+
+            while let Some(signal) = invoked.next().await {
+                let _ = output.send(signal.args()?.action_key.to_string()).await;
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            eprintln!("Failed to follow notification buttons: {}", e);
+        }
+        std::future::pending::<()>().await;
+    })
+}
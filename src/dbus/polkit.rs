@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Asks polkit to authenticate the user before the settings are unlocked.
+
+use std::collections::HashMap;
+
+use zbus::zvariant::Value;
+use zbus::{proxy, Connection};
+
+use crate::error::AirTrayError;
+
+/// Action installed by `res/com.github.introini.airtray.policy`.
+const UNLOCK_ACTION: &str = "com.github.introini.airtray.unlock-settings";
+
+/// Lets polkit show its authentication dialog.
+const ALLOW_USER_INTERACTION: u32 = 1;
+
+#[proxy(
+    interface = "org.freedesktop.PolicyKit1.Authority",
+    default_service = "org.freedesktop.PolicyKit1",
+    default_path = "/org/freedesktop/PolicyKit1/Authority"
+)]
+trait Authority {
+    fn check_authorization(
+        &self,
+        subject: &(&str, HashMap<&str, Value<'_>>),
+        action_id: &str,
+        details: HashMap<&str, &str>,
+        flags: u32,
+        cancellation_id: &str,
+    ) -> zbus::Result<(bool, bool, HashMap<String, String>)>;
+}
+
+/// When this process started, in clock ticks since boot, which polkit uses
+/// to tell it apart from a later process with the same pid.
+fn start_time() -> Result<u64, AirTrayError> {
+    let stat = std::fs::read_to_string("/proc/self/stat")
+        .map_err(|e| AirTrayError::DbusError(e.to_string()))?;
+    // The command name may contain spaces, so count from the parenthesis closing it
+    stat.rsplit_once(')')
+        .and_then(|(_, fields)| fields.split_whitespace().nth(19))
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| AirTrayError::DbusError(String::from("unreadable /proc/self/stat")))
+}
+
+/// Authenticates the user for unlocking the settings, returning whether it
+/// succeeded. Resolves once the dialog is dealt with.
+pub async fn authorize_unlock() -> Result<bool, AirTrayError> {
+    let subject = HashMap::from([
+        ("pid", Value::from(std::process::id())),
+        ("start-time", Value::from(start_time()?)),
+    ]);
+
+    let connection = Connection::system().await?;
+    let (authorized, _, _) = AuthorityProxy::new(&connection)
+        .await?
+        .check_authorization(
+            &("unix-process", subject),
+            UNLOCK_ACTION,
+            HashMap::new(),
+            ALLOW_USER_INTERACTION,
+            "",
+        )
+        .await?;
+    Ok(authorized)
+}
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Requests the performance power profile from power-profiles-daemon while a
+//! sender is connected.
+
+use std::convert::Infallible;
+
+use cosmic::iced::futures::Stream;
+use zbus::{proxy, Connection};
+
+use crate::error::AirTrayError;
+
+#[proxy(
+    interface = "org.freedesktop.UPower.PowerProfiles",
+    default_service = "org.freedesktop.UPower.PowerProfiles",
+    default_path = "/org/freedesktop/UPower/PowerProfiles"
+)]
+trait PowerProfiles {
+    fn hold_profile(&self, profile: &str, reason: &str, application_id: &str)
+        -> zbus::Result<u32>;
+}
+
+/// Holds the performance profile for as long as the stream is polled. The
+/// daemon releases the hold when the connection closes, so nothing has to be
+/// undone when the stream is dropped: the daemon goes back to the profile
+/// that was active before, unless the user picked another one meanwhile.
+pub fn hold_performance(application_id: &'static str) -> impl Stream<Item = Infallible> {
+    cosmic::iced::stream::channel(1, move |_output| async move {
+        let result: Result<Connection, AirTrayError> = async {
+            let connection = Connection::system().await?;
+            PowerProfilesProxy::new(&connection)
+                .await?
+                .hold_profile("performance", "AirPlay session", application_id)
+                .await?;
+            Ok(connection)
+        }
+        .await;
+
+        match result {
+            Ok(_connection) => std::future::pending::<()>().await,
+            Err(e) => eprintln!("Failed to request the performance power profile: {}", e),
+        }
+    })
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The applet's own D-Bus interface, so other programs (a KDE Connect run
+//! command, a keyboard shortcut, `airtray --toggle`) can control the receiver
+//! and open the popup, bar modules (`airtray watch`) can follow its state and
+//! `airtray config` can change settings, e.g. over SSH.
+
+use std::sync::LazyLock;
+
+use cosmic::cosmic_config::{self, CosmicConfigEntry};
+use cosmic::iced::futures::channel::mpsc;
+use cosmic::iced::futures::{SinkExt, Stream};
+use tokio::sync::watch;
+use zbus::{fdo, interface, proxy};
+
+use crate::config::{keys, Config, LockMethod};
+use crate::dbus::polkit;
+use crate::debug;
+use crate::error::AirTrayError;
+
+/// Well-known name the applet owns on the session bus, its app id.
+pub const BUS_NAME: &str = "com.github.introini.airtray";
+/// Path of the control object.
+pub const OBJECT_PATH: &str = "/com/github/introini/airtray";
+
+/// Something another program asked the applet to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Request {
+    Start,
+    Stop,
+    Toggle,
+    /// Open the popup, e.g. from a keyboard shortcut.
+    ShowPopup,
+}
+
+/// The receiver state as exposed in the interface's properties.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Status {
+    /// "stopped", "standby", "starting", "running", "stopping" or "failed".
+    pub state: String,
+    /// Names of the connected senders.
+    pub clients: Vec<String>,
+    /// What the applet shows as its status line.
+    pub text: String,
+}
+
+static STATUS: LazyLock<watch::Sender<Status>> =
+    LazyLock::new(|| watch::Sender::new(Status::default()));
+
+/// Exposes `status`, notifying watchers if it changed.
+pub fn publish(status: Status) {
+    STATUS.send_if_modified(|current| {
+        let changed = *current != status;
+        *current = status;
+        changed
+    });
+}
+
+/// The status last published.
+pub fn current() -> Status {
+    STATUS.borrow().clone()
+}
+
+struct Service {
+    requests: mpsc::Sender<Request>,
+    status: Status,
+}
+
+impl Service {
+    async fn send(&self, request: Request) {
+        let _ = self.requests.clone().send(request).await;
+    }
+}
+
+/// The stored settings and their store, which the applet watches, so what
+/// is written there takes effect right away.
+fn settings() -> Result<(cosmic_config::Config, Config), AirTrayError> {
+    let handler = cosmic_config::Config::new(BUS_NAME, Config::VERSION)?;
+    let config = Config::get_entry(&handler).unwrap_or_else(|(_, config)| config);
+    Ok((handler, config))
+}
+
+#[interface(name = "com.github.introini.AirTray")]
+impl Service {
+    /// Turns the receiver on.
+    async fn start(&self) {
+        self.send(Request::Start).await;
+    }
+
+    /// Turns the receiver off.
+    async fn stop(&self) {
+        self.send(Request::Stop).await;
+    }
+
+    /// Turns the receiver on if it is off, and off otherwise.
+    async fn toggle(&self) {
+        self.send(Request::Toggle).await;
+    }
+
+    /// Opens the popup, or the window outside the panel, if it isn't open.
+    async fn show_popup(&self) {
+        self.send(Request::ShowPopup).await;
+    }
+
+    /// A setting by its dotted key, e.g. "video.fps", as JSON. An empty key
+    /// gives all settings. PINs, passwords and tokens are redacted, as in
+    /// debug bundles.
+    fn get_setting(&self, key: &str) -> fdo::Result<String> {
+        let (_, config) = settings().map_err(|e| fdo::Error::Failed(e.to_string()))?;
+        let value = keys::get(&debug::redact(&config), key)
+            .map_err(|e| fdo::Error::InvalidArgs(e.to_string()))?;
+        Ok(value.to_string())
+    }
+
+    /// Changes the setting at `key` to `value`, JSON or plain text. While
+    /// the settings are locked, this takes the authentication that unlocks
+    /// them in the popup, so the lock can't be undone from outside.
+    async fn set_setting(&self, key: &str, value: &str) -> fdo::Result<()> {
+        let (handler, config) = settings().map_err(|e| fdo::Error::Failed(e.to_string()))?;
+        let changed =
+            keys::set(&config, key, value).map_err(|e| fdo::Error::InvalidArgs(e.to_string()))?;
+
+        if config.lock_method() != LockMethod::None {
+            let authorized = polkit::authorize_unlock()
+                .await
+                .map_err(|e| fdo::Error::Failed(e.to_string()))?;
+            if !authorized {
+                return Err(fdo::Error::AccessDenied(String::from("the settings are locked")));
+            }
+        }
+        changed
+            .write_entry(&handler)
+            .map_err(|e| fdo::Error::Failed(AirTrayError::from(e).to_string()))
+    }
+
+    #[zbus(property)]
+    fn state(&self) -> &str {
+        &self.status.state
+    }
+
+    #[zbus(property)]
+    fn clients(&self) -> Vec<String> {
+        self.status.clients.clone()
+    }
+
+    #[zbus(property)]
+    fn status_text(&self) -> &str {
+        &self.status.text
+    }
+}
+
+/// Client side of the control interface, used by the command line.
+#[proxy(
+    interface = "com.github.introini.AirTray",
+    default_service = "com.github.introini.airtray",
+    default_path = "/com/github/introini/airtray"
+)]
+pub trait Control {
+    fn start(&self) -> zbus::Result<()>;
+    fn stop(&self) -> zbus::Result<()>;
+    fn toggle(&self) -> zbus::Result<()>;
+    fn show_popup(&self) -> zbus::Result<()>;
+    fn get_setting(&self, key: &str) -> zbus::Result<String>;
+    fn set_setting(&self, key: &str, value: &str) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn state(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn clients(&self) -> zbus::Result<Vec<String>>;
+    #[zbus(property)]
+    fn status_text(&self) -> zbus::Result<String>;
+}
+
+/// Serves the control interface for as long as the stream is polled,
+/// yielding the requests it receives.
+pub fn serve() -> impl Stream<Item = Request> {
+    cosmic::iced::stream::channel(16, |requests| async move {
+        let mut status = STATUS.subscribe();
+        let service = Service {
+            requests,
+            status: status.borrow_and_update().clone(),
+        };
+        let connection = zbus::connection::Builder::session()
+            .and_then(|builder| builder.name(BUS_NAME))
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, service));
+
+        let connection = match connection {
+            Ok(builder) => match builder.build().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    eprintln!("Failed to register {} on the session bus: {}", BUS_NAME, e);
+                    return;
+                }
+            },
+            Err(e) => {
+                eprintln!("Failed to set up the D-Bus service: {}", e);
+                return;
+            }
+        };
+
+        // Requests are delivered by the connection's executor until we are
+        // dropped, while the properties follow the published status
+        let service = match connection
+            .object_server()
+            .interface::<_, Service>(OBJECT_PATH)
+            .await
+        {
+            Ok(service) => service,
+            Err(e) => {
+                eprintln!("Failed to set up the D-Bus service: {}", e);
+                return;
+            }
+        };
+        while status.changed().await.is_ok() {
+            service.get_mut().await.status = status.borrow_and_update().clone();
+            let context = service.signal_context();
+            let current = service.get().await;
+            let _ = current.state_changed(context).await;
+            let _ = current.clients_changed(context).await;
+            let _ = current.status_text_changed(context).await;
+        }
+    })
+}
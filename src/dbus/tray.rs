@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! A StatusNotifierItem, the tray icon of KDE, GNOME with the AppIndicator
+//! extension, Waybar and others, for desktops without the COSMIC panel.
+//! Clicking it shows the controls in a window, a middle click turns the
+//! receiver on or off.
+
+use std::sync::LazyLock;
+
+use cosmic::iced::futures::channel::mpsc;
+use cosmic::iced::futures::{SinkExt, Stream};
+use tokio::sync::watch;
+use zbus::object_server::SignalContext;
+use zbus::zvariant::ObjectPath;
+use zbus::{interface, proxy};
+
+/// Path trays look for the item at.
+const OBJECT_PATH: &str = "/StatusNotifierItem";
+/// Icon of the item, the same as the panel button's.
+const ICON: &str = "com.github.introini.airtray";
+
+/// What the tray icon shows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrayState {
+    /// A sender is streaming, which trays may highlight.
+    pub streaming: bool,
+    /// Status line of the tooltip.
+    pub status: String,
+}
+
+static STATE: LazyLock<watch::Sender<TrayState>> =
+    LazyLock::new(|| watch::Sender::new(TrayState::default()));
+
+/// Has the tray icon show `state`.
+pub fn show(state: TrayState) {
+    STATE.send_if_modified(|current| {
+        let changed = *current != state;
+        *current = state;
+        changed
+    });
+}
+
+/// A click on the tray icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayEvent {
+    /// Show or hide the controls.
+    Activate,
+    /// Turn the receiver on or off.
+    SecondaryActivate,
+}
+
+#[proxy(
+    interface = "org.kde.StatusNotifierWatcher",
+    default_service = "org.kde.StatusNotifierWatcher",
+    default_path = "/StatusNotifierWatcher"
+)]
+trait Watcher {
+    fn register_status_notifier_item(&self, service: &str) -> zbus::Result<()>;
+}
+
+struct Item {
+    events: mpsc::Sender<TrayEvent>,
+    state: TrayState,
+}
+
+impl Item {
+    async fn send(&self, event: TrayEvent) {
+        let _ = self.events.clone().send(event).await;
+    }
+}
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl Item {
+    async fn activate(&self, _x: i32, _y: i32) {
+        self.send(TrayEvent::Activate).await;
+    }
+
+    async fn secondary_activate(&self, _x: i32, _y: i32) {
+        self.send(TrayEvent::SecondaryActivate).await;
+    }
+
+    /// There is no menu, so this shows the controls too.
+    async fn context_menu(&self, _x: i32, _y: i32) {
+        self.send(TrayEvent::Activate).await;
+    }
+
+    async fn scroll(&self, _delta: i32, _orientation: &str) {}
+
+    #[zbus(property)]
+    fn category(&self) -> &str {
+        "ApplicationStatus"
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> &str {
+        "airtray"
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> &str {
+        "AirTray"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        if self.state.streaming {
+            "NeedsAttention"
+        } else {
+            "Active"
+        }
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> &str {
+        ICON
+    }
+
+    #[zbus(property)]
+    fn attention_icon_name(&self) -> &str {
+        ICON
+    }
+
+    /// Icon name, pixmaps, title and text.
+    #[zbus(property)]
+    fn tool_tip(&self) -> (String, Vec<(i32, i32, Vec<u8>)>, String, String) {
+        (
+            ICON.to_string(),
+            Vec::new(),
+            String::from("AirTray"),
+            self.state.status.clone(),
+        )
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn menu(&self) -> ObjectPath<'static> {
+        ObjectPath::from_static_str_unchecked("/NO_DBUSMENU")
+    }
+
+    #[zbus(signal)]
+    async fn new_status(ctxt: &SignalContext<'_>, status: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_tool_tip(ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+}
+
+/// Shows the tray icon for as long as the stream is polled, yielding the
+/// clicks on it.
+pub fn serve() -> impl Stream<Item = TrayEvent> {
+    cosmic::iced::stream::channel(16, |events| async move {
+        if let Err(e) = run(events).await {
+            eprintln!("Failed to show the tray icon: {}", e);
+        }
+    })
+}
+
+async fn run(events: mpsc::Sender<TrayEvent>) -> zbus::Result<()> {
+    let mut state = STATE.subscribe();
+    let item = Item {
+        events,
+        state: state.borrow_and_update().clone(),
+    };
+    let name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+    let connection = zbus::connection::Builder::session()?
+        .name(name.clone())?
+        .serve_at(OBJECT_PATH, item)?
+        .build()
+        .await?;
+    WatcherProxy::new(&connection)
+        .await?
+        .register_status_notifier_item(&name)
+        .await?;
+
+    let item = connection
+        .object_server()
+        .interface::<_, Item>(OBJECT_PATH)
+        .await?;
+    while state.changed().await.is_ok() {
+        item.get_mut().await.state = state.borrow_and_update().clone();
+        let status = item.get().await.status().to_string();
+        Item::new_status(item.signal_context(), &status).await?;
+        Item::new_tool_tip(item.signal_context()).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Debug bundles for bug reports: a zip with the settings, recent logs and
+//! what the applet depends on, saved to the Downloads folder.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+use chrono::Local;
+use zip::write::SimpleFileOptions;
+
+use crate::backend;
+use crate::config::Config;
+use crate::error::AirTrayError;
+use crate::host;
+
+/// Replaces secrets in the bundled settings.
+const REDACTED: &str = "<redacted>";
+
+/// Log lines of the applet and UXPlay included, newest last.
+const LOG_LINES: &str = "2000";
+
+/// Programs besides UXPlay whose presence and version matter for bug reports.
+const PROGRAMS: [(&str, &str); 3] = [
+    ("pactl", "--version"),
+    ("gst-inspect-1.0", "--version"),
+    ("avahi-daemon", "--version"),
+];
+
+/// `config` without passwords, tokens and PINs.
+pub fn redact(config: &Config) -> Config {
+    let mut config = config.clone();
+    let redact = |secret: &mut String| {
+        if !secret.is_empty() {
+            *secret = String::from(REDACTED);
+        }
+    };
+    redact(&mut config.integrations.mqtt.password);
+    redact(&mut config.companion.token);
+    redact(&mut config.integrations.rest.token);
+    redact(&mut config.lock.pin);
+    for profile in &mut config.profiles {
+        redact(&mut profile.pin);
+    }
+    config
+}
+
+/// Output of running `program` with `args`, or why it couldn't run.
+fn run(program: &str, args: &[&str]) -> String {
+    output(program, args, host::command(program))
+}
+
+/// Output of `command`, shown as `program` run with `args`.
+fn output(program: &str, args: &[&str], mut command: Command) -> String {
+    match command.args(args).output() {
+        Ok(output) => format!(
+            "$ {} {}\n{}{}\n",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        Err(e) => format!("$ {} {}\nfailed: {}\n", program, args.join(" "), e),
+    }
+}
+
+/// Versions of the applet, the system and the programs it runs.
+fn versions(config: &Config) -> String {
+    let mut text = format!(
+        "AirTray {}{}\n",
+        env!("CARGO_PKG_VERSION"),
+        if host::in_flatpak() { " (Flatpak)" } else { "" }
+    );
+    text.push_str(&run("uname", &["-srmo"]));
+    text.push_str(&std::fs::read_to_string("/etc/os-release").unwrap_or_default());
+    let uxplay = backend::launch(&config.launch, std::iter::empty::<(&str, &str)>());
+    text.push_str(&output(&backend::resolve(&config.launch), &["-v"], uxplay));
+    for (program, arg) in PROGRAMS {
+        text.push_str(&run(program, &[arg]));
+    }
+    text
+}
+
+/// Checks of what the receiver needs at runtime.
+fn diagnostics(config: &Config) -> String {
+    let mut text = format!("UXPlay: {}\n", backend::resolve(&config.launch));
+    text.push_str(&run("pactl", &["info"]));
+    text.push_str(&run("gst-inspect-1.0", &["avdec_h264"]));
+    text.push_str(&run("avahi-browse", &["-tpr", "_airplay._tcp"]));
+    for var in ["XDG_CURRENT_DESKTOP", "XDG_SESSION_TYPE", "WAYLAND_DISPLAY", "DISPLAY"] {
+        text.push_str(&format!("{}={}\n", var, std::env::var(var).unwrap_or_default()));
+    }
+    text
+}
+
+/// Recent journal entries of the applet and of UXPlay, which the panel
+/// both runs under the user's session.
+fn logs() -> String {
+    run(
+        "journalctl",
+        &["--user", "-b", "--no-pager", "-n", LOG_LINES, "_COMM=airtray", "+", "_COMM=uxplay"],
+    )
+}
+
+/// The settings as bundled, without secrets.
+pub fn settings(config: &Config) -> String {
+    format!("{:#?}\n", redact(config))
+}
+
+fn write_bundle(path: &PathBuf, config: &Config) -> zip::result::ZipResult<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    let files = [
+        ("config.txt", settings(config)),
+        ("versions.txt", versions(config)),
+        ("diagnostics.txt", diagnostics(config)),
+        ("log.txt", logs()),
+    ];
+    for (name, contents) in files {
+        zip.start_file(name, SimpleFileOptions::default())?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Writes a debug bundle to the Downloads folder, returning its path.
+/// Runs the diagnostic programs, so it is kept off the async worker threads.
+pub async fn save(config: Config) -> Result<PathBuf, AirTrayError> {
+    let name = format!("airtray-debug-{}.zip", Local::now().format("%Y%m%d-%H%M%S"));
+    let path = config.companion.download_dir().join(name);
+    tokio::task::spawn_blocking(move || {
+        write_bundle(&path, &config)
+            .map(|()| path)
+            .map_err(|e| AirTrayError::DebugBundleError(e.to_string()))
+    })
+    .await
+    .map_err(|e| AirTrayError::DebugBundleError(e.to_string()))?
+}
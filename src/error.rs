@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use crate::fl;
+
+/// Errors surfaced by the backend, audio, history, config, D-Bus and remote
+/// control modules.
+///
+/// `Display` is meant for logs; use [`AirTrayError::user_message`] for text
+/// shown in the popup.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AirTrayError {
+    /// A required program is not installed.
+    #[error("{binary} was not found in PATH")]
+    BinaryNotFound { binary: String },
+    /// A program exists but could not be launched.
+    #[error("failed to start {binary}: {reason}")]
+    SpawnFailed { binary: String, reason: String },
+    /// The receiver exited without being asked to, with the last lines it
+    /// wrote to stderr.
+    #[error("receiver exited unexpectedly with code {code:?}")]
+    ProcessExited { code: Option<i32>, stderr: Vec<String> },
+    /// A port the receiver needs is taken by another program, named if it
+    /// could be found.
+    #[error("{protocol} port {port} is already in use")]
+    PortInUse {
+        protocol: String,
+        port: u16,
+        process: Option<String>,
+    },
+    /// A port the receiver needs is taken by another user's program,
+    /// usually their AirTray in another login session.
+    #[error("{protocol} port {port} is already in use by user {user}")]
+    PortInUseByUser {
+        protocol: String,
+        port: u16,
+        user: String,
+    },
+    /// The sound server rejected a request.
+    #[error("audio setup failed: {0}")]
+    AudioError(String),
+    /// The session history could not be read or written.
+    #[error("history error: {0}")]
+    HistoryError(String),
+    /// Settings could not be read or written.
+    #[error("settings error: {0}")]
+    ConfigError(String),
+    /// Another desktop service could not be reached over D-Bus.
+    #[error("D-Bus call failed: {0}")]
+    DbusError(String),
+    /// A control command could not be delivered to the sender.
+    #[error("remote control failed: {0}")]
+    ControlError(String),
+    /// A debug bundle could not be written.
+    #[error("debug bundle failed: {0}")]
+    DebugBundleError(String),
+    /// UXPlay's startup file could not be read or written.
+    #[error("uxplayrc failed: {0}")]
+    UxplayrcError(String),
+    /// The receiver's keys could not be backed up or restored.
+    #[error("receiver keys: {0}")]
+    IdentityError(String),
+    /// The webhook could not be called.
+    #[error("webhook failed: {0}")]
+    WebhookError(String),
+    /// No token could be made for an endpoint, so it isn't served.
+    #[error("no access token: {0}")]
+    TokenError(String),
+}
+
+impl AirTrayError {
+    /// Maps a failed launch of `binary` to the matching variant.
+    pub fn spawn(binary: &str, error: std::io::Error) -> Self {
+        if error.kind() == std::io::ErrorKind::NotFound {
+            Self::BinaryNotFound {
+                binary: binary.to_string(),
+            }
+        } else {
+            Self::SpawnFailed {
+                binary: binary.to_string(),
+                reason: error.to_string(),
+            }
+        }
+    }
+
+    /// Localized, user-facing description.
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::BinaryNotFound { binary } => {
+                fl!("error-binary-not-found", binary = binary.as_str())
+            }
+            Self::SpawnFailed { binary, reason } => fl!(
+                "error-spawn-failed",
+                binary = binary.as_str(),
+                reason = reason.as_str()
+            ),
+            Self::ProcessExited { code: Some(code), .. } => {
+                fl!("error-process-exited-code", code = *code)
+            }
+            Self::ProcessExited { code: None, .. } => fl!("error-process-exited"),
+            Self::PortInUse {
+                protocol,
+                port,
+                process: Some(process),
+            } => fl!(
+                "error-port-in-use-by",
+                protocol = protocol.as_str(),
+                port = *port,
+                process = process.as_str()
+            ),
+            Self::PortInUse {
+                protocol,
+                port,
+                process: None,
+            } => fl!("error-port-in-use", protocol = protocol.as_str(), port = *port),
+            Self::PortInUseByUser {
+                protocol,
+                port,
+                user,
+            } => fl!(
+                "error-port-in-use-by-user",
+                protocol = protocol.as_str(),
+                port = *port,
+                user = user.as_str()
+            ),
+            Self::AudioError(reason) => fl!("error-audio", reason = reason.as_str()),
+            Self::HistoryError(reason) => fl!("error-history", reason = reason.as_str()),
+            Self::ConfigError(reason) => fl!("error-config", reason = reason.as_str()),
+            Self::DbusError(reason) => fl!("error-dbus", reason = reason.as_str()),
+            Self::ControlError(reason) => fl!("error-control", reason = reason.as_str()),
+            Self::DebugBundleError(reason) => fl!("error-debug-bundle", reason = reason.as_str()),
+            Self::UxplayrcError(reason) => fl!("error-uxplayrc", reason = reason.as_str()),
+            Self::IdentityError(reason) => fl!("error-identity", reason = reason.as_str()),
+            Self::WebhookError(reason) => fl!("error-webhook", reason = reason.as_str()),
+            Self::TokenError(reason) => fl!("error-token", reason = reason.as_str()),
+        }
+    }
+}
+
+impl From<cosmic::cosmic_config::Error> for AirTrayError {
+    fn from(error: cosmic::cosmic_config::Error) -> Self {
+        Self::ConfigError(error.to_string())
+    }
+}
@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Decoded video frames streamed from UXPlay to the applet, for mirror views
+//! the applet draws itself.
+//!
+//! UXPlay's video sink encodes every frame as a binary PPM image and pushes it
+//! over a local TCP connection, so each frame starts with a small header
+//! giving its size and needs no other framing.
+
+use std::io;
+
+use cosmic::iced::futures::{SinkExt, Stream};
+use tokio::io::{AsyncBufRead, AsyncReadExt, BufReader};
+use tokio::net::TcpListener;
+
+/// Local port the frames are pushed to.
+const PORT: u16 = 7079;
+
+/// Largest frame accepted, in pixels.
+const MAX_PIXELS: usize = 7680 * 4320;
+
+/// Size of a frame in pixels.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct FrameSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A decoded frame, as RGBA pixels.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub size: FrameSize,
+    pub pixels: Vec<u8>,
+}
+
+/// Builds the GStreamer video sink handed to UXPlay (`-vs`) that sends the
+/// frames to [`receive`]. Frames are scaled to `size`, letterboxed to keep
+/// the aspect ratio, unless `integer` scaling is on: the view then enlarges
+/// them from their source size itself.
+pub fn video_sink(size: FrameSize, integer: bool) -> String {
+    let scale = if integer {
+        String::new()
+    } else {
+        format!(
+            "videoscale add-borders=true ! \
+             video/x-raw,width={},height={},pixel-aspect-ratio=1/1 ! ",
+            size.width, size.height
+        )
+    };
+    format!(
+        "{scale}videoconvert ! video/x-raw,format=RGB ! pnmenc ! \
+         tcpclientsink host=127.0.0.1 port={PORT}"
+    )
+}
+
+/// Accepts frames for as long as the stream is polled. Has to be running
+/// before UXPlay starts the video pipeline.
+pub fn receive() -> impl Stream<Item = Frame> {
+    cosmic::iced::stream::channel(1, move |mut output| async move {
+        let listener = match TcpListener::bind(("127.0.0.1", PORT)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to listen for video frames on port {}: {}", PORT, e);
+                return;
+            }
+        };
+
+        let mut rgb = Vec::new();
+        loop {
+            let mut stream = match listener.accept().await {
+                Ok((stream, _)) => BufReader::new(stream),
+                Err(e) => {
+                    eprintln!("Failed to accept video connection: {}", e);
+                    continue;
+                }
+            };
+
+            // One connection per mirroring session
+            loop {
+                let size = match read_header(&mut stream).await {
+                    Ok(size) => size,
+                    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                    Err(e) => {
+                        eprintln!("Failed to read video frame: {}", e);
+                        break;
+                    }
+                };
+                rgb.resize(size.width as usize * size.height as usize * 3, 0);
+                if stream.read_exact(&mut rgb).await.is_err() {
+                    break;
+                }
+
+                let frame = Frame {
+                    size,
+                    pixels: rgb
+                        .chunks_exact(3)
+                        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], u8::MAX])
+                        .collect(),
+                };
+                if output.send(frame).await.is_err() {
+                    return;
+                }
+            }
+        }
+    })
+}
+
+/// Reads the header of the next frame: `P6`, the width, the height and the
+/// largest value, each followed by one whitespace byte.
+async fn read_header(stream: &mut (impl AsyncBufRead + Unpin)) -> io::Result<FrameSize> {
+    let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidData, what.to_string());
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    while fields.len() < 4 {
+        let byte = stream.read_u8().await?;
+        if !byte.is_ascii_whitespace() {
+            if field.len() >= 10 {
+                return Err(invalid("header field too long"));
+            }
+            field.push(char::from(byte));
+        } else if !field.is_empty() {
+            fields.push(std::mem::take(&mut field));
+        }
+    }
+
+    if fields[0] != "P6" || fields[3] != "255" {
+        return Err(invalid("not an 8-bit RGB frame"));
+    }
+    let (Ok(width), Ok(height)) = (fields[1].parse::<u32>(), fields[2].parse::<u32>()) else {
+        return Err(invalid("invalid frame size"));
+    };
+    if width == 0 || height == 0 || width as usize * height as usize > MAX_PIXELS {
+        return Err(invalid("invalid frame size"));
+    }
+    Ok(FrameSize { width, height })
+}
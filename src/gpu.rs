@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Picks the GPU UXPlay decodes and draws the mirror with, for laptops with
+//! integrated and discrete graphics.
+
+use std::path::Path;
+
+/// A GPU with a render node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpu {
+    /// Render node name, e.g. "renderD129".
+    pub node: String,
+    /// Vendor and driver, e.g. "NVIDIA (nvidia)".
+    pub name: String,
+    /// PCI address, e.g. "0000:01:00.0".
+    slot: String,
+    vendor: Vendor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Vendor {
+    Intel,
+    Amd,
+    Nvidia,
+    Other,
+}
+
+impl Vendor {
+    fn from_id(id: &str) -> Self {
+        match id.trim() {
+            "0x8086" => Self::Intel,
+            "0x1002" => Self::Amd,
+            "0x10de" => Self::Nvidia,
+            _ => Self::Other,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Intel => "Intel",
+            Self::Amd => "AMD",
+            Self::Nvidia => "NVIDIA",
+            Self::Other => "GPU",
+        }
+    }
+}
+
+/// The GPUs with a render node, in node order.
+pub fn gpus() -> Vec<Gpu> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+
+    let mut gpus: Vec<Gpu> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let node = entry.file_name().to_string_lossy().into_owned();
+            if !node.starts_with("renderD") {
+                return None;
+            }
+            let device = entry.path().join("device");
+            let vendor = Vendor::from_id(&std::fs::read_to_string(device.join("vendor")).ok()?);
+            let slot = uevent_value(&device, "PCI_SLOT_NAME")?;
+            let driver = uevent_value(&device, "DRIVER").unwrap_or_default();
+            Some(Gpu {
+                name: format!("{} ({})", vendor.name(), driver),
+                node,
+                slot,
+                vendor,
+            })
+        })
+        .collect();
+    gpus.sort_by(|a, b| a.node.cmp(&b.node));
+    gpus
+}
+
+/// A `KEY=value` entry of a device's uevent file.
+fn uevent_value(device: &Path, key: &str) -> Option<String> {
+    std::fs::read_to_string(device.join("uevent"))
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+        .map(String::from)
+}
+
+/// Environment steering GStreamer's VA-API decoders, Mesa and the NVIDIA
+/// driver to the GPU with render node `node`. Empty for the default GPU.
+pub fn env(node: &str) -> Vec<(&'static str, String)> {
+    let Some(gpu) = gpus().into_iter().find(|gpu| gpu.node == node) else {
+        return Vec::new();
+    };
+
+    let mut env = vec![
+        ("GST_VAAPI_DRM_DEVICE", format!("/dev/dri/{}", gpu.node)),
+        // Mesa wants the PCI address with underscores, e.g. "pci-0000_01_00_0"
+        ("DRI_PRIME", format!("pci-{}", gpu.slot.replace([':', '.'], "_"))),
+    ];
+    if gpu.vendor == Vendor::Nvidia {
+        env.push(("__NV_PRIME_RENDER_OFFLOAD", String::from("1")));
+        env.push(("__GLX_VENDOR_LIBRARY_NAME", String::from("nvidia")));
+    }
+    env
+}
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Tracks signs of a struggling connection during a session.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Only problems this recent count.
+const WINDOW: Duration = Duration::from_secs(30);
+/// Dropped-frame warnings within the window that make the stream poor.
+const MAX_DROPPED: usize = 3;
+/// Resent packets within the window that make the stream poor.
+const MAX_RESENT: usize = 20;
+
+/// Recent dropped frames and packet retransmissions.
+#[derive(Debug, Default)]
+pub struct StreamHealth {
+    dropped: VecDeque<Instant>,
+    resent: VecDeque<Instant>,
+}
+
+impl StreamHealth {
+    /// GStreamer reported dropping frames.
+    pub fn frames_dropped(&mut self) {
+        record(&mut self.dropped);
+    }
+
+    /// The receiver asked the sender to resend missing packets.
+    pub fn packets_resent(&mut self) {
+        record(&mut self.resent);
+    }
+
+    /// Dropped-frame warnings and resent packets within the window.
+    pub fn counts(&self) -> (usize, usize) {
+        (recent(&self.dropped), recent(&self.resent))
+    }
+
+    /// Whether the stream degraded enough to warn about.
+    pub fn is_poor(&self) -> bool {
+        let (dropped, resent) = self.counts();
+        dropped >= MAX_DROPPED || resent >= MAX_RESENT
+    }
+
+    /// Forgets everything, e.g. when the session ends.
+    pub fn clear(&mut self) {
+        self.dropped.clear();
+        self.resent.clear();
+    }
+}
+
+fn record(events: &mut VecDeque<Instant>) {
+    let now = Instant::now();
+    while events.front().is_some_and(|&time| now - time > WINDOW) {
+        events.pop_front();
+    }
+    events.push_back(now);
+}
+
+fn recent(events: &VecDeque<Instant>) -> usize {
+    events.iter().filter(|time| time.elapsed() <= WINDOW).count()
+}
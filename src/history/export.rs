@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The session history as CSV or JSON, e.g. for reports on how much a
+//! meeting room's screen is used.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use serde_json::{json, Value};
+
+use super::Session;
+use crate::error::AirTrayError;
+
+/// Columns of the CSV export, and keys of the JSON one.
+const COLUMNS: [&str; 7] = ["device_id", "device", "model", "start", "end", "seconds", "bytes"];
+
+/// A file format the history can be exported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Writes `sessions` as `format` to a dated file in `dir`, returning its
+/// path. Devices are named by their nicknames where they have one.
+pub fn export(
+    sessions: &[Session],
+    nicknames: &BTreeMap<String, String>,
+    format: ExportFormat,
+    dir: &Path,
+) -> Result<PathBuf, AirTrayError> {
+    let (extension, contents) = match format {
+        ExportFormat::Csv => ("csv", csv(sessions, nicknames)),
+        ExportFormat::Json => ("json", json(sessions, nicknames)),
+    };
+    let name = format!("airtray-history-{}.{}", Local::now().format("%Y%m%d"), extension);
+    let path = dir.join(name);
+    std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&path, contents))
+        .map_err(|e| AirTrayError::HistoryError(format!("{}: {}", path.display(), e)))?;
+    Ok(path)
+}
+
+/// The values of a session's row, in the order of [`COLUMNS`].
+fn row(session: &Session, nicknames: &BTreeMap<String, String>) -> [Value; 7] {
+    let device = nicknames.get(&session.device_id).unwrap_or(&session.device_name);
+    [
+        json!(session.device_id),
+        json!(device),
+        json!(session.model),
+        json!(session.start.to_rfc3339()),
+        json!(session.end.to_rfc3339()),
+        json!(session.duration().num_seconds()),
+        json!(session.bytes),
+    ]
+}
+
+/// One line per session under a header line, quoted where needed.
+fn csv(sessions: &[Session], nicknames: &BTreeMap<String, String>) -> String {
+    let mut text = COLUMNS.join(",");
+    text.push('\n');
+    for session in sessions {
+        let fields = row(session, nicknames).map(|value| match value {
+            Value::String(field) if field.contains([',', '"', '\n', '\r']) => {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            }
+            Value::String(field) => field,
+            value => value.to_string(),
+        });
+        text.push_str(&fields.join(","));
+        text.push('\n');
+    }
+    text
+}
+
+/// An array with an object per session.
+fn json(sessions: &[Session], nicknames: &BTreeMap<String, String>) -> String {
+    let sessions: Vec<Value> = sessions
+        .iter()
+        .map(|session| {
+            let fields = COLUMNS.iter().map(|column| column.to_string());
+            Value::Object(fields.zip(row(session, nicknames)).collect())
+        })
+        .collect();
+    format!("{:#}\n", Value::Array(sessions))
+}
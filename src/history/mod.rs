@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Record of past streaming sessions, kept as JSON lines in the user's data directory.
+
+mod export;
+mod stats;
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+pub use export::{export, ExportFormat};
+pub use stats::{last_week, Stats, WeekSummary};
+
+use crate::error::AirTrayError;
+
+/// One sender's connection, from connect to disconnect.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub device_id: String,
+    pub device_name: String,
+    pub model: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    /// Network traffic meanwhile, an estimate as everything else on the
+    /// network counts too. 0 for sessions recorded before it was.
+    #[serde(default)]
+    pub bytes: u64,
+}
+
+impl Session {
+    pub fn duration(&self) -> chrono::Duration {
+        self.end - self.start
+    }
+}
+
+/// A sender that connected at some point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownDevice {
+    pub id: String,
+    /// The name it used last.
+    pub name: String,
+    pub model: String,
+    pub last_seen: DateTime<Local>,
+}
+
+/// Every device in `sessions`, most recently seen first.
+pub fn known_devices(sessions: &[Session]) -> Vec<KnownDevice> {
+    let mut devices: Vec<KnownDevice> = Vec::new();
+    for session in sessions {
+        match devices.iter_mut().find(|device| device.id == session.device_id) {
+            Some(device) if device.last_seen <= session.end => {
+                device.name = session.device_name.clone();
+                device.model = session.model.clone();
+                device.last_seen = session.end;
+            }
+            Some(_) => {}
+            None => devices.push(KnownDevice {
+                id: session.device_id.clone(),
+                name: session.device_name.clone(),
+                model: session.model.clone(),
+                last_seen: session.end,
+            }),
+        }
+    }
+    devices.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    devices
+}
+
+/// The on-disk session log.
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        let dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".local/share"));
+        Self {
+            path: dir.join("airtray").join("history.jsonl"),
+        }
+    }
+}
+
+impl History {
+    /// A history stored at `path` instead of the data directory.
+    #[cfg(test)]
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Reads all recorded sessions, oldest first. Unreadable lines are skipped.
+    pub fn load(&self) -> Result<Vec<Session>, AirTrayError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AirTrayError::HistoryError(e.to_string())),
+        };
+
+        Ok(contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Removes every session of the device with `device_id`.
+    pub fn forget(&self, device_id: &str) -> Result<(), AirTrayError> {
+        let sessions = self.load()?;
+        let write = || -> std::io::Result<()> {
+            let mut contents = String::new();
+            for session in sessions.iter().filter(|session| session.device_id != device_id) {
+                contents.push_str(&serde_json::to_string(session)?);
+                contents.push('\n');
+            }
+            fs::write(&self.path, contents)
+        };
+
+        write().map_err(|e| AirTrayError::HistoryError(e.to_string()))
+    }
+
+    /// Monday of the week the weekly summary was last sent in.
+    pub fn summarized(&self) -> Option<NaiveDate> {
+        let text = fs::read_to_string(self.summary_path()).ok()?;
+        text.trim().parse().ok()
+    }
+
+    /// Records that the weekly summary was sent in the week starting on
+    /// `monday`, so it isn't sent again.
+    pub fn set_summarized(&self, monday: NaiveDate) -> Result<(), AirTrayError> {
+        fs::write(self.summary_path(), format!("{monday}\n"))
+            .map_err(|e| AirTrayError::HistoryError(e.to_string()))
+    }
+
+    fn summary_path(&self) -> PathBuf {
+        self.path.with_file_name("weekly-summary")
+    }
+
+    /// Appends a finished session.
+    pub fn append(&self, session: &Session) -> Result<(), AirTrayError> {
+        let write = || -> std::io::Result<()> {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            let line = serde_json::to_string(session)?;
+            writeln!(file, "{line}")
+        };
+
+        write().map_err(|e| AirTrayError::HistoryError(e.to_string()))
+    }
+}
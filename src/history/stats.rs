@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone};
+
+use super::Session;
+
+/// Sessions listed on the statistics page.
+const RECENT: usize = 10;
+
+/// Devices named in the weekly summary.
+const SUMMARY_DEVICES: usize = 3;
+
+/// Totals shown on the statistics page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Time streamed since Monday 00:00.
+    pub this_week: Duration,
+    /// Number of recorded sessions.
+    pub sessions: usize,
+    /// Mean session length, if there are any sessions.
+    pub average: Option<Duration>,
+    /// Device with the most sessions and its session count.
+    pub top_device: Option<(String, usize)>,
+    /// The latest sessions, newest first, with nicknames as device names.
+    pub recent: Vec<Session>,
+}
+
+impl Stats {
+    /// Aggregates `sessions` as of `now`, showing devices by their nicknames
+    /// where they have one.
+    pub fn compute(
+        sessions: &[Session],
+        nicknames: &BTreeMap<String, String>,
+        now: DateTime<Local>,
+    ) -> Self {
+        let week_start = start_of_week(now);
+        let this_week = sessions
+            .iter()
+            .map(|session| {
+                let start = session.start.max(week_start);
+                let end = session.end.min(now);
+                (end - start).max(Duration::zero())
+            })
+            .sum();
+
+        let total: Duration = sessions.iter().map(Session::duration).sum();
+        let average = (!sessions.is_empty()).then(|| total / sessions.len() as i32);
+
+        let mut counts: HashMap<&str, (usize, &str)> = HashMap::new();
+        for session in sessions {
+            let entry = counts
+                .entry(session.device_id.as_str())
+                .or_insert((0, session.device_name.as_str()));
+            entry.0 += 1;
+            // Prefer the most recent name the device used
+            entry.1 = session.device_name.as_str();
+        }
+        let top_device = counts
+            .into_iter()
+            .max_by_key(|(_, (count, _))| *count)
+            .map(|(id, (count, name))| {
+                let name = nicknames.get(id).map_or(name, String::as_str);
+                (name.to_string(), count)
+            });
+
+        let recent = sessions
+            .iter()
+            .rev()
+            .take(RECENT)
+            .map(|session| Session {
+                device_name: nicknames
+                    .get(&session.device_id)
+                    .unwrap_or(&session.device_name)
+                    .clone(),
+                ..session.clone()
+            })
+            .collect();
+
+        Self {
+            this_week,
+            sessions: sessions.len(),
+            average,
+            top_device,
+            recent,
+        }
+    }
+}
+
+/// Streaming in the week before the current one, for the weekly summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeekSummary {
+    /// Monday of the current week, which the summary is sent in.
+    pub week: NaiveDate,
+    /// Time streamed in the week before.
+    pub total: Duration,
+    /// Devices by their time streamed, most first, named by their nicknames
+    /// where they have one.
+    pub top_devices: Vec<(String, Duration)>,
+}
+
+/// Sums up the sessions of the week before the one containing `now`.
+pub fn last_week(
+    sessions: &[Session],
+    nicknames: &BTreeMap<String, String>,
+    now: DateTime<Local>,
+) -> WeekSummary {
+    let end = start_of_week(now);
+    let start = end - Duration::weeks(1);
+
+    let mut devices: HashMap<&str, (Duration, &str)> = HashMap::new();
+    for session in sessions {
+        let streamed = session.end.min(end) - session.start.max(start);
+        if streamed <= Duration::zero() {
+            continue;
+        }
+        let entry = devices
+            .entry(session.device_id.as_str())
+            .or_insert((Duration::zero(), session.device_name.as_str()));
+        entry.0 += streamed;
+        entry.1 = session.device_name.as_str();
+    }
+
+    let total = devices.values().map(|(streamed, _)| *streamed).sum();
+    let mut top_devices: Vec<(String, Duration)> = devices
+        .into_iter()
+        .map(|(id, (streamed, name))| {
+            (nicknames.get(id).map_or(name, String::as_str).to_string(), streamed)
+        })
+        .collect();
+    top_devices.sort_by(|a, b| b.1.cmp(&a.1));
+    top_devices.truncate(SUMMARY_DEVICES);
+
+    WeekSummary {
+        week: end.date_naive(),
+        total,
+        top_devices,
+    }
+}
+
+/// Monday 00:00 of the week containing `now`, in local time.
+pub(super) fn start_of_week(now: DateTime<Local>) -> DateTime<Local> {
+    let monday = now.date_naive() - Duration::days(i64::from(now.weekday().num_days_from_monday()));
+    let midnight = monday.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .unwrap_or(now)
+}
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Commands the user runs on receiver events, e.g. to switch the TV to the
+//! computer's input over HDMI-CEC when mirroring begins. They run through
+//! `sh -c` on the host, with the details in `AIRTRAY_*` environment variables.
+
+use std::future::Future;
+use std::process::Stdio;
+
+use crate::config::{Hook, HooksConfig};
+use crate::host;
+
+impl Hook {
+    /// Value of `AIRTRAY_EVENT` for the hook.
+    fn event(self) -> &'static str {
+        match self {
+            Hook::Start => "start",
+            Hook::Stop => "stop",
+            Hook::ClientConnect => "client-connect",
+            Hook::ClientDisconnect => "client-disconnect",
+        }
+    }
+}
+
+/// Runs the command for `hook`, if there is one, until it exits. `receiver`
+/// is the name the receiver advertises, `client` the id and name of the
+/// sender a client hook is about.
+pub fn run(
+    hooks: &HooksConfig,
+    hook: Hook,
+    receiver: &str,
+    client: Option<(&str, &str)>,
+) -> impl Future<Output = ()> {
+    let script = hooks.command(hook).trim().to_string();
+    let mut envs = vec![
+        ("AIRTRAY_EVENT", hook.event().to_string()),
+        ("AIRTRAY_RECEIVER", receiver.to_string()),
+    ];
+    if let Some((id, name)) = client {
+        envs.push(("AIRTRAY_CLIENT_ID", id.to_string()));
+        envs.push(("AIRTRAY_CLIENT_NAME", name.to_string()));
+    }
+
+    async move {
+        if script.is_empty() {
+            return;
+        }
+        let mut command = host::command_with_env("sh", envs);
+        command.arg("-c").arg(&script).stdin(Stdio::null());
+
+        match tokio::process::Command::from(command).status().await {
+            Ok(status) if !status.success() => {
+                eprintln!("The {} hook failed: {}", hook.event(), status);
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Failed to run the {} hook: {}", hook.event(), e),
+        }
+    }
+}
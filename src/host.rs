@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Runs programs installed on the host. Inside a Flatpak sandbox they are
+//! out of reach, so they are started through `flatpak-spawn --host`, which
+//! forwards signals and exits with the program.
+//!
+//! UXPlay may also live in a Toolbox or Distrobox container. Entering one
+//! doesn't pass signals on, so the program announces its pid, which these
+//! containers share with the host, to be signalled directly.
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Whether the applet runs inside a Flatpak sandbox.
+pub fn in_flatpak() -> bool {
+    Path::new("/.flatpak-info").exists()
+}
+
+/// The path of `program` in the first of `dirs` that has it, or else just
+/// `program`, to be looked up in PATH. Not available in a sandbox, which
+/// can't see the host's directories.
+pub fn resolve(program: &str, dirs: &[PathBuf]) -> String {
+    if in_flatpak() {
+        return program.to_string();
+    }
+    dirs.iter()
+        .map(|dir| dir.join(program))
+        .find(|path| executable(path))
+        .map_or_else(|| program.to_string(), |path| path.to_string_lossy().into_owned())
+}
+
+/// Whether `program` is a path to an executable, or one is found in `dirs`
+/// or PATH. Always true in a sandbox, which can't tell.
+pub fn installed(program: &str, dirs: &[PathBuf]) -> bool {
+    if in_flatpak() {
+        return true;
+    }
+    if program.contains('/') {
+        return executable(Path::new(program));
+    }
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    dirs.iter()
+        .cloned()
+        .chain(std::env::split_paths(&path))
+        .any(|dir| executable(&dir.join(program)))
+}
+
+fn executable(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// A command running `program` on the host.
+pub fn command(program: &str) -> Command {
+    command_with_env(program, std::iter::empty::<(&str, String)>())
+}
+
+/// A command running `program` on the host with extra environment
+/// variables, which have to be passed explicitly to cross the sandbox.
+pub fn command_with_env<K, V>(program: &str, envs: impl IntoIterator<Item = (K, V)>) -> Command
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    if !in_flatpak() {
+        let mut command = Command::new(program);
+        command.envs(
+            envs.into_iter()
+                .map(|(key, value)| (key.as_ref().to_string(), value.as_ref().to_string())),
+        );
+        return command;
+    }
+
+    let mut command = Command::new("flatpak-spawn");
+    // Ends the host process if the applet goes away without stopping it
+    command.args(["--host", "--watch-bus"]);
+    for (key, value) in envs {
+        command.arg(format!("--env={}={}", key.as_ref(), value.as_ref()));
+    }
+    command.arg(program);
+    command
+}
+
+/// Printed before the pid of a program started in a container.
+const PID_MARKER: &str = "airtray-pid:";
+
+/// A command running `program` in the container entered by `prefix`, e.g.
+/// "distrobox enter media --". The prefix is split at whitespace. The
+/// program's first line of output is its pid, see [`container_pid`].
+pub fn in_container<K, V>(
+    prefix: &str,
+    program: &str,
+    envs: impl IntoIterator<Item = (K, V)>,
+) -> Command
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut words = prefix.split_whitespace();
+    let mut command = command(words.next().unwrap_or("sh"));
+    command.args(words);
+    // The environment is set inside, as entering doesn't carry it over
+    command.args([
+        "sh",
+        "-c",
+        &format!("echo {PID_MARKER}$$; exec \"$@\""),
+        "sh",
+        "env",
+    ]);
+    for (key, value) in envs {
+        command.arg(format!("{}={}", key.as_ref(), value.as_ref()));
+    }
+    command.arg(program);
+    command
+}
+
+/// The pid announced by a program started with [`in_container`], if `line`
+/// is the announcement.
+pub fn container_pid(line: &str) -> Option<i32> {
+    line.trim().strip_prefix(PID_MARKER)?.parse().ok()
+}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The receiver's identity: the key UXPlay signs with, which senders
+//! remember it by, and the register of devices that entered its PIN. Both
+//! live in AirTray's data folder and can be backed up as a zip, so devices
+//! don't have to pair again after a reinstall.
+
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+
+use crate::config::Config;
+use crate::error::AirTrayError;
+
+/// The files making up the identity, by their name in the data folder and
+/// in backups.
+const KEY: &str = "uxplay.pem";
+const REGISTER: &str = "uxplay.register";
+
+/// Where the identity is kept.
+pub fn dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from(".local/share"))
+        .join("airtray")
+}
+
+/// The private key UXPlay is started with.
+pub fn key_path() -> PathBuf {
+    dir().join(KEY)
+}
+
+/// The devices that don't have to enter the PIN again.
+pub fn register_path() -> PathBuf {
+    dir().join(REGISTER)
+}
+
+/// Where backups go by default, the Downloads folder.
+pub fn backup_path(config: &Config) -> PathBuf {
+    config.companion.download_dir().join("airtray-keys.zip")
+}
+
+/// Saves the key and the register to a zip at `path`.
+pub fn backup(path: &Path) -> Result<(), AirTrayError> {
+    if !key_path().exists() {
+        return Err(AirTrayError::IdentityError(String::from(
+            "no key yet, start the receiver once first",
+        )));
+    }
+    write_backup(path)
+        .map_err(|e| AirTrayError::IdentityError(format!("{}: {}", path.display(), e)))
+}
+
+fn write_backup(path: &Path) -> zip::result::ZipResult<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    for name in [KEY, REGISTER] {
+        // The register only exists once a device entered the PIN
+        let Ok(contents) = std::fs::read(dir().join(name)) else {
+            continue;
+        };
+        zip.start_file(name, SimpleFileOptions::default().unix_permissions(0o600))?;
+        zip.write_all(&contents)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Replaces the key and the register with those in the backup at `path`.
+/// UXPlay reads them when it starts.
+pub fn restore(path: &Path) -> Result<(), AirTrayError> {
+    read_backup(path)
+        .map_err(|e| AirTrayError::IdentityError(format!("{}: {}", path.display(), e)))
+}
+
+fn read_backup(path: &Path) -> zip::result::ZipResult<()> {
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    if zip.by_name(KEY).is_err() {
+        return Err(zip::result::ZipError::FileNotFound);
+    }
+
+    std::fs::create_dir_all(dir())?;
+    for name in [KEY, REGISTER] {
+        let Ok(mut file) = zip.by_name(name) else {
+            // Devices registered since are forgotten, as the backup had none
+            let _ = std::fs::remove_file(dir().join(name));
+            continue;
+        };
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(dir().join(name))?
+            .write_all(&contents)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Recent output of UXPlay, kept in memory for the log page.
+
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex};
+
+use chrono::{DateTime, Local};
+
+/// Lines kept, oldest dropped first.
+const CAPACITY: usize = 1000;
+
+static LINES: LazyLock<Mutex<VecDeque<LogLine>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::with_capacity(CAPACITY)));
+
+/// How serious a log line is, least serious first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    /// Guesses the level from the wording, as UXPlay and GStreamer don't
+    /// tag their lines consistently.
+    fn of(text: &str) -> Self {
+        let text = text.to_lowercase();
+        if text.contains("error") || text.contains("failed") || text.contains("critical") {
+            Self::Error
+        } else if text.contains("warn") {
+            Self::Warning
+        } else {
+            Self::Info
+        }
+    }
+}
+
+/// A line UXPlay wrote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub text: String,
+}
+
+/// Keeps `text` for the log page.
+pub fn record(text: &str) {
+    let Ok(mut lines) = LINES.lock() else {
+        return;
+    };
+    if lines.len() == CAPACITY {
+        lines.pop_front();
+    }
+    lines.push_back(LogLine {
+        time: Local::now(),
+        level: Level::of(text),
+        text: text.to_string(),
+    });
+}
+
+/// Kept lines at least as serious as `level`, oldest first.
+pub fn lines(level: Level) -> Vec<LogLine> {
+    LINES
+        .lock()
+        .map(|lines| lines.iter().filter(|line| line.level >= level).cloned().collect())
+        .unwrap_or_default()
+}
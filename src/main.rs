@@ -1,9 +1,45 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use app::AirTray;
+use app::{AirTray, Mode};
 /// The `app` module is used by convention to indicate the main component of our application.
 mod app;
+mod appletv;
+mod audio;
+mod backend;
+mod cli;
+mod companion;
+mod config;
 mod core;
+mod dacp;
+mod dbus;
+mod debug;
+mod error;
+mod frames;
+mod gpu;
+mod health;
+mod history;
+mod hooks;
+mod host;
+mod identity;
+mod log;
+mod mdns;
+mod metadata;
+mod mqtt;
+mod plugins;
+mod ports;
+mod receiver;
+mod rest;
+mod schedule;
+mod sound;
+mod usage;
+mod uxplayrc;
+mod video;
+mod viewer;
+mod wake;
+mod watchdog;
+mod webhook;
+#[cfg(test)]
+mod tests;
 
 /// The `cosmic::app::run()` function is the starting point of your application.
 /// It takes two arguments:
@@ -11,5 +47,26 @@ mod core;
 /// - `()` is the flags that your app needs to use before it starts.
 ///  If your app does not need any flags, you can pass in `()`.
 fn main() -> cosmic::iced::Result {
-    cosmic::applet::run::<AirTray>(())
+    if let Some(code) = cli::run(std::env::args().skip(1)) {
+        std::process::exit(code);
+    }
+
+    // For desktops without the COSMIC panel
+    if std::env::args().skip(1).any(|arg| arg == "--window") {
+        let settings = cosmic::app::Settings::default()
+            .size(cosmic::iced::Size::new(400.0, 720.0))
+            .size_limits(cosmic::iced::Limits::NONE.min_width(300.0).min_height(200.0));
+        return cosmic::app::run::<AirTray>(settings, Mode::Window);
+    }
+
+    // For desktops with a tray but without the COSMIC panel
+    #[cfg(feature = "tray")]
+    if std::env::args().skip(1).any(|arg| arg == "--tray") {
+        let settings = cosmic::app::Settings::default()
+            .no_main_window(true)
+            .exit_on_close(false);
+        return cosmic::app::run::<AirTray>(settings, Mode::Tray);
+    }
+
+    cosmic::applet::run::<AirTray>(Mode::Applet)
 }
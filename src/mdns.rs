@@ -0,0 +1,341 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Announces and looks up services on the local network, through Avahi or
+//! the responder built into the applet.
+
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use mdns_sd::{IfKind, ServiceDaemon, ServiceEvent, ServiceInfo};
+use tokio::process::{Child, Command};
+
+use crate::config::{hostname, IpVersion, MdnsResponder};
+use crate::error::AirTrayError;
+use crate::host;
+
+/// How long the built-in responder collects answers when browsing.
+const BROWSE_TIME: Duration = Duration::from_secs(2);
+
+/// Service type AirPlay receivers announce.
+pub const AIRPLAY: &str = "_airplay._tcp";
+
+/// Service type AirPlay receivers announce for audio, which older senders
+/// and audio-only apps look for.
+pub const RAOP: &str = "_raop._tcp";
+
+/// Service type iPhones, iPads and Macs announce, all of which can send to
+/// AirPlay receivers.
+pub const COMPANION_LINK: &str = "_companion-link._tcp";
+
+/// A service announced on the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    /// Instance name, e.g. "Living Room".
+    pub name: String,
+    /// Host announcing it, e.g. "tv.local".
+    pub host: String,
+    pub address: String,
+    pub port: u16,
+    /// TXT records, e.g. "deviceid=12:34:56:78:9A:BC".
+    pub txt: Vec<String>,
+}
+
+impl Service {
+    /// Whether this machine announces the service.
+    pub fn is_local(&self) -> bool {
+        self.host.eq_ignore_ascii_case(&format!("{}.local", hostname()))
+    }
+
+    /// Whether the service announces this machine's device id.
+    pub fn has_local_id(&self) -> bool {
+        self.txt("deviceid").is_some_and(|id| id.eq_ignore_ascii_case(device_id()))
+    }
+
+    /// Value of the TXT record `key`.
+    pub fn txt(&self, key: &str) -> Option<&str> {
+        self.txt
+            .iter()
+            .find_map(|record| record.strip_prefix(key)?.strip_prefix('='))
+    }
+}
+
+/// A service announced by the applet, withdrawn when dropped.
+pub enum Advertisement {
+    /// avahi-publish-service, announcing for as long as it runs.
+    Avahi(Child),
+    Builtin(ServiceDaemon),
+}
+
+impl Drop for Advertisement {
+    fn drop(&mut self) {
+        match self {
+            Self::Avahi(child) => {
+                let _ = child.start_kill();
+            }
+            // Sends the goodbye packets
+            Self::Builtin(daemon) => {
+                let _ = daemon.shutdown();
+            }
+        }
+    }
+}
+
+/// Announces the service `name` of `service_type` on `port` with the TXT
+/// records `txt`, e.g. "model=AppleTV3,2". The built-in responder only
+/// announces on the addresses of `ip_version`.
+pub fn advertise(
+    responder: MdnsResponder,
+    ip_version: IpVersion,
+    name: &str,
+    service_type: &str,
+    port: u16,
+    txt: &[String],
+) -> Result<Advertisement, AirTrayError> {
+    match responder {
+        MdnsResponder::Avahi => Command::from(host::command("avahi-publish-service"))
+            .arg(name)
+            .arg(service_type)
+            .arg(port.to_string())
+            .args(txt)
+            .stdout(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map(Advertisement::Avahi)
+            .map_err(|e| AirTrayError::spawn("avahi-publish-service", e)),
+        MdnsResponder::Builtin => {
+            let daemon = builtin(ip_version)?;
+            let properties: Vec<(&str, &str)> = txt
+                .iter()
+                .map(|record| record.split_once('=').unwrap_or((record, "")))
+                .collect();
+            let info = ServiceInfo::new(
+                &format!("{}.local.", service_type),
+                name,
+                &format!("{}.local.", hostname()),
+                "",
+                port,
+                &properties[..],
+            )
+            .map_err(mdns_error)?
+            .enable_addr_auto();
+            daemon.register(info).map_err(mdns_error)?;
+            Ok(Advertisement::Builtin(daemon))
+        }
+    }
+}
+
+/// A built-in responder on the interfaces of `ip_version`.
+fn builtin(ip_version: IpVersion) -> Result<ServiceDaemon, AirTrayError> {
+    let daemon = ServiceDaemon::new().map_err(mdns_error)?;
+    let disabled = match ip_version {
+        IpVersion::Both => None,
+        IpVersion::Ipv4 => Some(IfKind::IPv6),
+        IpVersion::Ipv6 => Some(IfKind::IPv4),
+    };
+    if let Some(kind) = disabled {
+        daemon.disable_interface(kind).map_err(mdns_error)?;
+    }
+    Ok(daemon)
+}
+
+fn mdns_error(error: mdns_sd::Error) -> AirTrayError {
+    AirTrayError::SpawnFailed {
+        binary: String::from("mDNS responder"),
+        reason: error.to_string(),
+    }
+}
+
+/// Services of `service_type` currently announced, once per name and host
+/// even if seen on several interfaces.
+pub async fn browse(
+    responder: MdnsResponder,
+    service_type: &str,
+) -> Result<Vec<Service>, AirTrayError> {
+    let services = match responder {
+        MdnsResponder::Avahi => browse_avahi(service_type).await?,
+        MdnsResponder::Builtin => browse_builtin(service_type).await?,
+    };
+
+    let mut unique: Vec<Service> = Vec::new();
+    for service in services {
+        if !unique
+            .iter()
+            .any(|known| known.name == service.name && known.host == service.host)
+        {
+            unique.push(service);
+        }
+    }
+    Ok(unique)
+}
+
+async fn browse_avahi(service_type: &str) -> Result<Vec<Service>, AirTrayError> {
+    let output = Command::from(host::command("avahi-browse"))
+        .args(["--parsable", "--resolve", "--terminate", service_type])
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| AirTrayError::spawn("avahi-browse", e))?;
+    // e.g. when avahi-daemon isn't running
+    if !output.status.success() {
+        return Err(AirTrayError::SpawnFailed {
+            binary: String::from("avahi-browse"),
+            reason: output.status.to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_line)
+        .collect())
+}
+
+/// Collects what answers within [`BROWSE_TIME`].
+async fn browse_builtin(service_type: &str) -> Result<Vec<Service>, AirTrayError> {
+    let daemon = builtin(IpVersion::Both)?;
+    let suffix = format!(".{}.local.", service_type);
+    let events = daemon.browse(&suffix[1..]).map_err(mdns_error)?;
+
+    let mut services = Vec::new();
+    let _ = tokio::time::timeout(BROWSE_TIME, async {
+        while let Ok(event) = events.recv_async().await {
+            if let ServiceEvent::ServiceResolved(info) = event {
+                services.push(Service {
+                    name: info.get_fullname().trim_end_matches(&suffix).to_string(),
+                    host: info.get_hostname().trim_end_matches('.').to_string(),
+                    address: info
+                        .get_addresses()
+                        .iter()
+                        .next()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                    port: info.get_port(),
+                    txt: info
+                        .get_properties()
+                        .iter()
+                        .map(|property| format!("{}={}", property.key(), property.val_str()))
+                        .collect(),
+                });
+            }
+        }
+    })
+    .await;
+
+    let _ = daemon.shutdown();
+    Ok(services)
+}
+
+/// Other machines on the network that AirPlay works with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Nearby {
+    pub receivers: Vec<Service>,
+    pub senders: Vec<Service>,
+}
+
+/// Browses for AirPlay receivers and senders other than this machine.
+pub async fn nearby(responder: MdnsResponder) -> Result<Nearby, AirTrayError> {
+    let (receivers, senders) =
+        tokio::join!(browse(responder, AIRPLAY), browse(responder, COMPANION_LINK));
+    let others =
+        |services: Vec<Service>| services.into_iter().filter(|s| !s.is_local()).collect();
+    Ok(Nearby {
+        receivers: others(receivers?),
+        senders: others(senders?),
+    })
+}
+
+/// AirPlay receivers on other machines called `name` or announcing this
+/// machine's device id, e.g. a forgotten instance or one impersonating it.
+pub async fn duplicates(responder: MdnsResponder, name: &str) -> Vec<Service> {
+    match browse(responder, AIRPLAY).await {
+        Ok(services) => services
+            .into_iter()
+            .filter(|service| !service.is_local())
+            .filter(|service| service.name == name || service.has_local_id())
+            .collect(),
+        Err(e) => {
+            eprintln!("Failed to browse AirPlay receivers: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Hardware address of the first network interface, which UXPlay also uses
+/// as its device id.
+pub fn device_id() -> &'static str {
+    static DEVICE_ID: LazyLock<String> = LazyLock::new(mac_address);
+    &DEVICE_ID
+}
+
+fn mac_address() -> String {
+    let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+        return String::from("00:00:00:00:00:00");
+    };
+
+    let mut interfaces: Vec<_> = entries
+        .flatten()
+        .filter(|entry| entry.file_name() != "lo")
+        .map(|entry| entry.path())
+        .collect();
+    interfaces.sort();
+
+    interfaces
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path.join("address")).ok())
+        .map(|address| address.trim().to_uppercase())
+        .find(|address| address != "00:00:00:00:00:00")
+        .unwrap_or_else(|| String::from("00:00:00:00:00:00"))
+}
+
+/// Parses a resolved entry, e.g. with `"deviceid=..." "model=..."` as TXT records:
+/// `=;wlan0;IPv4;Living\032Room;_airplay._tcp;local;tv.local;192.168.1.20;7000;<TXT records>`
+fn parse_line(line: &str) -> Option<Service> {
+    let mut fields = line.splitn(10, ';');
+    if fields.next()? != "=" {
+        return None;
+    }
+    let mut fields = fields.skip(2);
+    let name = unescape(fields.next()?);
+    let mut fields = fields.skip(2);
+    let host = fields.next()?.to_string();
+    let address = fields.next()?.to_string();
+    let port = fields.next()?.parse().ok()?;
+    let txt = fields
+        .next()
+        .unwrap_or_default()
+        .split("\" \"")
+        .map(|record| record.trim_matches('"').to_string())
+        .filter(|record| !record.is_empty())
+        .collect();
+
+    Some(Service {
+        name,
+        host,
+        address,
+        port,
+        txt,
+    })
+}
+
+/// Undoes Avahi's `\DDD` escaping of service names.
+fn unescape(name: &str) -> String {
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut rest = name.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let code = tail
+            .get(..3)
+            .and_then(|digits| std::str::from_utf8(digits).ok()?.parse::<u8>().ok());
+        match code.filter(|_| byte == b'\\') {
+            Some(code) => {
+                bytes.push(code);
+                rest = &tail[3..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Now-playing information for audio streams, from the files UXPlay exports
+//! it to (`-md` for the text, `-ca` for the cover art).
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use cosmic::iced::futures::{SinkExt, Stream};
+use cosmic::widget::image;
+
+/// How often the exported files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The track the sender is playing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NowPlaying {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub artwork: Option<image::Handle>,
+}
+
+/// Where UXPlay is told to write the track metadata.
+pub fn metadata_path() -> PathBuf {
+    runtime_dir().join("airtray-metadata.txt")
+}
+
+/// Where UXPlay is told to write the cover art.
+pub fn artwork_path() -> PathBuf {
+    runtime_dir().join("airtray-coverart.jpg")
+}
+
+fn runtime_dir() -> PathBuf {
+    dirs::runtime_dir().unwrap_or_else(std::env::temp_dir)
+}
+
+/// Yields the current track whenever the exported files change.
+pub fn watch() -> impl Stream<Item = Option<NowPlaying>> {
+    cosmic::iced::stream::channel(1, |mut output| async move {
+        let mut seen = None;
+
+        loop {
+            let modified = (modified(&metadata_path()), modified(&artwork_path()));
+            if seen != Some(modified) {
+                seen = Some(modified);
+                if output.send(read().await).await.is_err() {
+                    return;
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Reads the exported files, or `None` if nothing has been exported yet.
+async fn read() -> Option<NowPlaying> {
+    let text = tokio::fs::read_to_string(metadata_path()).await.ok()?;
+    let mut now_playing = NowPlaying::default();
+
+    // "Title: Song", "Artist: Band", "Album: Record"
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "title" => now_playing.title = value,
+            "artist" => now_playing.artist = value,
+            "album" => now_playing.album = value,
+            _ => {}
+        }
+    }
+
+    // Loaded from memory, iced caches images loaded by path
+    now_playing.artwork = tokio::fs::read(artwork_path())
+        .await
+        .ok()
+        .map(image::Handle::from_bytes);
+
+    (!now_playing.title.is_empty()).then_some(now_playing)
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Publishes the receiver state to an MQTT broker, with Home Assistant
+//! discovery so the applet shows up as a device without extra setup.
+
+use std::time::Duration;
+
+use cosmic::iced::futures::{SinkExt, Stream};
+use rumqttc::{AsyncClient, LastWill, MqttOptions, QoS};
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+use crate::config::{hostname, MqttConfig};
+
+/// How long to wait before reconnecting after the broker went away.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// What is published on every change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Status {
+    /// "stopped", "starting", "running", "stopping" or "failed".
+    pub state: &'static str,
+    /// Names of the connected senders.
+    pub clients: Vec<String>,
+    pub now_playing: Option<Track>,
+}
+
+/// The track being streamed, if the sender reported one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Track {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+}
+
+/// Hands statuses to the connection task.
+#[derive(Debug, Clone)]
+pub struct Publisher(mpsc::UnboundedSender<Status>);
+
+impl Publisher {
+    pub fn publish(&self, status: Status) {
+        let _ = self.0.send(status);
+    }
+}
+
+/// Connects to the broker for as long as the stream is polled. The stream
+/// first yields the [`Publisher`] to send statuses through.
+pub fn connect(config: MqttConfig) -> impl Stream<Item = Publisher> {
+    cosmic::iced::stream::channel(1, move |mut output| async move {
+        let (tx, mut statuses) = mpsc::unbounded_channel();
+        let _ = output.send(Publisher(tx)).await;
+
+        let prefix = config.topic_prefix.trim_end_matches('/').to_string();
+        let availability = format!("{prefix}/availability");
+
+        let mut options = MqttOptions::new(format!("airtray-{}", hostname()), &config.host, config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        options.set_last_will(LastWill::new(&availability, "offline", QoS::AtLeastOnce, true));
+        if !config.username.is_empty() {
+            options.set_credentials(&config.username, &config.password);
+        }
+
+        let (client, mut eventloop) = AsyncClient::new(options, 16);
+        let mut last = None;
+
+        loop {
+            tokio::select! {
+                status = statuses.recv() => {
+                    let Some(status) = status else { break };
+                    publish_status(&client, &prefix, &status).await;
+                    last = Some(status);
+                }
+                event = eventloop.poll() => match event {
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(_))) => {
+                        // Announce again after every (re)connect, the broker may have restarted
+                        let _ = client.publish(&availability, QoS::AtLeastOnce, true, "online").await;
+                        announce(&client, &prefix).await;
+                        if let Some(status) = &last {
+                            publish_status(&client, &prefix, status).await;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("MQTT connection to {}:{} failed: {}", config.host, config.port, e);
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                },
+            }
+        }
+    })
+}
+
+async fn publish_status(client: &AsyncClient, prefix: &str, status: &Status) {
+    let topics = [
+        (format!("{prefix}/state"), status.state.to_string()),
+        (format!("{prefix}/clients"), status.clients.len().to_string()),
+        (
+            format!("{prefix}/status"),
+            serde_json::to_string(status).unwrap_or_default(),
+        ),
+    ];
+
+    for (topic, payload) in topics {
+        if let Err(e) = client.publish(topic, QoS::AtLeastOnce, true, payload).await {
+            eprintln!("Failed to publish to MQTT: {}", e);
+        }
+    }
+}
+
+/// Publishes Home Assistant discovery configs for the state and client count.
+async fn announce(client: &AsyncClient, prefix: &str) {
+    let host = hostname();
+    let device = json!({
+        "identifiers": [format!("airtray_{host}")],
+        "name": format!("AirTray {host}"),
+        "manufacturer": "AirTray",
+    });
+    let entities = [
+        (
+            "binary_sensor",
+            "mirroring",
+            json!({
+                "name": "AirPlay mirroring",
+                "state_topic": format!("{prefix}/clients"),
+                "value_template": "{{ 'ON' if value | int > 0 else 'OFF' }}",
+            }),
+        ),
+        (
+            "sensor",
+            "state",
+            json!({ "name": "AirPlay receiver", "state_topic": format!("{prefix}/state") }),
+        ),
+        (
+            "sensor",
+            "clients",
+            json!({ "name": "AirPlay clients", "state_topic": format!("{prefix}/clients") }),
+        ),
+    ];
+
+    for (component, object, mut entity) in entities {
+        entity["unique_id"] = json!(format!("airtray_{host}_{object}"));
+        entity["availability_topic"] = json!(format!("{prefix}/availability"));
+        entity["device"] = device.clone();
+
+        let topic = format!("homeassistant/{component}/airtray_{host}/{object}/config");
+        if let Err(e) = client
+            .publish(topic, QoS::AtLeastOnce, true, entity.to_string())
+            .await
+        {
+            eprintln!("Failed to publish Home Assistant discovery: {}", e);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Extension points for receiver backends and output modules that live
+//! outside the applet, e.g. another AirPlay implementation or a recorder of
+//! one's own.
+//!
+//! Rust has no stable ABI, so plugins aren't loaded from a directory at
+//! runtime. A plugin is a crate built into the applet behind a cargo feature
+//! of its own, with a `register` function adding it to the [`Registry`] in
+//! [`registry`]. Its backends implement [`Backend`] and its outputs
+//! [`Output`].
+
+use std::fmt;
+
+use crate::backend::{Backend, UxplayBackend};
+use crate::config::Config;
+
+/// Name of the built-in UXPlay backend, used when none is set.
+pub const DEFAULT_BACKEND: &str = "uxplay";
+
+/// A receiver backend, chosen by `name` in the launch settings.
+#[derive(Debug, Clone, Copy)]
+pub struct BackendPlugin {
+    pub name: &'static str,
+    pub create: fn() -> Box<dyn Backend>,
+}
+
+/// Something the receiver went through, told to output modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginEvent {
+    Started,
+    Stopped,
+    ClientConnected { id: String, name: String },
+    /// Sent once the sender's grace period is over.
+    ClientDisconnected { id: String, name: String },
+}
+
+impl fmt::Display for PluginEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Started => write!(f, "start"),
+            Self::Stopped => write!(f, "stop"),
+            Self::ClientConnected { id, name } => write!(f, "{} ({}) connecting", name, id),
+            Self::ClientDisconnected { id, name } => write!(f, "{} ({}) leaving", name, id),
+        }
+    }
+}
+
+/// An output module. It is called on the UI thread, so anything slow
+/// belongs on a thread or task of its own.
+pub trait Output: Send {
+    /// Name shown in the log.
+    fn name(&self) -> &str;
+
+    /// Handles `event`, given the settings the receiver runs with. An error
+    /// is logged.
+    fn on_event(&mut self, event: &PluginEvent, config: &Config) -> Result<(), String>;
+}
+
+/// The backends and outputs available to the applet, which plugins add
+/// theirs to.
+#[derive(Default)]
+pub struct Registry {
+    pub backends: Vec<BackendPlugin>,
+    pub outputs: Vec<Box<dyn Output>>,
+}
+
+impl Registry {
+    /// Creates the backend called `name`, or the built-in one if there is
+    /// no such backend.
+    pub fn backend(&self, name: &str) -> Box<dyn Backend> {
+        let name = if name.is_empty() { DEFAULT_BACKEND } else { name };
+        match self.backends.iter().find(|plugin| plugin.name == name) {
+            Some(plugin) => (plugin.create)(),
+            None => {
+                eprintln!("No backend called {}, using {}", name, DEFAULT_BACKEND);
+                Box::new(UxplayBackend::default())
+            }
+        }
+    }
+
+    /// Hands the registered outputs over to the applet.
+    pub fn into_outputs(self) -> Vec<Box<dyn Output>> {
+        self.outputs
+    }
+}
+
+/// The built-in backend and everything the plugins built in register.
+pub fn registry() -> Registry {
+    let mut registry = Registry::default();
+    registry.backends.push(BackendPlugin {
+        name: DEFAULT_BACKEND,
+        create: || Box::new(UxplayBackend::default()),
+    });
+
+    // Plugins register here, each behind its feature, e.g.
+    // #[cfg(feature = "airtray-shairport")]
+    // airtray_shairport::register(&mut registry);
+
+    registry
+}
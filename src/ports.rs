@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Checks that the ports UXPlay is told to use are free before it starts,
+//! so a conflict is reported as such instead of as a failed start.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, UdpSocket};
+use std::os::unix::fs::MetadataExt;
+
+use crate::config::PortConfig;
+use crate::error::AirTrayError;
+
+/// TCP port senders connect to.
+pub const AIRPLAY_PORT: u16 = 7000;
+
+/// TCP ports UXPlay listens on: for connecting, for data and for the
+/// mirrored video.
+pub const TCP_PORTS: [u16; 3] = [AIRPLAY_PORT, 7001, 7100];
+
+/// UXPlay uses this many consecutive UDP ports from the configured one, for
+/// audio data, control and timing.
+pub const UDP_COUNT: u16 = 3;
+
+/// State of a listening socket in /proc/net/tcp.
+const TCP_LISTEN: u8 = 0x0A;
+
+/// Fails with the first port UXPlay needs that something else already uses,
+/// e.g. shairport-sync or another receiver.
+pub fn check(ports: &PortConfig) -> Result<(), AirTrayError> {
+    // The sockets are closed again right away
+    for port in TCP_PORTS {
+        if taken(port, |address| TcpListener::bind(address).map(drop)) {
+            return Err(in_use("TCP", port));
+        }
+    }
+    for port in ports.udp_range() {
+        if taken(port, |address| UdpSocket::bind(address).map(drop)) {
+            return Err(in_use("UDP", port));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `bind` finds `port` taken on IPv4 or IPv6. Other failures, like
+/// IPv6 being turned off, don't count.
+fn taken(port: u16, bind: impl Fn(SocketAddr) -> io::Result<()>) -> bool {
+    [IpAddr::V4(Ipv4Addr::UNSPECIFIED), IpAddr::V6(Ipv6Addr::UNSPECIFIED)]
+        .into_iter()
+        .any(|ip| {
+            matches!(bind(SocketAddr::new(ip, port)), Err(e) if e.kind() == io::ErrorKind::AddrInUse)
+        })
+}
+
+fn in_use(protocol: &str, port: u16) -> AirTrayError {
+    let sockets = sockets(protocol, port);
+
+    // Most likely AirTray or UXPlay in another login session on this machine
+    let other_uid = std::fs::metadata("/proc/self").ok().and_then(|own| {
+        sockets.iter().map(|socket| socket.uid).find(|uid| *uid != own.uid())
+    });
+    if let Some(uid) = other_uid {
+        let user = user_name(uid).unwrap_or_else(|| uid.to_string());
+        return AirTrayError::PortInUseByUser {
+            protocol: protocol.to_string(),
+            port,
+            user,
+        };
+    }
+
+    AirTrayError::PortInUse {
+        protocol: protocol.to_string(),
+        port,
+        process: owner(&sockets),
+    }
+}
+
+/// A socket bound to a port.
+struct Socket {
+    inode: String,
+    /// User owning the socket.
+    uid: u32,
+    state: u8,
+}
+
+/// The sockets holding `port`. Of TCP sockets only listening ones count:
+/// connections, e.g. still in TIME_WAIT, don't keep others from listening.
+fn sockets(protocol: &str, port: u16) -> Vec<Socket> {
+    let tables: &[&str] = match protocol {
+        "TCP" => &["/proc/net/tcp", "/proc/net/tcp6"],
+        _ => &["/proc/net/udp", "/proc/net/udp6"],
+    };
+    tables
+        .iter()
+        .filter_map(|table| std::fs::read_to_string(table).ok())
+        .flat_map(|table| parse_table(&table, port))
+        .filter(|socket| protocol != "TCP" || socket.state == TCP_LISTEN)
+        .collect()
+}
+
+/// Name of the process holding one of `sockets`. Only processes of the
+/// current user can be told, others' open files can't be read.
+fn owner(sockets: &[Socket]) -> Option<String> {
+    if sockets.is_empty() {
+        return None;
+    }
+
+    std::fs::read_dir("/proc").ok()?.flatten().find_map(|process| {
+        let holds = std::fs::read_dir(process.path().join("fd"))
+            .ok()?
+            .flatten()
+            .filter_map(|fd| std::fs::read_link(fd.path()).ok())
+            .any(|target| {
+                let target = target.to_string_lossy();
+                sockets.iter().any(|socket| target == format!("socket:[{}]", socket.inode))
+            });
+        let name = std::fs::read_to_string(process.path().join("comm")).ok()?;
+        holds.then(|| name.trim().to_string())
+    })
+}
+
+/// The sockets bound to `port` in a /proc/net table, whose lines look like
+/// `0: 00000000:1B58 00000000:0000 0A ... 1000 0 123456 ...`.
+fn parse_table(table: &str, port: u16) -> Vec<Socket> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local_port = fields.get(1)?.rsplit_once(':')?.1;
+            if u16::from_str_radix(local_port, 16).ok()? != port {
+                return None;
+            }
+            Some(Socket {
+                inode: fields.get(9)?.to_string(),
+                uid: fields.get(7)?.parse().ok()?,
+                state: u8::from_str_radix(fields.get(3)?, 16).ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Login name of the user with `uid`, from /etc/passwd.
+fn user_name(uid: u32) -> Option<String> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        (fields.nth(1)?.parse() == Ok(uid)).then(|| name.to_string())
+    })
+}
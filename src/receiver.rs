@@ -0,0 +1,334 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use chrono::{DateTime, Local};
+use cosmic::iced::futures::StreamExt;
+use cosmic::iced::task::{self, Task};
+
+use crate::backend::{Backend, BackendEvent, UxplayBackend};
+use crate::config::{ConcurrentPolicy, Config};
+use crate::error::AirTrayError;
+use crate::history::Session;
+use crate::usage;
+
+/// A connected sender.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    pub id: String,
+    /// Nickname if there is one, else the announced name.
+    pub name: String,
+    /// Name the sender announced, e.g. "John's iPhone".
+    pub announced_name: String,
+    pub model: String,
+    pub connected_at: DateTime<Local>,
+    /// The network counters when it connected.
+    pub bytes_at_connect: u64,
+}
+
+impl Client {
+    /// Closes this client's session at `end`.
+    fn into_session(self, end: DateTime<Local>) -> Session {
+        Session {
+            device_id: self.id,
+            device_name: self.name,
+            model: self.model,
+            start: self.connected_at,
+            end,
+            bytes: usage::network_bytes().saturating_sub(self.bytes_at_connect),
+        }
+    }
+}
+
+/// Lifecycle of the AirPlay receiver. Every change to the running process
+/// goes through one of the transitions on [`Receiver`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ReceiverState {
+    /// Nothing is running.
+    #[default]
+    Stopped,
+    /// The receiver is advertised by the applet itself and starts when a
+    /// sender tries to connect.
+    Standby,
+    /// The receiver is being launched.
+    Starting,
+    /// The receiver is up and advertised.
+    Running {
+        /// Connected senders, in connection order.
+        clients: Vec<Client>,
+    },
+    /// The receiver has been asked to shut down and is still exiting.
+    Stopping,
+    /// The receiver could not be started or exited on its own.
+    Failed { reason: AirTrayError },
+}
+
+impl ReceiverState {
+    /// Whether the receiver is on or on its way up, i.e. the toggle position.
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Standby | Self::Starting | Self::Running { .. })
+    }
+}
+
+/// Results of backend work running on the executor.
+///
+/// Each event carries the generation of the process it belongs to, so results
+/// from a process that has since been replaced are ignored.
+#[derive(Debug, Clone)]
+pub enum ReceiverEvent {
+    Started {
+        generation: u64,
+        result: Result<(), AirTrayError>,
+    },
+    Backend {
+        generation: u64,
+        event: BackendEvent,
+    },
+}
+
+/// Owns the backend and drives it according to [`ReceiverState`].
+pub struct Receiver {
+    state: ReceiverState,
+    backend: Box<dyn Backend>,
+    /// Settings applied the next time the receiver is started.
+    pub config: Config,
+    /// Incremented for every spawned process.
+    generation: u64,
+    /// Cancels the launch while in [`ReceiverState::Starting`].
+    starting: Option<task::Handle>,
+    /// Sessions that ended since the last [`Receiver::take_ended`].
+    ended: Vec<Session>,
+    /// Go back to [`ReceiverState::Standby`] once the process has exited.
+    resume_standby: bool,
+    /// Start again once the process has exited.
+    restart: bool,
+}
+
+impl Default for Receiver {
+    fn default() -> Self {
+        Self::new(Box::new(UxplayBackend::default()))
+    }
+}
+
+impl Receiver {
+    pub fn new(backend: Box<dyn Backend>) -> Self {
+        Self {
+            state: ReceiverState::Stopped,
+            backend,
+            config: Config::default(),
+            generation: 0,
+            starting: None,
+            ended: Vec::new(),
+            resume_standby: false,
+            restart: false,
+        }
+    }
+
+    pub fn state(&self) -> &ReceiverState {
+        &self.state
+    }
+
+    /// Shows the connected clients by their current nicknames.
+    pub fn apply_nicknames(&mut self) {
+        if let ReceiverState::Running { clients } = &mut self.state {
+            for client in clients {
+                client.name = self.config.device_name(&client.id, &client.announced_name);
+            }
+        }
+    }
+
+    /// Sessions that ended since the last call, for the history.
+    pub fn take_ended(&mut self) -> Vec<Session> {
+        std::mem::take(&mut self.ended)
+    }
+
+    /// Ends the sessions of all connected clients.
+    fn end_sessions(&mut self) {
+        if let ReceiverState::Running { clients } = &mut self.state {
+            let now = Local::now();
+            self.ended
+                .extend(clients.drain(..).map(|client| client.into_session(now)));
+        }
+    }
+
+    /// Generation of the most recently spawned process.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Starts the receiver unless it is already running or still shutting down.
+    pub fn start(&mut self) -> Task<ReceiverEvent> {
+        if matches!(
+            self.state,
+            ReceiverState::Starting | ReceiverState::Running { .. } | ReceiverState::Stopping
+        ) {
+            return Task::none();
+        }
+
+        self.generation += 1;
+        let generation = self.generation;
+        self.state = ReceiverState::Starting;
+
+        let spawn = self.backend.spawn(&self.config);
+        let (task, handle) = Task::future(async move {
+            ReceiverEvent::Started {
+                generation,
+                result: spawn.await,
+            }
+        })
+        .abortable();
+
+        self.starting = Some(handle);
+        task
+    }
+
+    /// Waits for a sender instead of starting right away.
+    pub fn standby(&mut self) {
+        if matches!(self.state, ReceiverState::Stopped | ReceiverState::Failed { .. }) {
+            self.state = ReceiverState::Standby;
+        }
+    }
+
+    /// Shuts the process down after a session and returns to standby.
+    pub fn idle(&mut self) {
+        if let ReceiverState::Running { .. } = self.state {
+            self.end_sessions();
+            self.state = ReceiverState::Stopping;
+            self.resume_standby = true;
+            self.backend.stop();
+        }
+    }
+
+    /// Stops the running process and starts a new one with the current
+    /// settings, disconnecting everyone.
+    pub fn restart(&mut self) {
+        if let ReceiverState::Running { .. } = self.state {
+            self.end_sessions();
+            self.state = ReceiverState::Stopping;
+            self.restart = true;
+            self.backend.stop();
+        }
+    }
+
+    /// Whether the process is being replaced through [`Receiver::restart`].
+    pub fn is_restarting(&self) -> bool {
+        self.restart
+    }
+
+    /// Stops the receiver if it is on. A failed receiver is reset to stopped.
+    pub fn stop(&mut self) {
+        self.resume_standby = false;
+        self.restart = false;
+        match self.state {
+            ReceiverState::Starting => {
+                // Nothing to wait for if the launch never got to spawn the process
+                if let Some(starting) = self.starting.take() {
+                    starting.abort();
+                }
+                self.backend.stop();
+                self.state = ReceiverState::Stopped;
+            }
+            ReceiverState::Running { .. } => {
+                self.end_sessions();
+                self.state = ReceiverState::Stopping;
+                self.backend.stop();
+            }
+            ReceiverState::Standby | ReceiverState::Failed { .. } => {
+                self.state = ReceiverState::Stopped
+            }
+            ReceiverState::Stopped | ReceiverState::Stopping => {}
+        }
+    }
+
+    /// Applies the result of backend work.
+    pub fn update(&mut self, event: ReceiverEvent) -> Task<ReceiverEvent> {
+        match event {
+            ReceiverEvent::Started { generation, .. } | ReceiverEvent::Backend { generation, .. }
+                if generation != self.generation =>
+            {
+                Task::none()
+            }
+            ReceiverEvent::Started { result, .. } => {
+                self.starting = None;
+                if self.state != ReceiverState::Starting {
+                    return Task::none();
+                }
+
+                match result {
+                    Ok(()) => {
+                        self.state = ReceiverState::Running {
+                            clients: Vec::new(),
+                        };
+                        let generation = self.generation;
+                        Task::stream(
+                            self.backend
+                                .events()
+                                .map(move |event| ReceiverEvent::Backend { generation, event }),
+                        )
+                    }
+                    Err(reason) => {
+                        eprintln!("Failed to start UXPlay: {}", reason);
+                        self.state = ReceiverState::Failed { reason };
+                        Task::none()
+                    }
+                }
+            }
+            ReceiverEvent::Backend { event, .. } => {
+                let restart = self.restart
+                    && self.state == ReceiverState::Stopping
+                    && matches!(event, BackendEvent::Exited(..));
+                self.on_backend_event(event);
+                if restart {
+                    self.restart = false;
+                    return self.start();
+                }
+                Task::none()
+            }
+        }
+    }
+
+    fn on_backend_event(&mut self, event: BackendEvent) {
+        match event {
+            BackendEvent::ClientConnected { id, name, model } => {
+                if let ReceiverState::Running { clients } = &mut self.state {
+                    // Senders re-announce themselves when they renegotiate
+                    if !clients.iter().any(|client| client.id == id) {
+                        // Anyone else was dropped for the newcomer
+                        if self.config.concurrent_clients == ConcurrentPolicy::Allow {
+                            let now = Local::now();
+                            self.ended
+                                .extend(clients.drain(..).map(|client| client.into_session(now)));
+                        }
+                        clients.push(Client {
+                            name: self.config.device_name(&id, &name),
+                            id,
+                            announced_name: name,
+                            model,
+                            connected_at: Local::now(),
+                            bytes_at_connect: usage::network_bytes(),
+                        });
+                    }
+                }
+            }
+            BackendEvent::ClientsDisconnected => self.end_sessions(),
+            // Only of interest to the applet
+            BackendEvent::Ready
+            | BackendEvent::FramesDropped
+            | BackendEvent::PacketsResent
+            | BackendEvent::AudioFormat(_)
+            | BackendEvent::Pin(_) => {}
+            BackendEvent::Exited(code, stderr) => match self.state {
+                ReceiverState::Stopping if self.resume_standby => {
+                    self.resume_standby = false;
+                    self.state = ReceiverState::Standby;
+                }
+                ReceiverState::Stopping => self.state = ReceiverState::Stopped,
+                ReceiverState::Running { .. } => {
+                    self.end_sessions();
+                    let reason = AirTrayError::ProcessExited { code, stderr };
+                    eprintln!("{}", reason);
+                    self.state = ReceiverState::Failed { reason };
+                }
+                _ => {}
+            },
+        }
+    }
+}
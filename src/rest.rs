@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! An HTTP API controlling the receiver, e.g. from a tablet on the wall of a
+//! meeting room, with a page doing just that at `/`. Only this computer can
+//! reach it unless other devices are allowed, and every request needs the
+//! token, as `?token=` or in an `Authorization: Bearer` header.
+//!
+//! - `GET /status`: state, connected senders and the status line
+//! - `GET /clients`: names of the connected senders
+//! - `GET /settings`: the receiver's settings, without secrets
+//! - `POST /enable`, `POST /disable`: turn the receiver on or off
+
+use std::io;
+use std::net::IpAddr;
+use std::sync::{LazyLock, Mutex};
+
+use cosmic::iced::futures::channel::mpsc;
+use cosmic::iced::futures::{SinkExt, Stream};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::companion;
+use crate::config::Config;
+use crate::dbus::service::{self, Request};
+
+/// Settings `GET /settings` answers with.
+static SETTINGS: LazyLock<Mutex<Option<Config>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Where and how the API is served.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Api {
+    pub port: u16,
+    pub token: String,
+    /// Listen on all interfaces rather than only localhost.
+    pub lan: bool,
+}
+
+/// Address of the control page, with the token. `address` is this
+/// machine's address on the LAN.
+pub fn url(api: &Api, address: IpAddr) -> String {
+    let host = if api.lan {
+        address.to_string()
+    } else {
+        String::from("localhost")
+    };
+    format!("http://{}:{}/?token={}", host, api.port, api.token)
+}
+
+/// Has `GET /settings` answer with `config`.
+pub fn publish_settings(config: &Config) {
+    let Ok(mut settings) = SETTINGS.lock() else {
+        return;
+    };
+    if settings.as_ref() != Some(config) {
+        *settings = Some(config.clone());
+    }
+}
+
+/// Listens for as long as the stream is polled, yielding the requests to
+/// turn the receiver on or off.
+pub fn serve(api: Api) -> impl Stream<Item = Request> {
+    cosmic::iced::stream::channel(16, move |requests| async move {
+        let address = if api.lan { "0.0.0.0" } else { "127.0.0.1" };
+        let listener = match TcpListener::bind((address, api.port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to listen on port {}: {}", api.port, e);
+                return;
+            }
+        };
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let (token, requests) = (api.token.clone(), requests.clone());
+                    tokio::spawn(async move {
+                        if let Err(e) = handle(stream, &token, requests).await {
+                            eprintln!("API request failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => eprintln!("Failed to accept connection: {}", e),
+            }
+        }
+    })
+}
+
+/// Answers one request.
+async fn handle(
+    stream: TcpStream,
+    token: &str,
+    mut requests: mpsc::Sender<Request>,
+) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    let mut bearer = String::new();
+
+    // Bounded like the companion endpoint's, as nobody is authorized yet
+    let head = async {
+        let mut head = (&mut stream).take(companion::MAX_HEAD);
+        head.read_line(&mut line).await?;
+        let mut header = String::new();
+        loop {
+            header.clear();
+            if head.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("authorization") {
+                    bearer = value.trim().strip_prefix("Bearer ").unwrap_or_default().to_string();
+                }
+            }
+        }
+        io::Result::Ok(head.limit() > 0)
+    };
+    let complete = tokio::time::timeout(companion::HEAD_TIMEOUT, head).await;
+    let stream = stream.get_mut();
+    match complete {
+        Ok(Ok(true)) => {}
+        Ok(Ok(false)) => return respond(stream, "400 Bad Request", None).await,
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return respond(stream, "408 Request Timeout", None).await,
+    }
+
+    // "POST /enable?token=0123 HTTP/1.1"
+    let mut parts = line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let authorized = companion::token_matches(&bearer, token)
+        || query
+            .split('&')
+            .filter_map(|pair| pair.strip_prefix("token="))
+            .any(|given| companion::token_matches(given, token));
+
+    if !authorized {
+        return respond(stream, "403 Forbidden", None).await;
+    }
+    match (method, path) {
+        ("GET", "/") => {
+            let page = page(token);
+            respond(stream, "200 OK", Some(("text/html; charset=utf-8", &page))).await
+        }
+        ("GET", "/status") => {
+            let status = service::current();
+            let body = json!({
+                "state": status.state,
+                "clients": status.clients,
+                "status": status.text,
+            });
+            respond_json(stream, &body).await
+        }
+        ("GET", "/clients") => respond_json(stream, &json!(service::current().clients)).await,
+        ("GET", "/settings") => {
+            let settings = SETTINGS.lock().ok().and_then(|settings| settings.clone());
+            respond_json(stream, &settings.map(|config| settings_json(&config)).into()).await
+        }
+        ("POST", "/enable") => {
+            let _ = requests.send(Request::Start).await;
+            respond(stream, "204 No Content", None).await
+        }
+        ("POST", "/disable") => {
+            let _ = requests.send(Request::Stop).await;
+            respond(stream, "204 No Content", None).await
+        }
+        _ => respond(stream, "404 Not Found", None).await,
+    }
+}
+
+/// The settings worth showing on a control page. Secrets like the lock PIN
+/// or the MQTT password are left out.
+fn settings_json(config: &Config) -> Value {
+    json!({
+        "name": config.receiver_name(),
+        "profile": config.profile,
+        "wake_on_demand": config.wake_on_demand,
+        "low_latency": config.low_latency,
+        "concurrent_clients": config.concurrent_clients,
+        "schedule": config.schedule,
+        "audio": config.audio,
+        "video": config.video,
+    })
+}
+
+async fn respond_json(stream: &mut TcpStream, body: &Value) -> io::Result<()> {
+    let body = body.to_string();
+    respond(stream, "200 OK", Some(("application/json", &body))).await
+}
+
+/// Writes a response with `status` and an optional content type and body.
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    body: Option<(&str, &str)>,
+) -> io::Result<()> {
+    let (content_type, body) = body.unwrap_or(("text/plain", ""));
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await
+}
+
+/// A page with the receiver's status and buttons to turn it on and off.
+fn page(token: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><title>AirTray</title></head>
+<body style="font-family: sans-serif; padding: 2em; text-align: center">
+<h1 id="status">…</h1>
+<p id="clients"></p>
+<button onclick="send('enable')" style="font-size: 2em">On</button>
+<button onclick="send('disable')" style="font-size: 2em">Off</button>
+<script>
+const headers = {{ Authorization: "Bearer {token}" }};
+async function refresh() {{
+  const status = await (await fetch("/status", {{ headers }})).json();
+  document.getElementById("status").textContent = status.status;
+  document.getElementById("clients").textContent = status.clients.join(", ");
+}}
+async function send(action) {{
+  await fetch("/" + action, {{ method: "POST", headers }});
+  refresh();
+}}
+refresh();
+setInterval(refresh, 2000);
+</script>
+</body>
+</html>
+"#
+    )
+}
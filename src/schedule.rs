@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Availability windows: the receiver is only on during the configured hours.
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveTime};
+
+use crate::config::ScheduleConfig;
+
+impl ScheduleConfig {
+    /// Whether the receiver should be available at `now`. A window ending
+    /// before it starts runs past midnight, into the next day.
+    pub fn is_open(&self, now: DateTime<Local>) -> bool {
+        let time = now.time();
+        let today = self.on(now);
+
+        if self.start <= self.end {
+            today && self.start <= time && time < self.end
+        } else {
+            let yesterday = now
+                .checked_sub_days(Days::new(1))
+                .is_some_and(|yesterday| self.on(yesterday));
+            (today && time >= self.start) || (yesterday && time < self.end)
+        }
+    }
+
+    /// The next time the receiver is switched on or off after `now`.
+    pub fn next_change(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let open = self.is_open(now);
+        let today = now.date_naive();
+
+        // Every boundary in the coming week, so a week without open days ends the search
+        let mut boundaries: Vec<_> = (0..=7)
+            .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+            .flat_map(|date| [self.start, self.end].map(|time| date.and_time(time)))
+            .filter_map(|time| time.and_local_timezone(Local).earliest())
+            .filter(|time| *time > now)
+            .collect();
+        boundaries.sort();
+
+        boundaries
+            .into_iter()
+            .find(|time| self.is_open(*time) != open)
+    }
+
+    fn on(&self, time: DateTime<Local>) -> bool {
+        self.days
+            .contains(&(time.weekday().num_days_from_monday() as u8))
+    }
+}
+
+/// Parses a time typed as "HH:MM".
+pub fn parse_time(input: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(input.trim(), "%H:%M").ok()
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Short sounds when senders connect and leave, for when the screen showing
+//! the notification is out of sight.
+
+use std::path::Path;
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+use crate::host;
+
+/// Plays `sound`, either a name from the freedesktop sound theme such as
+/// "device-added", or the path of a sound file.
+pub async fn play(sound: String) {
+    if sound.is_empty() {
+        return;
+    }
+
+    let mut command = Command::from(host::command("canberra-gtk-play"));
+    if Path::new(&sound).is_absolute() {
+        command.arg("--file").arg(&sound);
+    } else {
+        command.arg("--id").arg(&sound);
+    }
+    command
+        .args(["--description", "AirTray"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    match command.status().await {
+        Ok(status) if !status.success() => eprintln!("Failed to play sound {}: {}", sound, status),
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to play sound {}: {}", sound, e),
+    }
+}
@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! How a bitrate limit lowers the quality asked of senders.
+
+use crate::config::{Codec, StreamQuality};
+use crate::video;
+
+fn quality(resolution: &str, fps: u32, codec: Codec) -> StreamQuality {
+    StreamQuality {
+        resolution: resolution.to_string(),
+        fps,
+        codec,
+    }
+}
+
+#[test]
+fn zero_bitrate_means_unlimited() {
+    for requested in [quality("3840x2160", 60, Codec::H264), quality("", 0, Codec::H265)] {
+        assert_eq!(video::within_bitrate(&requested, 0), requested);
+    }
+}
+
+#[test]
+fn quality_within_the_limit_is_kept() {
+    let requested = quality("1920x1080", 30, Codec::H264);
+    assert_eq!(video::within_bitrate(&requested, 100), requested);
+
+    // UXPlay's defaults, left for it to fill in
+    let requested = quality("", 0, Codec::H264);
+    assert_eq!(video::within_bitrate(&requested, 10), requested);
+}
+
+#[test]
+fn frame_rate_is_lowered_before_the_resolution() {
+    let requested = quality("1920x1080", 60, Codec::H264);
+    assert_eq!(video::within_bitrate(&requested, 10), quality("1920x1080", 30, Codec::H264));
+}
+
+#[test]
+fn resolution_is_lowered_until_it_fits() {
+    let requested = quality("3840x2160", 30, Codec::H264);
+    assert_eq!(video::within_bitrate(&requested, 3), quality("1280x720", 30, Codec::H264));
+    // H.265 needs fewer bits for the same picture
+    let requested = quality("3840x2160", 30, Codec::H265);
+    assert_eq!(video::within_bitrate(&requested, 3), quality("1600x900", 30, Codec::H265));
+}
+
+#[test]
+fn smallest_limit_still_gives_a_picture() {
+    let requested = quality("1920x1080", 60, Codec::H264);
+    assert_eq!(video::within_bitrate(&requested, 1), quality("640x360", 30, Codec::H264));
+}
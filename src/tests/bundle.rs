@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! What debug bundles give away.
+
+use crate::config::{Config, Profile};
+use crate::debug;
+
+#[test]
+fn bundled_settings_have_no_secrets() {
+    let mut config = Config::default();
+    config.lock.pin = String::from("secret-lock-pin");
+    config.companion.token = String::from("secret-companion-token");
+    config.integrations.rest.token = String::from("secret-rest-token");
+    config.integrations.mqtt.password = String::from("secret-mqtt-password");
+    config.profiles.push(Profile {
+        name: String::from("Room"),
+        pin: String::from("secret-profile-pin"),
+        ..Default::default()
+    });
+
+    let settings = debug::settings(&config);
+    assert!(!settings.contains("secret"), "{settings}");
+    assert!(settings.contains("<redacted>"));
+}
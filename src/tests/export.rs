@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The session history as exported for spreadsheets.
+
+use std::collections::BTreeMap;
+
+use chrono::{Local, TimeZone};
+
+use crate::history::{self, ExportFormat, Session};
+
+use super::temp_path;
+
+fn session(device_id: &str, device_name: &str, model: &str) -> Session {
+    Session {
+        device_id: device_id.to_string(),
+        device_name: device_name.to_string(),
+        model: model.to_string(),
+        start: Local.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap(),
+        end: Local.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap(),
+        bytes: 1024,
+    }
+}
+
+#[test]
+fn csv_quotes_fields_with_commas_quotes_and_line_breaks() {
+    let sessions = [
+        session("AA:BB", "Plain", "iPhone"),
+        session("CC:DD", "Bob's \"iPad\"", "iPad13,4"),
+        session("EE:FF", "Two\nlines", "Mac"),
+    ];
+    let nicknames = BTreeMap::from([(String::from("AA:BB"), String::from("Front, desk"))]);
+
+    let dir = temp_path("export");
+    let path = history::export(&sessions, &nicknames, ExportFormat::Csv, &dir).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let (start, end) = (sessions[0].start.to_rfc3339(), sessions[0].end.to_rfc3339());
+    let expected = format!(
+        "device_id,device,model,start,end,seconds,bytes\n\
+         AA:BB,\"Front, desk\",iPhone,{start},{end},1800,1024\n\
+         CC:DD,\"Bob's \"\"iPad\"\"\",\"iPad13,4\",{start},{end},1800,1024\n\
+         EE:FF,\"Two\nlines\",Mac,{start},{end},1800,1024\n"
+    );
+    assert_eq!(text, expected);
+}
+
+#[test]
+fn json_has_an_object_per_session() {
+    let sessions = [session("AA:BB", "Bob's \"iPad\"", "iPad13,4")];
+
+    let dir = temp_path("export");
+    let path = history::export(&sessions, &BTreeMap::new(), ExportFormat::Json, &dir).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let exported: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(exported[0]["device"], "Bob's \"iPad\"");
+    assert_eq!(exported[0]["model"], "iPad13,4");
+    assert_eq!(exported[0]["seconds"], 1800);
+    assert_eq!(exported[0]["bytes"], 1024);
+}
@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Files received from the phone, as they land on disk.
+
+use crate::companion::files;
+
+use super::temp_path;
+
+#[tokio::test]
+async fn received_files_never_replace_each_other() {
+    let dir = temp_path("files");
+    let first = files::receive(&mut &b"first"[..], 5, &dir, "photo.jpg").await.unwrap();
+    let second = files::receive(&mut &b"second"[..], 6, &dir, "photo.jpg").await.unwrap();
+
+    assert_eq!(first, dir.join("photo.jpg"));
+    assert_eq!(second, dir.join("photo (2).jpg"));
+    assert_eq!(std::fs::read(&first).unwrap(), b"first");
+    assert_eq!(std::fs::read(&second).unwrap(), b"second");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn cut_short_uploads_leave_no_file() {
+    let dir = temp_path("files");
+    let result = files::receive(&mut &b"half"[..], 8, &dir, "video.mp4").await;
+
+    assert!(result.is_err());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
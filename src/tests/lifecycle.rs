@@ -0,0 +1,416 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use cosmic::Application;
+
+use crate::app::{AirTray, Message};
+use crate::backend::{BackendEvent, MockBackend};
+use crate::error::AirTrayError;
+use crate::history::History;
+use crate::receiver::{ReceiverEvent, ReceiverState};
+
+use super::temp_path;
+
+/// Builds an applet wired to a fresh mock, returning both.
+fn applet() -> (AirTray, MockBackend) {
+    let (app, backend, _history) = applet_with_history();
+    (app, backend)
+}
+
+/// Like [`applet`], also returning the (initially empty) history it records into.
+fn applet_with_history() -> (AirTray, MockBackend, History) {
+    let backend = MockBackend::new();
+    let history = History::at(temp_path("history.jsonl"));
+    let app = AirTray::with_backend(Box::new(backend.clone()), history.clone());
+    (app, backend, history)
+}
+
+/// Feeds a scripted sequence of messages to the applet.
+fn run(app: &mut AirTray, messages: impl IntoIterator<Item = Message>) {
+    for message in messages {
+        let _ = app.update(message);
+    }
+}
+
+/// The launch task of the current process completing.
+fn started(app: &AirTray, result: Result<(), AirTrayError>) -> Message {
+    Message::Receiver(ReceiverEvent::Started {
+        generation: app.receiver_generation(),
+        result,
+    })
+}
+
+fn not_found() -> AirTrayError {
+    AirTrayError::BinaryNotFound {
+        binary: String::from("uxplay"),
+    }
+}
+
+/// The current process exiting.
+fn exited(app: &AirTray, code: Option<i32>) -> Message {
+    Message::Receiver(ReceiverEvent::Backend {
+        generation: app.receiver_generation(),
+        event: BackendEvent::Exited(code, Vec::new()),
+    })
+}
+
+/// Something the running receiver reports.
+fn backend_event(app: &AirTray, event: BackendEvent) -> Message {
+    Message::Receiver(ReceiverEvent::Backend {
+        generation: app.receiver_generation(),
+        event,
+    })
+}
+
+fn phone() -> BackendEvent {
+    BackendEvent::ClientConnected {
+        id: String::from("12:34:56:78:9A:BC"),
+        name: String::from("John's iPhone"),
+        model: String::from("iPhone14,2"),
+    }
+}
+
+/// Turns the receiver on and completes the launch.
+fn enable(app: &mut AirTray) {
+    run(app, [Message::ToggleAirPlay(true)]);
+    let started = started(app, Ok(()));
+    run(app, [started]);
+}
+
+/// Turns the receiver off and lets the process exit.
+fn disable(app: &mut AirTray) {
+    run(app, [Message::ToggleAirPlay(false)]);
+    let exited = exited(app, Some(0));
+    run(app, [exited]);
+}
+
+#[test]
+fn enabling_spawns_once() {
+    let (mut app, backend) = applet();
+    run(&mut app, [Message::ToggleAirPlay(true)]);
+
+    assert_eq!(backend.state().spawns, 1);
+    assert_eq!(app.receiver_state(), &ReceiverState::Starting);
+    assert!(app.airplay_enabled());
+}
+
+#[test]
+fn repeated_enable_does_not_double_spawn() {
+    let (mut app, backend) = applet();
+    run(
+        &mut app,
+        [Message::ToggleAirPlay(true), Message::ToggleAirPlay(true)],
+    );
+    let started = started(&app, Ok(()));
+    run(&mut app, [started, Message::ToggleAirPlay(true)]);
+
+    assert_eq!(backend.state().spawns, 1);
+}
+
+#[test]
+fn disabling_kills_the_receiver() {
+    let (mut app, backend) = applet();
+    enable(&mut app);
+    run(&mut app, [Message::ToggleAirPlay(false)]);
+
+    assert_eq!(app.receiver_state(), &ReceiverState::Stopping);
+    assert!(!app.airplay_enabled());
+
+    let exited = exited(&app, Some(0));
+    run(&mut app, [exited]);
+
+    let state = backend.state();
+    assert_eq!(state.kills, 1);
+    assert!(!state.running);
+    assert_eq!(app.receiver_state(), &ReceiverState::Stopped);
+}
+
+#[test]
+fn disabling_while_stopped_is_a_no_op() {
+    let (mut app, backend) = applet();
+    run(&mut app, [Message::ToggleAirPlay(false)]);
+
+    let state = backend.state();
+    assert_eq!(state.spawns, 0);
+    assert_eq!(state.kills, 0);
+}
+
+#[test]
+fn disabling_while_starting_cancels_the_launch() {
+    let (mut app, backend) = applet();
+    run(
+        &mut app,
+        [Message::ToggleAirPlay(true), Message::ToggleAirPlay(false)],
+    );
+
+    assert_eq!(backend.state().kills, 1);
+    assert_eq!(app.receiver_state(), &ReceiverState::Stopped);
+
+    // A launch that still completes afterwards must not revive the receiver
+    let started = started(&app, Ok(()));
+    run(&mut app, [started]);
+    assert_eq!(app.receiver_state(), &ReceiverState::Stopped);
+}
+
+#[test]
+fn enabling_while_stopping_waits_for_the_exit() {
+    let (mut app, backend) = applet();
+    enable(&mut app);
+    run(
+        &mut app,
+        [Message::ToggleAirPlay(false), Message::ToggleAirPlay(true)],
+    );
+
+    assert_eq!(backend.state().spawns, 1);
+    assert_eq!(app.receiver_state(), &ReceiverState::Stopping);
+}
+
+#[test]
+fn toggling_repeatedly_leaves_nothing_running() {
+    let (mut app, backend) = applet();
+    for _ in 0..5 {
+        enable(&mut app);
+        disable(&mut app);
+    }
+
+    let state = backend.state();
+    assert_eq!(state.spawns, 5);
+    assert_eq!(state.kills, 5);
+    assert!(!state.running);
+}
+
+#[test]
+fn failed_spawn_turns_the_toggle_back_off() {
+    let (mut app, backend) = applet();
+    backend.state().fail_next_spawn = true;
+    run(&mut app, [Message::ToggleAirPlay(true)]);
+    let started = started(&app, Err(not_found()));
+    run(&mut app, [started]);
+
+    assert!(!app.airplay_enabled());
+    assert_eq!(
+        app.receiver_state(),
+        &ReceiverState::Failed {
+            reason: not_found()
+        }
+    );
+
+    // The next attempt must be able to start the receiver again
+    enable(&mut app);
+    assert_eq!(backend.state().spawns, 1);
+    assert!(app.airplay_enabled());
+}
+
+#[test]
+fn crashed_receiver_is_noticed_and_can_be_restarted() {
+    let (mut app, backend) = applet();
+    enable(&mut app);
+    backend.crash();
+    let exited = exited(&app, Some(1));
+    run(&mut app, [exited]);
+
+    assert!(!app.airplay_enabled());
+    assert_eq!(
+        app.receiver_state(),
+        &ReceiverState::Failed {
+            reason: AirTrayError::ProcessExited {
+                code: Some(1),
+                stderr: Vec::new()
+            }
+        }
+    );
+
+    enable(&mut app);
+    let state = backend.state();
+    assert_eq!(state.spawns, 2);
+    assert!(state.running);
+}
+
+#[test]
+fn receiver_exiting_before_it_is_ready_is_retried() {
+    let (mut app, backend) = applet();
+    enable(&mut app);
+    backend.crash();
+    let exited = exited(&app, Some(1));
+    run(&mut app, [exited, Message::RetryStart]);
+
+    assert_eq!(backend.state().spawns, 2);
+    assert_eq!(app.receiver_state(), &ReceiverState::Starting);
+}
+
+#[test]
+fn retry_after_turning_off_does_nothing() {
+    let (mut app, backend) = applet();
+    enable(&mut app);
+    backend.crash();
+    let exited = exited(&app, Some(1));
+    run(&mut app, [exited, Message::ToggleAirPlay(false), Message::RetryStart]);
+
+    assert_eq!(backend.state().spawns, 1);
+    assert_eq!(app.receiver_state(), &ReceiverState::Stopped);
+}
+
+#[test]
+fn exit_of_a_previous_process_is_ignored() {
+    let (mut app, _backend) = applet();
+    enable(&mut app);
+    let stale = exited(&app, Some(0));
+    disable(&mut app);
+    enable(&mut app);
+    run(&mut app, [stale]);
+
+    assert_eq!(
+        app.receiver_state(),
+        &ReceiverState::Running {
+            clients: Vec::new()
+        }
+    );
+}
+
+#[test]
+fn spawn_uses_current_settings() {
+    let (mut app, backend) = applet();
+    run(
+        &mut app,
+        [
+            Message::NameChanged(String::from("Living Room")),
+            Message::ToggleAirPlay(true),
+        ],
+    );
+
+    let state = backend.state();
+    let config = state.last_config.as_ref().expect("receiver was spawned");
+    assert_eq!(config.name, "Living Room");
+}
+
+#[test]
+fn enabled_receiver_is_running_without_clients() {
+    let (mut app, _backend) = applet();
+    enable(&mut app);
+
+    assert_eq!(
+        app.receiver_state(),
+        &ReceiverState::Running {
+            clients: Vec::new()
+        }
+    );
+}
+
+#[test]
+fn stopping_a_failed_receiver_resets_it() {
+    let (mut app, backend) = applet();
+    backend.state().fail_next_spawn = true;
+    run(&mut app, [Message::ToggleAirPlay(true)]);
+    let started = started(&app, Err(not_found()));
+    run(&mut app, [started, Message::ToggleAirPlay(false)]);
+
+    assert_eq!(app.receiver_state(), &ReceiverState::Stopped);
+    assert_eq!(backend.state().kills, 0);
+}
+
+#[test]
+fn connected_clients_are_tracked_once() {
+    let (mut app, _backend) = applet();
+    enable(&mut app);
+    let connected = backend_event(&app, phone());
+    run(&mut app, [connected.clone(), connected]);
+
+    match app.receiver_state() {
+        ReceiverState::Running { clients } => {
+            assert_eq!(clients.len(), 1);
+            assert_eq!(clients[0].name, "John's iPhone");
+        }
+        state => panic!("unexpected state {state:?}"),
+    }
+}
+
+#[test]
+fn finished_sessions_are_recorded() {
+    let (mut app, _backend, history) = applet_with_history();
+    enable(&mut app);
+    let connected = backend_event(&app, phone());
+    let disconnected = backend_event(&app, BackendEvent::ClientsDisconnected);
+    run(&mut app, [connected, disconnected]);
+
+    let sessions = history.load().unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].device_id, "12:34:56:78:9A:BC");
+    assert_eq!(sessions[0].model, "iPhone14,2");
+}
+
+#[test]
+fn stopping_ends_open_sessions() {
+    let (mut app, _backend, history) = applet_with_history();
+    enable(&mut app);
+    let connected = backend_event(&app, phone());
+    run(&mut app, [connected]);
+    disable(&mut app);
+
+    assert_eq!(history.load().unwrap().len(), 1);
+}
+
+#[test]
+fn wake_on_demand_waits_for_a_sender() {
+    let (mut app, backend) = applet();
+    run(
+        &mut app,
+        [
+            Message::ToggleWakeOnDemand(true),
+            Message::ToggleAirPlay(true),
+        ],
+    );
+
+    assert_eq!(app.receiver_state(), &ReceiverState::Standby);
+    assert!(app.airplay_enabled());
+    assert_eq!(backend.state().spawns, 0);
+
+    run(&mut app, [Message::Wake]);
+    assert_eq!(backend.state().spawns, 1);
+}
+
+#[test]
+fn wake_on_demand_returns_to_standby_after_a_session() {
+    let (mut app, backend) = applet();
+    run(
+        &mut app,
+        [
+            Message::ToggleWakeOnDemand(true),
+            Message::ToggleAirPlay(true),
+            Message::Wake,
+        ],
+    );
+    let started = started(&app, Ok(()));
+    let connected = backend_event(&app, phone());
+    let disconnected = backend_event(&app, BackendEvent::ClientsDisconnected);
+    run(&mut app, [started, connected, disconnected]);
+    assert_eq!(backend.state().kills, 1);
+
+    let exited = exited(&app, Some(0));
+    run(&mut app, [exited]);
+    assert_eq!(app.receiver_state(), &ReceiverState::Standby);
+}
+
+#[test]
+fn device_profile_restarts_the_receiver_for_it() {
+    let (mut app, backend) = applet();
+    run(
+        &mut app,
+        [
+            Message::AddProfile,
+            Message::ProfileResolutionChanged(String::from("1280x720")),
+            Message::SelectProfile(0),
+            Message::SelectDeviceProfile(String::from("12:34:56:78:9A:BC"), 1),
+        ],
+    );
+    enable(&mut app);
+    let connected = backend_event(&app, phone());
+    run(&mut app, [connected]);
+    assert_eq!(backend.state().kills, 1);
+    assert_eq!(app.receiver_state(), &ReceiverState::Stopping);
+
+    let exited = exited(&app, Some(0));
+    run(&mut app, [exited]);
+    let state = backend.state();
+    assert_eq!(state.spawns, 2);
+    let config = state.last_config.as_ref().unwrap();
+    assert_eq!(config.active_profile().unwrap().resolution, "1280x720");
+}
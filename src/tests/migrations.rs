@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Settings as every version of the applet stored them, loaded through the
+//! migrations into today's [`Config`].
+
+use std::path::PathBuf;
+
+use cosmic::cosmic_config::CosmicConfigEntry;
+
+use crate::config::migrations::{self, Entries};
+use crate::config::{Codec, Config, LockMethod, ScaleMethod, ScalingConfig};
+
+/// The settings stored by `version`. Those of all versions are the same
+/// settings, written as that version did.
+fn fixture(version: u64) -> Entries {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/fixtures/config")
+        .join(format!("v{version}"));
+    migrations::read(&dir).unwrap()
+}
+
+/// The settings in `entries`, read field by field like cosmic-config would.
+fn config(entries: &Entries) -> Config {
+    let fields: Vec<String> = entries
+        .iter()
+        .map(|(key, value)| format!("{key}: {}", value.trim()))
+        .collect();
+    ron::from_str(&format!("({})", fields.join(", "))).unwrap()
+}
+
+#[test]
+fn every_config_version_loads_into_the_current_one() {
+    for version in 1..=Config::VERSION {
+        let mut entries = fixture(version);
+        migrations::migrate(&mut entries, version);
+        let config = config(&entries);
+
+        assert_eq!(config.name, "Living Room", "v{version}");
+        assert!(config.auto_rename, "v{version}");
+        assert!(config.low_latency, "v{version}");
+
+        let profile = config.active_profile().unwrap();
+        assert_eq!(profile.resolution, "3840x2160", "v{version}");
+        assert_eq!(profile.codec, Codec::H265, "v{version}");
+        assert_eq!(profile.pin, "1234", "v{version}");
+        assert_eq!(profile.volume.percent, 80, "v{version}");
+
+        assert_eq!(
+            config.audio.secondary_sink.as_deref(),
+            Some("alsa_output.hdmi-stereo"),
+            "v{version}"
+        );
+        assert!(config.audio.pause_local_media, "v{version}");
+        assert_eq!(config.video.output, "HDMI-A-1", "v{version}");
+        assert_eq!(config.video.quality.fps, 60, "v{version}");
+
+        assert!(config.is_blocked("12:34:56:78:9A:BC"), "v{version}");
+        assert_eq!(config.device_name("12:34:56:78:9A:BC", "iPad"), "Old iPad", "v{version}");
+        assert_eq!(config.lock.method, LockMethod::Pin, "v{version}");
+    }
+}
+
+#[test]
+fn removed_lanczos_scaling_loads_as_bilinear() {
+    let scaling: ScalingConfig = ron::from_str("(method: Lanczos, integer: true)").unwrap();
+    assert_eq!(scaling.method, ScaleMethod::Bilinear);
+    assert!(scaling.integer);
+}
@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Tests that drive the applet through `AirTray::update` against a
+//! `MockBackend`, the way the COSMIC runtime would, that load the settings
+//! of older versions, and of the parsers and writers of files.
+
+mod bitrate;
+mod bundle;
+mod export;
+mod files;
+mod lifecycle;
+mod migrations;
+mod schedule;
+mod uxplayrc;
+mod validation;
+mod webhook;
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A path in the temp directory unique to this test run, so tests never
+/// touch the user's real data.
+pub fn temp_path(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("airtray-test-{}-{n}-{name}", std::process::id()))
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Availability windows, including ones running past midnight.
+
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+
+use crate::config::ScheduleConfig;
+use crate::schedule;
+
+/// A time on the week of Monday 2026-10-12, which has no daylight saving change.
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+    Local.with_ymd_and_hms(2026, 10, day, hour, minute, 0).unwrap()
+}
+
+/// Open on Fridays from 22:00 until 06:00 on Saturday.
+fn friday_night() -> ScheduleConfig {
+    ScheduleConfig {
+        enabled: true,
+        days: vec![4],
+        start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+    }
+}
+
+#[test]
+fn times_are_parsed_as_hours_and_minutes() {
+    assert_eq!(schedule::parse_time("09:05"), NaiveTime::from_hms_opt(9, 5, 0));
+    assert_eq!(schedule::parse_time(" 22:30 "), NaiveTime::from_hms_opt(22, 30, 0));
+    assert_eq!(schedule::parse_time("0:00"), NaiveTime::from_hms_opt(0, 0, 0));
+
+    for input in ["", "24:00", "12:60", "noon", "12", "12:00:00"] {
+        assert_eq!(schedule::parse_time(input), None, "{input}");
+    }
+}
+
+#[test]
+fn daytime_window_is_open_between_start_and_end() {
+    let schedule = ScheduleConfig::default();
+
+    assert!(!schedule.is_open(at(12, 8, 59)));
+    assert!(schedule.is_open(at(12, 9, 0)));
+    assert!(!schedule.is_open(at(12, 18, 0)));
+    // Saturday isn't one of the days
+    assert!(!schedule.is_open(at(17, 12, 0)));
+}
+
+#[test]
+fn overnight_window_runs_into_the_next_day() {
+    let schedule = friday_night();
+
+    assert!(!schedule.is_open(at(16, 21, 59)));
+    assert!(schedule.is_open(at(16, 22, 0)));
+    assert!(schedule.is_open(at(17, 5, 59)));
+    assert!(!schedule.is_open(at(17, 6, 0)));
+    assert!(!schedule.is_open(at(17, 23, 0)));
+    // Only the night after a listed day
+    assert!(!schedule.is_open(at(16, 5, 0)));
+}
+
+#[test]
+fn overnight_window_changes_at_its_boundaries() {
+    let schedule = friday_night();
+
+    assert_eq!(schedule.next_change(at(16, 21, 0)), Some(at(16, 22, 0)));
+    assert_eq!(schedule.next_change(at(16, 23, 0)), Some(at(17, 6, 0)));
+    // A week on, skipping the other days
+    assert_eq!(schedule.next_change(at(17, 7, 0)), Some(at(23, 22, 0)));
+}
+
+#[test]
+fn window_without_days_never_opens() {
+    let schedule = ScheduleConfig {
+        days: Vec::new(),
+        ..friday_night()
+    };
+
+    assert!(!schedule.is_open(at(16, 23, 0)));
+    assert_eq!(schedule.next_change(at(16, 21, 0)), None);
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Reading and writing UXPlay's startup file.
+
+use crate::config::{Codec, Config, PortConfig, Profile};
+use crate::uxplayrc::{self, Imported};
+
+#[test]
+fn resolution_is_read_with_and_without_a_frame_rate() {
+    let imported = uxplayrc::parse("-s 1920x1080@60");
+    assert_eq!(imported.profile.resolution, "1920x1080");
+    assert_eq!(imported.profile.fps, 60);
+
+    let imported = uxplayrc::parse("-s 1280x720");
+    assert_eq!(imported.profile.resolution, "1280x720");
+    assert_eq!(imported.profile.fps, 0);
+    assert!(imported.skipped.is_empty());
+}
+
+#[test]
+fn udp_port_is_read_with_and_without_the_protocol() {
+    assert_eq!(uxplayrc::parse("-p udp 6000").udp_port, Some(6000));
+    assert_eq!(uxplayrc::parse("-p 7000").udp_port, Some(7000));
+
+    let imported = uxplayrc::parse("-p udp many");
+    assert_eq!(imported.udp_port, None);
+    assert_eq!(imported.skipped, ["-p udp many"]);
+}
+
+#[test]
+fn options_are_read_without_dashes_around_comments_and_blank_lines() {
+    let text = "\
+# Living room TV
+n Living Room
+
+  -h265
+fps 30
+-pin 1234
+-block 12:34:56:78:9A:BC AA:BB:CC:DD:EE:FF
+-d
+";
+    let imported = uxplayrc::parse(text);
+    assert_eq!(imported.name.as_deref(), Some("Living Room"));
+    assert_eq!(imported.profile.codec, Codec::H265);
+    assert_eq!(imported.profile.fps, 30);
+    assert_eq!(imported.profile.pin, "1234");
+    assert_eq!(imported.blocked, ["12:34:56:78:9A:BC", "AA:BB:CC:DD:EE:FF"]);
+    assert!(imported.debug);
+    assert!(imported.skipped.is_empty());
+}
+
+#[test]
+fn options_without_a_setting_are_kept_as_written() {
+    let imported = uxplayrc::parse("-vdmp 100\n-fps fast\n-nh extra");
+    assert_eq!(imported.skipped, ["-vdmp 100", "-fps fast", "-nh extra"]);
+    assert_eq!(imported.profile, Profile::default());
+}
+
+/// A config with a profile using every option that is read back.
+fn room_config(name: &str) -> Config {
+    Config {
+        name: name.to_string(),
+        ports: PortConfig { udp: 6000 },
+        profile: String::from("Room"),
+        profiles: vec![Profile {
+            name: String::from("Room"),
+            pin: String::from("1234"),
+            resolution: String::from("1920x1080"),
+            fps: 30,
+            codec: Codec::H265,
+            video_pipeline: String::from("glimagesink sync=false"),
+            audio_pipeline: String::from("pulsesink device=hdmi"),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn rendered_options_read_back_as_the_same_settings() {
+    let imported = uxplayrc::parse(&uxplayrc::render(&room_config("Bob's Room")));
+    assert_eq!(
+        imported,
+        Imported {
+            name: Some(String::from("Bob's Room")),
+            profile: Profile {
+                pin: String::from("1234"),
+                resolution: String::from("1920x1080"),
+                fps: 30,
+                codec: Codec::H265,
+                video_pipeline: String::from("glimagesink sync=false"),
+                audio_pipeline: String::from("pulsesink device=hdmi"),
+                ..Default::default()
+            },
+            udp_port: Some(6000),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn command_line_quotes_words_with_spaces_and_quotes() {
+    let text = uxplayrc::render(&room_config("Bob's Room"));
+    let command = text.lines().nth(1).unwrap();
+
+    assert!(command.starts_with("# uxplay -n 'Bob'\\''s Room' -nh -p udp 6000 "));
+    assert!(command.contains(" -as 'pulsesink device=hdmi' "));
+    assert!(command.ends_with(" -vs 'glimagesink sync=false'"));
+}
+
+#[test]
+fn command_line_quotes_every_word_with_shell_syntax() {
+    let mut config = room_config("$(reboot); ls & `id` | *");
+    config.profiles[0].audio_pipeline = String::from("pulsesink;rm");
+    let text = uxplayrc::render(&config);
+    let command = text.lines().nth(1).unwrap();
+
+    assert!(command.starts_with("# uxplay -n '$(reboot); ls & `id` | *' -nh "));
+    assert!(command.contains(" -as 'pulsesink;rm' "));
+    assert!(command.contains(" -pin 1234 "));
+
+    // The option lines keep the name as it is
+    let name = uxplayrc::parse(&text).name;
+    assert_eq!(name.as_deref(), Some("$(reboot); ls & `id` | *"));
+}
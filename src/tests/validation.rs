@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The checks of what UXPlay can be started with, at their limits.
+
+use crate::config::validation;
+use crate::ports::UDP_COUNT;
+
+#[test]
+fn udp_port_must_be_unprivileged_with_room_after_it() {
+    let last = u16::MAX - UDP_COUNT + 1;
+
+    assert!(validation::udp_port(0));
+    assert!(!validation::udp_port(1));
+    assert!(!validation::udp_port(1023));
+    assert!(validation::udp_port(1024));
+    assert!(validation::udp_port(last));
+    assert!(!validation::udp_port(last + 1));
+    assert!(!validation::udp_port(u16::MAX));
+}
+
+#[test]
+fn resolution_must_be_width_x_height() {
+    assert!(validation::resolution(""));
+    assert!(validation::resolution("1920x1080"));
+    assert!(validation::resolution("1x1"));
+    assert!(validation::resolution("4294967295x1"));
+
+    for text in ["0x1080", "1920x0", "4294967296x1", "1920x", "x1080"] {
+        assert!(!validation::resolution(text), "{text}");
+    }
+    for text in ["1920", "1920X1080", "-1920x1080", "1920 x 1080"] {
+        assert!(!validation::resolution(text), "{text}");
+    }
+}
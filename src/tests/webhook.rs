@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The webhook URLs and the requests sent to them.
+
+use crate::webhook::{self, Target};
+
+#[test]
+fn webhook_urls_are_split_into_host_port_and_path() {
+    assert_eq!(
+        webhook::parse_url("http://localhost:5678/webhook/airtray"),
+        Ok(Target {
+            host: "localhost",
+            port: 5678,
+            authority: "localhost:5678",
+            path: "/webhook/airtray",
+        })
+    );
+    assert_eq!(
+        webhook::parse_url("http://nodered.lan"),
+        Ok(Target {
+            host: "nodered.lan",
+            port: 80,
+            authority: "nodered.lan",
+            path: "/",
+        })
+    );
+    assert_eq!(
+        webhook::parse_url("http://[::1]:1880/airplay?room=1"),
+        Ok(Target {
+            host: "::1",
+            port: 1880,
+            authority: "[::1]:1880",
+            path: "/airplay?room=1",
+        })
+    );
+    assert_eq!(webhook::parse_url("http://[fe80::1]").map(|target| target.port), Ok(80));
+}
+
+#[test]
+fn unusable_webhook_urls_are_rejected() {
+    let urls = ["https://n8n.example.com/webhook", "localhost:5678", "http://host:port/", "http:///"];
+    for url in urls {
+        assert!(webhook::parse_url(url).is_err(), "{url}");
+    }
+}
+
+#[test]
+fn webhook_request_posts_json_with_a_matching_length() {
+    let target = webhook::parse_url("http://localhost:5678/hook").unwrap();
+    let body = r#"{"event":"session-started"}"#;
+
+    assert_eq!(
+        webhook::request(&target, body),
+        format!(
+            "POST /hook HTTP/1.1\r\nHost: localhost:5678\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    );
+}
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Samples UXPlay's CPU and memory use from /proc, to tell whether stutter
+//! comes from the receiver or something else, and reads the network
+//! counters sessions' data use is estimated from.
+
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Clock ticks per second in /proc, USER_HZ, which Linux fixes at 100.
+const CLOCK_TICKS: f32 = 100.0;
+
+/// Name of the receiver process.
+const PROCESS: &str = "uxplay";
+
+/// What the receiver used since the previous sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Usage {
+    /// Share of one CPU core in percent, so above 100 when using several.
+    pub cpu: f32,
+    /// Resident memory in MiB.
+    pub memory: u64,
+}
+
+/// Remembers the previous sample to work out CPU use in between.
+#[derive(Debug, Default)]
+pub struct UsageSampler {
+    last: Option<(u32, u64, Instant)>,
+}
+
+impl UsageSampler {
+    /// Current usage of this user's UXPlay process. `None` if it isn't
+    /// running or can't be seen, e.g. from inside a Flatpak sandbox, and on
+    /// the first sample.
+    pub fn sample(&mut self) -> Option<Usage> {
+        let pid = self
+            .last
+            .map(|(pid, ..)| pid)
+            .filter(|&pid| is_receiver(pid))
+            .or_else(find_receiver);
+        let Some(pid) = pid else {
+            self.last = None;
+            return None;
+        };
+
+        let ticks = cpu_ticks(pid)?;
+        let now = Instant::now();
+        let previous = self.last.replace((pid, ticks, now));
+
+        let (_, last_ticks, last_time) = previous.filter(|(last, ..)| *last == pid)?;
+        let elapsed = now.duration_since(last_time).as_secs_f32();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        Some(Usage {
+            cpu: ticks.saturating_sub(last_ticks) as f32 / CLOCK_TICKS / elapsed * 100.0,
+            memory: resident_kib(pid)? / 1024,
+        })
+    }
+
+    /// Forgets the previous sample, e.g. when the receiver stopped.
+    pub fn clear(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Bytes received and sent on all network interfaces but loopback since
+/// they came up. Streams can't be told apart from other traffic there.
+pub fn network_bytes() -> u64 {
+    let Ok(interfaces) = std::fs::read_dir("/sys/class/net") else {
+        return 0;
+    };
+    interfaces
+        .flatten()
+        .filter(|interface| interface.file_name() != "lo")
+        .flat_map(|interface| {
+            let statistics = interface.path().join("statistics");
+            ["rx_bytes", "tx_bytes"].map(|counter| statistics.join(counter))
+        })
+        .filter_map(|path| std::fs::read_to_string(path).ok()?.trim().parse::<u64>().ok())
+        .sum()
+}
+
+fn proc_path(pid: u32) -> PathBuf {
+    PathBuf::from("/proc").join(pid.to_string())
+}
+
+/// Whether `pid` is a UXPlay process of the current user.
+fn is_receiver(pid: u32) -> bool {
+    let path = proc_path(pid);
+    let uid = |path: &std::path::Path| std::fs::metadata(path).map(|meta| meta.uid()).ok();
+    let own = uid(&path).is_some_and(|owner| Some(owner) == uid("/proc/self".as_ref()));
+    own && std::fs::read_to_string(path.join("comm")).is_ok_and(|name| name.trim() == PROCESS)
+}
+
+fn find_receiver() -> Option<u32> {
+    std::fs::read_dir("/proc")
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .find(|&pid| is_receiver(pid))
+}
+
+/// Time spent in user and kernel mode, from /proc/<pid>/stat.
+fn cpu_ticks(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(proc_path(pid).join("stat")).ok()?;
+    // The command name may contain spaces, the fields after it don't; utime
+    // and stime are the 14th and 15th field counting from 1, the name 2nd
+    let mut fields = stat.rsplit_once(')')?.1.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// Resident memory, from /proc/<pid>/status.
+fn resident_kib(pid: u32) -> Option<u64> {
+    std::fs::read_to_string(proc_path(pid).join("status"))
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
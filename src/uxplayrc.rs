@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! UXPlay's own startup file, which standalone setups keep their options in.
+//! Each line holds one option with its arguments, with or without the dash,
+//! e.g. "n Living Room" or "-s 1920x1080@60"; "#" starts a comment.
+
+use std::path::PathBuf;
+
+use chrono::Local;
+
+use crate::audio::snapcast;
+use crate::backend::LOW_LATENCY_AUDIO;
+use crate::config::{validation, Codec, Config, Profile};
+use crate::error::AirTrayError;
+use crate::video;
+
+/// Settings read from a uxplayrc, in AirTray's terms.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Imported {
+    pub name: Option<String>,
+    /// Resolution, frame rate, codec, PIN and sinks, unnamed.
+    pub profile: Profile,
+    pub udp_port: Option<u16>,
+    /// Device ids to refuse.
+    pub blocked: Vec<String>,
+    pub debug: bool,
+    /// Options AirTray has no setting for, as written.
+    pub skipped: Vec<String>,
+}
+
+/// Where UXPlay looks for its startup file: `$UXPLAYRC`, `~/.uxplayrc` or
+/// `~/.config/uxplayrc`, whichever exists first.
+pub fn path() -> Option<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    std::env::var_os("UXPLAYRC")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain([home.join(".uxplayrc"), home.join(".config").join("uxplayrc")])
+        .find(|path| path.is_file())
+}
+
+/// Reads the startup file UXPlay would use.
+pub fn load() -> Result<Imported, AirTrayError> {
+    let path = path().ok_or_else(|| AirTrayError::UxplayrcError(String::from("not found")))?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| AirTrayError::UxplayrcError(format!("{}: {}", path.display(), e)))?;
+    Ok(parse(&text))
+}
+
+/// Writes what [`render`] gives for `config` to the download folder,
+/// returning the path.
+pub fn export(config: &Config) -> Result<PathBuf, AirTrayError> {
+    let path = config.companion.download_dir().join("airtray.uxplayrc");
+    std::fs::create_dir_all(config.companion.download_dir())
+        .and_then(|()| std::fs::write(&path, render(config)))
+        .map_err(|e| AirTrayError::UxplayrcError(format!("{}: {}", path.display(), e)))?;
+    Ok(path)
+}
+
+/// The settings of `config` with the selected profile as a uxplayrc, headed
+/// by the same as a command line. What only works with the applet running,
+/// like the AirTray window, the equalizer or sink delays, is left out.
+pub fn render(config: &Config) -> String {
+    let options = options(config);
+    let command = options
+        .iter()
+        .flatten()
+        .map(|word| shell_quote(word))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut text = format!("# Exported by AirTray. As a command line:\n# uxplay {command}\n");
+    for line in options {
+        text.push_str(&line.join(" "));
+        text.push('\n');
+    }
+    text
+}
+
+/// `word` as the shell reads it back: single-quoted unless it only has
+/// characters without a meaning to the shell, so names and pipelines can't
+/// run anything when the command line is pasted.
+fn shell_quote(word: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./=:,@+%".contains(c);
+    if !word.is_empty() && word.chars().all(plain) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// Options UXPlay would be started with, one per line, in the order the
+/// backend passes them.
+fn options(config: &Config) -> Vec<Vec<String>> {
+    let option = |words: &[&str]| words.iter().map(|word| word.to_string()).collect();
+    let mut options: Vec<Vec<String>> = Vec::new();
+
+    if !config.name.is_empty() {
+        options.push(option(&["-n", &config.name]));
+        options.push(option(&["-nh"]));
+    }
+    if config.debug {
+        options.push(option(&["-d"]));
+    }
+    if config.ports.udp != 0 && validation::udp_port(config.ports.udp) {
+        options.push(option(&["-p", "udp", &config.ports.udp.to_string()]));
+    }
+
+    let profile = config.active_profile();
+    let video_pipeline = profile.map_or("", |profile| profile.video_pipeline.trim());
+    let audio_pipeline = profile.map_or("", |profile| profile.audio_pipeline.trim());
+
+    if !audio_pipeline.is_empty() {
+        options.push(option(&["-as", audio_pipeline]));
+    } else if config.audio.snapcast.enabled {
+        options.push(option(&["-as", &snapcast::audio_sink(&config.audio.snapcast)]));
+    }
+    for id in config.blocked_devices(Local::now()) {
+        options.push(option(&["-block", &id]));
+    }
+    if config.random_pin {
+        options.push(option(&["-pin"]));
+    } else if let Some(pin) = profile.map(|profile| &profile.pin).filter(|pin| !pin.is_empty()) {
+        options.push(option(&["-pin", pin]));
+    }
+
+    if video::uxplay_fullscreen(&config.video) {
+        options.push(option(&["-fs"]));
+    }
+    let quality = video::within_bitrate(&config.stream_quality(), config.video.max_bitrate);
+    if !quality.resolution.is_empty() && validation::resolution(&quality.resolution) {
+        options.push(option(&["-s", &quality.resolution]));
+    }
+    if quality.fps > 0 {
+        options.push(option(&["-fps", &quality.fps.to_string()]));
+    }
+    if quality.codec == Codec::H265 {
+        options.push(option(&["-h265"]));
+    }
+    if config.low_latency {
+        options.push(option(&["-vsync", "no"]));
+        options.push(option(&["-al", LOW_LATENCY_AUDIO]));
+    }
+
+    // The AirTray window and the viewer only exist with the applet running
+    if !video_pipeline.is_empty() {
+        options.push(option(&["-vs", video_pipeline]));
+    } else if video::frame_size(&config.video).is_none() && !config.viewer.enabled {
+        options.push(option(&["-vs", &video::video_sink(&config.video)]));
+    }
+
+    options
+}
+
+/// Maps the options in `text` to settings.
+pub fn parse(text: &str) -> Imported {
+    let mut imported = Imported::default();
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let Some(option) = words.next() else {
+            continue;
+        };
+        let args: Vec<&str> = words.collect();
+        let profile = &mut imported.profile;
+
+        match (option.trim_start_matches('-'), args.as_slice()) {
+            ("n", [_, ..]) => imported.name = Some(args.join(" ")),
+            // AirTray always leaves the hostname out
+            ("nh", []) => {}
+            ("s", [size]) => {
+                let (resolution, fps) = size.split_once('@').unwrap_or((size, ""));
+                profile.resolution = resolution.to_string();
+                if let Ok(fps) = fps.parse() {
+                    profile.fps = fps;
+                }
+            }
+            ("fps", [fps]) => match fps.parse() {
+                Ok(fps) => profile.fps = fps,
+                Err(_) => imported.skipped.push(line.to_string()),
+            },
+            ("h265", []) => profile.codec = Codec::H265,
+            ("pin", [pin]) => profile.pin = pin.to_string(),
+            ("vs", [_, ..]) => profile.video_pipeline = args.join(" "),
+            ("as", [_, ..]) => profile.audio_pipeline = args.join(" "),
+            // "-p udp 6000", or "-p 6000" for the TCP and UDP ports alike
+            ("p", ["udp", port] | [port]) => match port.parse() {
+                Ok(port) => imported.udp_port = Some(port),
+                Err(_) => imported.skipped.push(line.to_string()),
+            },
+            ("block", ids) if !ids.is_empty() => {
+                imported.blocked.extend(ids.iter().map(|id| id.to_string()));
+            }
+            ("d", []) => imported.debug = true,
+            _ => imported.skipped.push(line.to_string()),
+        }
+    }
+
+    imported
+}
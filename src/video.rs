@@ -0,0 +1,307 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Options for the mirror window, opened by UXPlay or drawn by the applet.
+
+use tokio::process::Command;
+
+use crate::config::{Codec, DisplayServer, PictureConfig, StreamQuality, VideoConfig};
+use crate::frames::{self, FrameSize};
+use crate::host;
+
+/// Size of the frames drawn in the embedded mirror window.
+const EMBEDDED_SIZE: FrameSize = FrameSize {
+    width: 1280,
+    height: 720,
+};
+
+/// Resolution UXPlay requests when none is set.
+const DEFAULT_RESOLUTION: &str = "1920x1080";
+
+/// Frame rate UXPlay requests when none is set.
+const DEFAULT_FPS: u32 = 30;
+
+/// GStreamer elements that can decode H.265, any one of which will do.
+const H265_DECODERS: [&str; 5] = [
+    "vah265dec",
+    "vaapih265dec",
+    "nvh265dec",
+    "v4l2slh265dec",
+    "avdec_h265",
+];
+
+/// Built-in bundles of stream quality settings.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum QualityPreset {
+    Low,
+    Balanced,
+    High,
+}
+
+impl QualityPreset {
+    pub const ALL: [Self; 3] = [Self::Low, Self::Balanced, Self::High];
+
+    /// The settings this preset stands for.
+    pub fn quality(self) -> StreamQuality {
+        let (resolution, fps, codec) = match self {
+            Self::Low => ("1280x720", 30, Codec::H264),
+            Self::Balanced => (DEFAULT_RESOLUTION, DEFAULT_FPS, Codec::H264),
+            Self::High => (DEFAULT_RESOLUTION, 60, Codec::H265),
+        };
+        StreamQuality {
+            resolution: String::from(resolution),
+            fps,
+            codec,
+        }
+    }
+
+    /// The preset `quality` amounts to, or `None` if it was customized.
+    /// UXPlay's defaults count as the balanced preset.
+    pub fn matching(quality: &StreamQuality) -> Option<Self> {
+        let resolution = match quality.resolution.as_str() {
+            "" => DEFAULT_RESOLUTION,
+            resolution => resolution,
+        };
+        let fps = match quality.fps {
+            0 => DEFAULT_FPS,
+            fps => fps,
+        };
+
+        Self::ALL.into_iter().find(|preset| {
+            let preset = preset.quality();
+            preset.resolution == resolution && preset.fps == fps && preset.codec == quality.codec
+        })
+    }
+}
+
+/// Bits per pixel per frame senders roughly use for screen content.
+fn bits_per_pixel(codec: Codec) -> f64 {
+    match codec {
+        Codec::H264 => 0.1,
+        Codec::H265 => 0.06,
+    }
+}
+
+/// Resolutions tried in turn to fit a bitrate limit.
+const RESOLUTIONS: [(u32, u32); 5] = [
+    (1920, 1080),
+    (1600, 900),
+    (1280, 720),
+    (960, 540),
+    (640, 360),
+];
+
+/// Lowest frame rate a bitrate limit brings the stream down to.
+const MIN_FPS: u32 = 15;
+
+/// Lowers the requested quality until its estimated bitrate is at most
+/// `max_bitrate` Mbit/s. Senders pick their bitrate themselves, so the only
+/// lever is asking for fewer pixels.
+pub fn within_bitrate(quality: &StreamQuality, max_bitrate: u32) -> StreamQuality {
+    if max_bitrate == 0 {
+        return quality.clone();
+    }
+
+    let (width, height) = quality
+        .resolution
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .unwrap_or((1920, 1080));
+    let fps = match quality.fps {
+        0 => DEFAULT_FPS,
+        fps => fps,
+    };
+    let fits = |width: u32, height: u32, fps: u32| {
+        let bitrate = f64::from(width) * f64::from(height) * f64::from(fps)
+            * bits_per_pixel(quality.codec);
+        bitrate <= f64::from(max_bitrate) * 1_000_000.0
+    };
+
+    if fits(width, height, fps) {
+        return quality.clone();
+    }
+
+    // Drop high frame rates first, then the resolution, then the rest of the frame rate
+    let candidates = std::iter::once((width, height))
+        .chain(RESOLUTIONS.into_iter().filter(|&(w, h)| w * h < width * height));
+    let mut last = (width, height);
+    for fps in [fps.min(DEFAULT_FPS), MIN_FPS] {
+        for (width, height) in candidates.clone() {
+            last = (width, height);
+            if fits(width, height, fps) {
+                return StreamQuality {
+                    resolution: format!("{width}x{height}"),
+                    fps,
+                    codec: quality.codec,
+                };
+            }
+        }
+    }
+
+    StreamQuality {
+        resolution: format!("{}x{}", last.0, last.1),
+        fps: MIN_FPS,
+        codec: quality.codec,
+    }
+}
+
+/// Whether the installed GStreamer has an H.265 decoder.
+pub async fn h265_available() -> bool {
+    for decoder in H265_DECODERS {
+        let status = Command::from(host::command("gst-inspect-1.0"))
+            .args(["--exists", decoder])
+            .status()
+            .await;
+        if status.is_ok_and(|status| status.success()) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Elements of the GStreamer `pipeline` fragment that aren't installed,
+/// skipping caps like "video/x-raw,format=RGB".
+pub async fn missing_elements(pipeline: String) -> Vec<String> {
+    let mut missing = Vec::new();
+    let elements = pipeline
+        .split('!')
+        .filter_map(|part| part.split_whitespace().next())
+        .filter(|element| !element.contains(['/', '=', '(']));
+    for element in elements {
+        let status = Command::from(host::command("gst-inspect-1.0"))
+            .args(["--exists", element])
+            .status()
+            .await;
+        if !status.is_ok_and(|status| status.success()) {
+            missing.push(element.to_string());
+        }
+    }
+    missing
+}
+
+/// The display server of the desktop session, from what it tells its
+/// programs. Sessions that say neither are taken to be X11.
+pub fn detect_display_server() -> DisplayServer {
+    let session = std::env::var("XDG_SESSION_TYPE").unwrap_or_default();
+    if session == "wayland" || std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        DisplayServer::Wayland
+    } else {
+        DisplayServer::X11
+    }
+}
+
+/// The display server set in `config`, or the detected one.
+pub fn display_server(config: &VideoConfig) -> DisplayServer {
+    match config.display_server {
+        DisplayServer::Automatic => detect_display_server(),
+        display_server => display_server,
+    }
+}
+
+/// Builds the GStreamer video sink handed to UXPlay (`-vs`). UXPlay's own
+/// pick often doesn't suit the session, so it is chosen by display server.
+///
+/// Wayland clients cannot place their own windows, so the only way to pick a
+/// monitor is to go fullscreen on it through waylandsink's `fullscreen-output`.
+pub fn video_sink(config: &VideoConfig) -> String {
+    let sink = match (frame_size(config), display_server(config)) {
+        (Some(size), _) => frames::video_sink(size, config.scaling.integer),
+        (None, DisplayServer::Wayland) if !config.output.is_empty() => format!(
+            "waylandsink fullscreen=true fullscreen-output={}",
+            config.output
+        ),
+        (None, DisplayServer::Wayland | DisplayServer::Automatic) => String::from("waylandsink"),
+        (None, DisplayServer::X11) => String::from("xvimagesink"),
+    };
+    match config.picture.videobalance() {
+        Some(balance) => format!("{balance} ! {sink}"),
+        None => sink,
+    }
+}
+
+/// Whether UXPlay has to make its window fullscreen itself, which on X11
+/// is the only way to show it on a picked monitor.
+pub fn uxplay_fullscreen(config: &VideoConfig) -> bool {
+    frame_size(config).is_none()
+        && !config.output.is_empty()
+        && display_server(config) == DisplayServer::X11
+}
+
+/// Size the frames the applet draws itself are scaled to, or `None` if
+/// UXPlay shows the mirror in its own window.
+pub fn frame_size(config: &VideoConfig) -> Option<FrameSize> {
+    if config.extended_display {
+        None
+    } else if config.pip.enabled {
+        let (width, height) = config.pip.size.dimensions();
+        Some(FrameSize { width, height })
+    } else if config.embedded {
+        Some(EMBEDDED_SIZE)
+    } else {
+        None
+    }
+}
+
+/// Connector names of the connected monitors, e.g. "HDMI-A-1".
+pub fn outputs() -> Vec<String> {
+    let mut outputs: Vec<String> = connectors()
+        .into_iter()
+        .map(|(connector, _)| connector)
+        .collect();
+    outputs.sort();
+    outputs
+}
+
+/// Preferred resolution of a monitor, e.g. "2560x1440", or of the first
+/// connected one if `output` is empty.
+pub fn native_resolution(output: &str) -> Option<String> {
+    let mut connectors = connectors();
+    connectors.sort();
+    let (_, path) = connectors
+        .into_iter()
+        .find(|(connector, _)| output.is_empty() || connector == output)?;
+
+    // The preferred mode comes first
+    let modes = std::fs::read_to_string(path.join("modes")).ok()?;
+    modes.lines().next().map(String::from)
+}
+
+/// Connected monitors by connector name, with their sysfs directories.
+fn connectors() -> Vec<(String, std::path::PathBuf)> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            std::fs::read_to_string(entry.path().join("status"))
+                .is_ok_and(|status| status.trim() == "connected")
+        })
+        .filter_map(|entry| {
+            // "card1-HDMI-A-1"
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let (_, connector) = name.split_once('-')?;
+            Some((connector.to_string(), entry.path()))
+        })
+        .collect()
+}
+
+impl PictureConfig {
+    /// Whether the picture is shown as received.
+    pub fn is_neutral(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// GStreamer element applying the adjustments in UXPlay's pipeline.
+    pub fn videobalance(&self) -> Option<String> {
+        if self.is_neutral() {
+            return None;
+        }
+        Some(format!(
+            "videoconvert ! videobalance brightness={:.2} contrast={:.2} saturation={:.2} ! videoconvert",
+            self.brightness as f32 / 100.0,
+            self.contrast as f32 / 100.0,
+            self.saturation as f32 / 100.0
+        ))
+    }
+}
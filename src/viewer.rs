@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Browser viewer for the mirrored screen, built on GStreamer's `webrtcsink`
+//! (gst-plugins-rs), which brings its own signalling and web server.
+
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+
+use crate::config::ViewerConfig;
+
+/// Port of the signalling server started by `webrtcsink`.
+const SIGNALLING_PORT: u16 = 8443;
+
+/// Builds the GStreamer video sink handed to UXPlay (`-vs`) so the decoded
+/// video is re-encoded to WebRTC and served to browsers instead of being
+/// shown in a local window.
+pub fn video_sink(config: &ViewerConfig) -> String {
+    format!(
+        "webrtcsink run-signalling-server=true signalling-server-port={SIGNALLING_PORT} \
+         run-web-server=true web-server-host-addr=http://0.0.0.0:{}/",
+        config.port
+    )
+}
+
+/// Address other machines on the LAN can open the viewer at, given this
+/// machine's [`lan_address`].
+pub fn url(config: &ViewerConfig, address: IpAddr) -> String {
+    format!("http://{}:{}/", address, config.port)
+}
+
+/// The address of the interface used for outgoing traffic. Connecting a UDP
+/// socket sends nothing, it only picks the route, but it is still a few
+/// system calls, so callers keep the result until the network changes.
+pub fn lan_address() -> IpAddr {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9))?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Wake on demand: the applet advertises the AirPlay service itself and only
+//! starts UXPlay once a sender actually tries to connect. Senders retry the
+//! connection, by which time UXPlay is up and has taken over the advertisement.
+
+use std::io;
+use std::net::SocketAddr;
+
+use cosmic::iced::futures::{SinkExt, Stream};
+use nix::sys::socket::{setsockopt, sockopt};
+use tokio::net::{TcpListener, TcpSocket};
+
+use crate::config::{IpVersion, MdnsResponder};
+use crate::mdns;
+use crate::ports::AIRPLAY_PORT;
+
+/// Advertises `name` through `responder` and listens on `ip_version` until a
+/// sender knocks, then yields once.
+pub fn listen(
+    name: String,
+    ip_version: IpVersion,
+    responder: MdnsResponder,
+) -> impl Stream<Item = ()> {
+    cosmic::iced::stream::channel(1, move |mut output| async move {
+        let listener = match bind(ip_version) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to listen on port {}: {}", AIRPLAY_PORT, e);
+                return;
+            }
+        };
+
+        // Under both service types, like UXPlay, so audio-only senders find it too
+        let raop_name = format!("{}@{}", mdns::device_id().replace(':', ""), name);
+        let advertisements = [
+            (name.as_str(), mdns::AIRPLAY, airplay_txt_records()),
+            (raop_name.as_str(), mdns::RAOP, raop_txt_records()),
+        ]
+        .into_iter()
+        .map(|(name, service_type, txt)| {
+            mdns::advertise(responder, ip_version, name, service_type, AIRPLAY_PORT, &txt)
+        })
+        .collect::<Result<Vec<_>, _>>();
+        let advertisements = match advertisements {
+            Ok(advertisements) => advertisements,
+            Err(e) => {
+                eprintln!("Failed to advertise {}: {}", name, e);
+                return;
+            }
+        };
+
+        match listener.accept().await {
+            Ok((_, peer)) => println!("Connection attempt from {}, waking up", peer),
+            Err(e) => eprintln!("Failed to accept connection: {}", e),
+        }
+
+        // Free the port and the name before UXPlay claims them
+        drop(listener);
+        drop(advertisements);
+        let _ = output.send(()).await;
+
+        std::future::pending::<()>().await;
+    })
+}
+
+/// Listens on the standby port with the wanted protocol versions.
+fn bind(ip_version: IpVersion) -> io::Result<TcpListener> {
+    let socket = match ip_version {
+        IpVersion::Ipv4 => TcpSocket::new_v4()?,
+        IpVersion::Both | IpVersion::Ipv6 => {
+            let socket = TcpSocket::new_v6()?;
+            // Set either way, the system default may be IPv6-only
+            setsockopt(&socket, sockopt::Ipv6V6Only, &(ip_version == IpVersion::Ipv6))?;
+            socket
+        }
+    };
+    socket.bind(SocketAddr::new(ip_version.unspecified(), AIRPLAY_PORT))?;
+    socket.listen(16)
+}
+
+/// TXT records matching what UXPlay advertises, so senders list the receiver.
+fn airplay_txt_records() -> Vec<String> {
+    vec![
+        format!("deviceid={}", mdns::device_id()),
+        String::from("features=0x5A7FFEE6"),
+        String::from("flags=0x4"),
+        String::from("model=AppleTV3,2"),
+        String::from("srcvers=220.68"),
+        String::from("vv=2"),
+    ]
+}
+
+/// TXT records matching what UXPlay advertises for audio. UXPlay's public
+/// key (`pk`) is left out, it is only known once UXPlay runs.
+fn raop_txt_records() -> Vec<String> {
+    [
+        "ch=2",
+        "cn=0,1,2,3",
+        "da=true",
+        "et=0,3,5",
+        "vv=2",
+        "ft=0x5A7FFEE6",
+        "am=AppleTV3,2",
+        "md=0,1,2",
+        "rhd=5.6.0.0",
+        "pw=false",
+        "sr=44100",
+        "ss=16",
+        "sv=false",
+        "tp=UDP",
+        "txtvers=1",
+        "sf=0x4",
+        "vs=220.68",
+        "vn=65537",
+    ]
+    .map(String::from)
+    .to_vec()
+}
@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Notices a receiver that stopped responding without exiting, by checking
+//! that it still accepts connections and is still advertised.
+
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+
+use crate::config::{IpVersion, MdnsResponder};
+use crate::mdns;
+use crate::ports::AIRPLAY_PORT;
+
+/// How long each check may take before it counts as failed.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Failed checks in a row after which the receiver is restarted, so a single
+/// slow answer doesn't interrupt anyone.
+pub const MAX_FAILURES: u32 = 2;
+
+/// Checks the receiver advertised as `name`, connecting over `ip_version`
+/// and browsing through `responder`, with the reason if unhealthy.
+pub async fn check(
+    name: String,
+    ip_version: IpVersion,
+    responder: MdnsResponder,
+) -> Result<(), String> {
+    let address = (ip_version.loopback(), AIRPLAY_PORT);
+    match tokio::time::timeout(TIMEOUT, TcpStream::connect(address)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => return Err(format!("port {} not reachable: {}", AIRPLAY_PORT, e)),
+        Err(_) => return Err(format!("port {} did not answer", AIRPLAY_PORT)),
+    }
+
+    match tokio::time::timeout(TIMEOUT, advertised(responder, &name)).await {
+        Ok(Some(false)) => Err(format!("{} is not advertised", name)),
+        Err(_) => Err(String::from("mDNS browsing timed out")),
+        // Without avahi-browse there's nothing to go by
+        Ok(_) => Ok(()),
+    }
+}
+
+/// Whether this machine announces an AirPlay receiver named `name`. `None`
+/// if Avahi can't be asked.
+async fn advertised(responder: MdnsResponder, name: &str) -> Option<bool> {
+    let services = mdns::browse(responder, mdns::AIRPLAY).await.ok()?;
+    Some(
+        services
+            .iter()
+            .any(|service| service.name == name && service.is_local()),
+    )
+}
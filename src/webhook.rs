@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Webhooks for self-hosted automation like n8n or Node-RED: the configured
+//! URL gets a JSON POST when a session starts or ends and when the receiver
+//! fails. Only plain HTTP, which such servers speak on the local network.
+
+use std::time::Duration;
+
+use chrono::Local;
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::error::AirTrayError;
+
+/// Give up on a server that doesn't answer within this long.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most of the answer read, only its status line matters.
+const MAX_RESPONSE: u64 = 8 * 1024;
+
+/// What the webhook is called for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookEvent {
+    /// The first sender connected.
+    SessionStarted,
+    /// The last sender is gone, after its grace period.
+    SessionEnded,
+    /// The receiver failed for the given reason.
+    Error(String),
+}
+
+impl WebhookEvent {
+    /// Value of the payload's `event` field.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::SessionStarted => "session-started",
+            Self::SessionEnded => "session-ended",
+            Self::Error(_) => "error",
+        }
+    }
+}
+
+/// The JSON body for `event`, with the receiver's name and the names of the
+/// connected senders.
+pub fn payload(event: &WebhookEvent, receiver: &str, clients: &[String]) -> String {
+    let error = match event {
+        WebhookEvent::Error(reason) => Some(reason.as_str()),
+        _ => None,
+    };
+    json!({
+        "event": event.name(),
+        "receiver": receiver,
+        "clients": clients,
+        "error": error,
+        "time": Local::now().to_rfc3339(),
+    })
+    .to_string()
+}
+
+/// Where a webhook URL points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target<'a> {
+    /// Host to connect to, without brackets around IPv6 addresses.
+    pub host: &'a str,
+    pub port: u16,
+    /// Host and port as written in the URL, for the Host header.
+    pub authority: &'a str,
+    pub path: &'a str,
+}
+
+/// Splits an `http://` URL, or says what is wrong with it.
+pub fn parse_url(url: &str) -> Result<Target<'_>, String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| String::from("only http:// URLs are supported"))?;
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let path = if path.is_empty() { "/" } else { path };
+    // "host", "host:port", "[::1]" or "[::1]:port"
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.ends_with(']') => {
+            (host, port.parse().map_err(|_| format!("invalid port {port}"))?)
+        }
+        _ => (authority, 80),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err(String::from("no host"));
+    }
+    Ok(Target {
+        host,
+        port,
+        authority,
+        path,
+    })
+}
+
+/// The HTTP request POSTing `body` to `target`.
+pub fn request(target: &Target, body: &str) -> String {
+    format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        target.path,
+        target.authority,
+        body.len()
+    )
+}
+
+/// POSTs `body` to `url`, an `http://` URL, expecting a 2xx answer.
+pub async fn call(url: String, body: String) -> Result<(), AirTrayError> {
+    tokio::time::timeout(TIMEOUT, post(&url, &body))
+        .await
+        .map_err(|_| AirTrayError::WebhookError(format!("{url}: timed out")))?
+}
+
+async fn post(url: &str, body: &str) -> Result<(), AirTrayError> {
+    let fail = |reason: String| AirTrayError::WebhookError(format!("{url}: {reason}"));
+    let target = parse_url(url).map_err(fail)?;
+
+    let io = |e: std::io::Error| fail(e.to_string());
+    let mut stream = TcpStream::connect((target.host, target.port)).await.map_err(io)?;
+    stream.write_all(request(&target, body).as_bytes()).await.map_err(io)?;
+
+    let mut response = Vec::new();
+    stream.take(MAX_RESPONSE).read_to_end(&mut response).await.map_err(io)?;
+    let response = String::from_utf8_lossy(&response);
+
+    // "HTTP/1.1 200 OK"
+    let status = response.split_whitespace().nth(1).unwrap_or_default();
+    if status.starts_with('2') {
+        Ok(())
+    } else {
+        Err(fail(format!(
+            "server answered {}",
+            response.lines().next().unwrap_or_default()
+        )))
+    }
+}